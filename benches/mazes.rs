@@ -0,0 +1,150 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use image::{ImageFormat, Rgb, RgbImage};
+use maze_solving::graph::Graph;
+use maze_solving::pathfinding::{
+    ALGORITHM_NAMES, HeuristicTable, PathfindingAlgorithm, Solver, alt_astar, astar_cached,
+    astar_weighted,
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::hint::black_box;
+use std::io::Cursor;
+
+/// Mazes of `cells`x`cells` cells, so `2 * cells + 1` pixels on a side
+const SIZES: &[u32] = &[25, 100, 400];
+
+/// Searches that blow up on larger mazes only run on sizes up to this
+const SLOW_SOLVER_MAX_SIZE: u32 = 100;
+
+/// Perfect maze carved by a seeded depth-first backtracker, encoded as PNG
+/// with openings in the top left and bottom right corner.
+fn generate_maze(cells: u32, seed: u64) -> Vec<u8> {
+    let side = 2 * cells + 1;
+    let mut img = RgbImage::from_pixel(side, side, Rgb([0, 0, 0]));
+    let open = Rgb([255, 255, 255]);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut visited = vec![false; (cells * cells) as usize];
+    let mut stack: Vec<(u32, u32)> = vec![(0, 0)];
+    visited[0] = true;
+    img.put_pixel(1, 1, open);
+    while let Some(&(x, y)) = stack.last() {
+        let mut next: Vec<(u32, u32)> = [(0, -1), (0, 1), (-1, 0), (1, 0)]
+            .iter()
+            .filter_map(|&(dx, dy)| Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?)))
+            .filter(|&(nx, ny)| nx < cells && ny < cells && !visited[(ny * cells + nx) as usize])
+            .collect();
+        next.shuffle(&mut rng);
+        let Some(&(nx, ny)) = next.first() else {
+            stack.pop();
+            continue;
+        };
+
+        visited[(ny * cells + nx) as usize] = true;
+        img.put_pixel(x + nx + 1, y + ny + 1, open);
+        img.put_pixel(2 * nx + 1, 2 * ny + 1, open);
+        stack.push((nx, ny));
+    }
+    img.put_pixel(1, 0, open);
+    img.put_pixel(side - 2, side - 1, open);
+
+    let mut bytes = Vec::new();
+    img.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .expect("encoding to memory can't fail");
+    bytes
+}
+
+fn construction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("construction");
+    for &cells in SIZES {
+        let bytes = generate_maze(cells, 1);
+        group.bench_with_input(BenchmarkId::new("from_bytes", cells), &bytes, |b, bytes| {
+            b.iter(|| Graph::from_bytes(black_box(bytes)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn solvers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve");
+    for &cells in SIZES {
+        let mut graph = Graph::from_bytes(&generate_maze(cells, 1)).unwrap();
+        graph.compact();
+
+        for &(name, algo) in ALGORITHM_NAMES {
+            let slow = matches!(
+                algo,
+                PathfindingAlgorithm::IterativeDeepening | PathfindingAlgorithm::IdaStar
+            );
+            if slow && cells > SLOW_SOLVER_MAX_SIZE {
+                continue;
+            }
+
+            let mut solver = Solver::new(algo);
+            group.bench_with_input(BenchmarkId::new(name, cells), &graph, |b, graph| {
+                b.iter(|| solver.solve(black_box(graph)).unwrap())
+            });
+        }
+
+        #[cfg(feature = "parallel")]
+        {
+            use maze_solving::pathfinding::{parallel_bfs, parallel_dijkstra, shortest_paths_from};
+
+            // A wrong parallel result would make its timing meaningless
+            let expected = shortest_paths_from(&graph, graph.start).0[graph.end];
+            let cost = parallel_dijkstra(&graph).unwrap().cost;
+            assert!(
+                (cost - expected).abs() <= 1e-4 * expected.max(1.0),
+                "parallel_dijkstra cost {cost}, dijkstra {expected}"
+            );
+
+            group.bench_with_input(
+                BenchmarkId::new("parallel_dijkstra", cells),
+                &graph,
+                |b, graph| b.iter(|| parallel_dijkstra(black_box(graph)).unwrap()),
+            );
+            group.bench_with_input(
+                BenchmarkId::new("parallel_bfs", cells),
+                &graph,
+                |b, graph| b.iter(|| parallel_bfs(black_box(graph)).unwrap()),
+            );
+        }
+    }
+    group.finish();
+}
+
+/// A* with the heuristic computed on every push against the same search
+/// looking it up in a `HeuristicTable` built beforehand, as repeated queries
+/// to one goal would.
+fn heuristics(c: &mut Criterion) {
+    let mut group = c.benchmark_group("heuristic");
+    for &cells in SIZES {
+        let mut graph = Graph::from_bytes(&generate_maze(cells, 1)).unwrap();
+        graph.compact();
+        let landmarks = graph.precompute_landmarks(4);
+        let table = HeuristicTable::new(&graph, graph.end);
+        let alt_table = HeuristicTable::from_landmarks(&graph, &landmarks, graph.end);
+
+        group.bench_with_input(BenchmarkId::new("astar", cells), &graph, |b, graph| {
+            b.iter(|| astar_weighted(black_box(graph), 1.0).unwrap())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("astar_cached", cells),
+            &graph,
+            |b, graph| b.iter(|| astar_cached(black_box(graph), &table, graph.start).unwrap()),
+        );
+        group.bench_with_input(BenchmarkId::new("alt_astar", cells), &graph, |b, graph| {
+            b.iter(|| alt_astar(black_box(graph), &landmarks, graph.start, graph.end).unwrap())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("alt_astar_cached", cells),
+            &graph,
+            |b, graph| b.iter(|| astar_cached(black_box(graph), &alt_table, graph.start).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, construction, solvers, heuristics);
+criterion_main!(benches);
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use maze_solving::graph::Graph;
+use maze_solving::pathfinding::{PathfindingAlgorithm, solve_graph};
+
+// Any input has to come back as a graph or an error, never a panic, and a
+// graph that did load has to be solvable without one too
+fuzz_target!(|data: &[u8]| {
+    if let Ok(graph) = Graph::from_bytes(data) {
+        let _ = solve_graph(&graph, &PathfindingAlgorithm::Dijkstra);
+    }
+});
@@ -1,28 +1,109 @@
-use image::{ImageError, ImageReader, RgbImage};
-use std::collections::HashMap;
+#[cfg(feature = "image")]
+use crate::font;
+#[cfg(feature = "image")]
+use crate::pathfinding::SearchEvent;
+use crate::pathfinding::{SolveError, shortest_paths_from};
+#[cfg(feature = "image")]
+use image::error::{ParameterError, ParameterErrorKind};
+#[cfg(feature = "image")]
+use image::{
+    DynamicImage, ImageDecoder, ImageError, ImageFormat, ImageReader, Limits, Rgb, RgbImage, Rgba,
+    RgbaImage,
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
-use std::path::Path;
+#[cfg(feature = "image")]
+use std::io::{BufRead, Cursor, Seek};
+#[cfg(feature = "image")]
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Coord {
     x: u32,
     y: u32,
 }
 
 impl Adjacent for Coord {
-    type Neighbors = std::iter::Flatten<std::array::IntoIter<Option<Self>, 4>>;
+    type Neighbors = CoordNeighbors;
     fn potential_neighbors(&self) -> Self::Neighbors {
-        let x = self.x;
-        let y = self.y;
+        let Coord { x, y } = *self;
+        let mut neighbors = CoordNeighbors {
+            buf: [*self; 4],
+            len: 0,
+            next: 0,
+        };
 
-        let arr = [
-            y.checked_sub(1).map(|new_y| Coord { x, y: new_y }),
-            y.checked_add(1).map(|new_y| Coord { x, y: new_y }),
-            x.checked_sub(1).map(|new_x| Coord { x: new_x, y }),
-            x.checked_add(1).map(|new_x| Coord { x: new_x, y }),
-        ];
+        if let Some(y) = y.checked_sub(1) {
+            neighbors.push(Coord { x, y });
+        }
+        if let Some(y) = y.checked_add(1) {
+            neighbors.push(Coord { x, y });
+        }
+        if let Some(x) = x.checked_sub(1) {
+            neighbors.push(Coord { x, y });
+        }
+        if let Some(x) = x.checked_add(1) {
+            neighbors.push(Coord { x, y });
+        }
+
+        neighbors
+    }
+}
+
+/// The up to four orthogonal neighbors of a `Coord`, stored inline.
+pub struct CoordNeighbors {
+    buf: [Coord; 4],
+    len: u8,
+    next: u8,
+}
+
+impl CoordNeighbors {
+    fn push(&mut self, coord: Coord) {
+        self.buf[self.len as usize] = coord;
+        self.len += 1;
+    }
+}
+
+impl Iterator for CoordNeighbors {
+    type Item = Coord;
+
+    fn next(&mut self) -> Option<Coord> {
+        if self.next == self.len {
+            return None;
+        }
+        self.next += 1;
+        Some(self.buf[self.next as usize - 1])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.len - self.next) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl Coord {
+    pub fn new(x: u32, y: u32) -> Self {
+        Self { x, y }
+    }
 
-        return arr.into_iter().flatten();
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+
+    pub fn euclidean_distance(&self, other: &Coord) -> f32 {
+        let dx = self.x.abs_diff(other.x) as f32;
+        let dy = self.y.abs_diff(other.y) as f32;
+        (dx * dx + dy * dy).sqrt()
     }
 }
 
@@ -32,9 +113,237 @@ impl fmt::Display for Coord {
     }
 }
 
+/// A single step between two pixels, see `Graph::path_to_moves`. Up is
+/// towards the top of the image, where `y` is 0. Displayed as `U`, `D`, `L`
+/// and `R`, diagonals combine two of them like `UL`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Move {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Move {
+    /// The move heading from `from` towards `to`, by the sign of each axis.
+    /// `None` if the two are the same pixel.
+    pub fn between(from: &Coord, to: &Coord) -> Option<Move> {
+        let move_ = match (to.x.cmp(&from.x), to.y.cmp(&from.y)) {
+            (Ordering::Equal, Ordering::Equal) => return None,
+            (Ordering::Equal, Ordering::Less) => Move::Up,
+            (Ordering::Equal, Ordering::Greater) => Move::Down,
+            (Ordering::Less, Ordering::Equal) => Move::Left,
+            (Ordering::Greater, Ordering::Equal) => Move::Right,
+            (Ordering::Less, Ordering::Less) => Move::UpLeft,
+            (Ordering::Greater, Ordering::Less) => Move::UpRight,
+            (Ordering::Less, Ordering::Greater) => Move::DownLeft,
+            (Ordering::Greater, Ordering::Greater) => Move::DownRight,
+        };
+        Some(move_)
+    }
+
+    /// The heading of the move in degrees, clockwise from `Up`.
+    pub fn angle(&self) -> u16 {
+        match self {
+            Move::Up => 0,
+            Move::UpRight => 45,
+            Move::Right => 90,
+            Move::DownRight => 135,
+            Move::Down => 180,
+            Move::DownLeft => 225,
+            Move::Left => 270,
+            Move::UpLeft => 315,
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letters = match self {
+            Move::Up => "U",
+            Move::Down => "D",
+            Move::Left => "L",
+            Move::Right => "R",
+            Move::UpLeft => "UL",
+            Move::UpRight => "UR",
+            Move::DownLeft => "DL",
+            Move::DownRight => "DR",
+        };
+        f.write_str(letters)
+    }
+}
+
+/// A step of a turtle tracing a path, see `turtle_commands`. Turns are in
+/// degrees, multiples of 45. Displayed as `forward 3`, `left 90` and so on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TurtleCommand {
+    /// Move this many pixels ahead, diagonally if the turtle faces a diagonal
+    Forward(u32),
+    Left(u16),
+    Right(u16),
+}
+
+impl fmt::Display for TurtleCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TurtleCommand::Forward(steps) => write!(f, "forward {steps}"),
+            TurtleCommand::Left(degrees) => write!(f, "left {degrees}"),
+            TurtleCommand::Right(degrees) => write!(f, "right {degrees}"),
+        }
+    }
+}
+
+/// `moves` as turtle commands for a turtle that starts out facing `heading`:
+/// runs of the same move become one `Forward` and every change of direction a
+/// turn, the shorter way round. Turning back on itself, as a search that
+/// backtracks out of a dead end might, is a `Right(180)`.
+pub fn turtle_commands(moves: &[Move], heading: Move) -> Vec<TurtleCommand> {
+    let mut commands = Vec::new();
+    let mut heading = heading;
+    for run in moves.chunk_by(|a, b| a == b) {
+        let turn = (run[0].angle() + 360 - heading.angle()) % 360;
+        match turn {
+            0 => {}
+            1..=180 => commands.push(TurtleCommand::Right(turn)),
+            _ => commands.push(TurtleCommand::Left(360 - turn)),
+        }
+        commands.push(TurtleCommand::Forward(run.len() as u32));
+        heading = run[0];
+    }
+    commands
+}
+
+/// A cell in a maze made of stacked 2D layers, `z` picks the layer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Coord3D {
+    x: u32,
+    y: u32,
+    z: u32,
+}
+
+impl Adjacent for Coord3D {
+    type Neighbors = std::iter::Flatten<std::array::IntoIter<Option<Coord3D>, 6>>;
+    fn potential_neighbors(&self) -> Self::Neighbors {
+        let Coord3D { x, y, z } = *self;
+        [
+            y.checked_sub(1).map(|y| Coord3D { x, y, z }),
+            y.checked_add(1).map(|y| Coord3D { x, y, z }),
+            x.checked_sub(1).map(|x| Coord3D { x, y, z }),
+            x.checked_add(1).map(|x| Coord3D { x, y, z }),
+            z.checked_sub(1).map(|z| Coord3D { x, y, z }),
+            z.checked_add(1).map(|z| Coord3D { x, y, z }),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+impl Distance for Coord3D {
+    fn distance(&self, other: &Self, heuristic: Heuristic) -> f32 {
+        heuristic.estimate(&[
+            self.x.abs_diff(other.x),
+            self.y.abs_diff(other.y),
+            self.z.abs_diff(other.z),
+        ])
+    }
+}
+
+impl fmt::Display for Coord3D {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+/// A cell of a hexagonal maze in offset coordinates: pointy-top hexagons in
+/// rows, with every odd row shifted half a cell to the right.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HexCoord {
+    col: u32,
+    row: u32,
+}
+
+impl HexCoord {
+    pub fn new(col: u32, row: u32) -> Self {
+        Self { col, row }
+    }
+
+    pub fn col(&self) -> u32 {
+        self.col
+    }
+
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+
+    /// Axial coordinates, where the six neighbors are at fixed offsets.
+    fn axial(&self) -> (i64, i64) {
+        let row = i64::from(self.row);
+        (i64::from(self.col) - (row - (row & 1)) / 2, row)
+    }
+}
+
+impl Adjacent for HexCoord {
+    type Neighbors = std::iter::Flatten<std::array::IntoIter<Option<HexCoord>, 6>>;
+    fn potential_neighbors(&self) -> Self::Neighbors {
+        let HexCoord { col, row } = *self;
+        // The rows above and below overlap this cell with the column to the
+        // left on even rows, and with the one to the right on odd rows
+        let (left, right) = if row.is_multiple_of(2) {
+            (col.checked_sub(1), Some(col))
+        } else {
+            (Some(col), col.checked_add(1))
+        };
+        let up = row.checked_sub(1);
+        let down = row.checked_add(1);
+        let at = |col: Option<u32>, row: Option<u32>| {
+            Some(HexCoord {
+                col: col?,
+                row: row?,
+            })
+        };
+        [
+            at(col.checked_sub(1), Some(row)),
+            at(col.checked_add(1), Some(row)),
+            at(left, up),
+            at(right, up),
+            at(left, down),
+            at(right, down),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+impl Distance for HexCoord {
+    /// Measured along the three axes of the hex grid, with
+    /// `Heuristic::Chebyshev` this is the exact number of steps.
+    fn distance(&self, other: &Self, heuristic: Heuristic) -> f32 {
+        let ((q1, r1), (q2, r2)) = (self.axial(), other.axial());
+        let (dq, dr) = (q1 - q2, r1 - r2);
+        heuristic.estimate(&[
+            dq.unsigned_abs() as u32,
+            dr.unsigned_abs() as u32,
+            (dq + dr).unsigned_abs() as u32,
+        ])
+    }
+}
+
+impl fmt::Display for HexCoord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.col, self.row)
+    }
+}
+
 impl fmt::Display for Vertex<Coord> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "()")
+        write!(f, "{}, {:?}", self.pos, self.neighbors)
     }
 }
 
@@ -43,94 +352,1899 @@ pub trait Adjacent: Sized {
     fn potential_neighbors(&self) -> Self::Neighbors;
 }
 
-#[derive(Clone)]
+/// Estimate of the travel cost between two positions, used as a search heuristic.
+pub trait Distance {
+    fn distance(&self, other: &Self, heuristic: Heuristic) -> f32;
+}
+
+impl Distance for Coord {
+    fn distance(&self, other: &Self, heuristic: Heuristic) -> f32 {
+        heuristic.estimate(&[self.x.abs_diff(other.x), self.y.abs_diff(other.y)])
+    }
+}
+
+/// The metric a search uses to estimate the remaining cost. A* and friends
+/// only return optimal paths if it never overestimates, which depends on the
+/// moves the graph allows: with diagonal steps Manhattan distance is too
+/// large, Octile is exact on an open grid and the others are smaller still.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Heuristic {
+    /// Sum of the per-axis distances, exact for orthogonal moves only
+    #[default]
+    Manhattan,
+    /// Straight line distance
+    Euclidean,
+    /// Diagonal steps of cost √2 as far as possible, then straight ones
+    Octile,
+    /// Largest per-axis distance
+    Chebyshev,
+}
+
+impl Heuristic {
+    fn estimate(self, deltas: &[u32]) -> f32 {
+        match self {
+            Heuristic::Manhattan => deltas.iter().sum::<u32>() as f32,
+            Heuristic::Euclidean => deltas
+                .iter()
+                .map(|&d| (d as f32) * (d as f32))
+                .sum::<f32>()
+                .sqrt(),
+            Heuristic::Octile => {
+                // A step along k axes at once costs √k. Sorted from largest
+                // to smallest, the k-th axis only adds √k - √(k-1) per step
+                let mut deltas = deltas.to_vec();
+                deltas.sort_unstable_by(|a, b| b.cmp(a));
+                deltas
+                    .iter()
+                    .enumerate()
+                    .map(|(k, &d)| d as f32 * (((k + 1) as f32).sqrt() - (k as f32).sqrt()))
+                    .sum()
+            }
+            Heuristic::Chebyshev => deltas.iter().copied().max().unwrap_or(0) as f32,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct Vertex<T> {
     pos: T,
     neighbors: Vec<(usize, f32)>,
 }
 
-impl<T> Vertex<T> {
-    pub fn get_neighbors(&self) -> &Vec<(usize, f32)> {
-        &self.neighbors
+impl<T> Vertex<T> {
+    pub fn get_pos(&self) -> &T {
+        &self.pos
+    }
+
+    pub fn get_neighbors(&self) -> &Vec<(usize, f32)> {
+        &self.neighbors
+    }
+}
+
+#[derive(Clone)]
+pub struct Graph<T> {
+    pub start: usize,
+    pub end: usize,
+    /// What the A* style solvers estimate the remaining cost with. Graphs
+    /// with diagonal steps default to `Octile`, or `Chebyshev` when those
+    /// cost 1, all others to `Manhattan`.
+    pub heuristic: Heuristic,
+    vertices: Vec<Vertex<T>>,
+    grid: Option<Grid>,
+    /// How the image edge was treated while loading, which decides what
+    /// `boundary_openings` counts as an opening.
+    border: Option<BorderMode>,
+    /// Positions walked along each directed edge, from the source vertex to the
+    /// destination inclusive. Only known for graphs built from an image.
+    edge_pixels: HashMap<(usize, usize), Vec<T>>,
+    /// Built by the first `nearest` call, dropped whenever a vertex may have
+    /// gained or lost all of its edges.
+    spatial_index: OnceLock<SpatialIndex>,
+    /// The decoded image the graph was loaded from, if `LoadOptions::keep_source`
+    /// asked for it. Shared between clones.
+    #[cfg(feature = "image")]
+    source: Option<Arc<RgbImage>>,
+}
+
+/// Live vertices bucketed by position into square cells, so nearest vertex
+/// queries only look at the cells around the query instead of every vertex.
+#[derive(Clone)]
+struct SpatialIndex {
+    columns: usize,
+    rows: usize,
+    /// Vertex indices per cell, row-major, each list in ascending order
+    cells: Vec<Vec<usize>>,
+}
+
+impl SpatialIndex {
+    /// Side of a cell in pixels.
+    const CELL_SIZE: u32 = 16;
+
+    fn new(vertices: &[Vertex<Coord>]) -> Self {
+        let live = || {
+            vertices
+                .iter()
+                .enumerate()
+                .filter(|(_, vertex)| !vertex.neighbors.is_empty())
+        };
+        let (max_x, max_y) = live().fold((0, 0), |(x, y), (_, vertex)| {
+            (x.max(vertex.pos.x), y.max(vertex.pos.y))
+        });
+        let columns = (max_x / Self::CELL_SIZE) as usize + 1;
+        let rows = (max_y / Self::CELL_SIZE) as usize + 1;
+        let mut cells = vec![Vec::new(); columns * rows];
+        for (idx, vertex) in live() {
+            let column = (vertex.pos.x / Self::CELL_SIZE) as usize;
+            let row = (vertex.pos.y / Self::CELL_SIZE) as usize;
+            cells[row * columns + column].push(idx);
+        }
+        Self {
+            columns,
+            rows,
+            cells,
+        }
+    }
+
+    fn nearest(&self, vertices: &[Vertex<Coord>], coord: &Coord) -> Option<usize> {
+        let (columns, rows) = (self.columns as i64, self.rows as i64);
+        // Queries beyond the indexed area start from the closest cell inside it
+        let column = i64::from(coord.x / Self::CELL_SIZE).min(columns - 1);
+        let row = i64::from(coord.y / Self::CELL_SIZE).min(rows - 1);
+        // Rings of cells around the starting cell, every pixel in ring `r` is
+        // more than `(r - 1) * CELL_SIZE` away, so once the best match is
+        // closer than that no further ring can beat or tie it
+        let last_ring = column
+            .max(columns - 1 - column)
+            .max(row.max(rows - 1 - row));
+        let mut best: Option<(f32, usize)> = None;
+        for radius in 0..=last_ring {
+            let reach = ((radius - 1) * i64::from(Self::CELL_SIZE)) as f32;
+            if best.is_some_and(|(dist, _)| dist <= reach) {
+                break;
+            }
+            let ring = (-radius..=radius).flat_map(|offset| {
+                [
+                    (column + offset, row - radius),
+                    (column + offset, row + radius),
+                    (column - radius, row + offset),
+                    (column + radius, row + offset),
+                ]
+            });
+            for (c, r) in ring {
+                if !(0..columns).contains(&c) || !(0..rows).contains(&r) {
+                    continue;
+                }
+                for &idx in &self.cells[(r * columns + c) as usize] {
+                    let dist = coord.euclidean_distance(&vertices[idx].pos);
+                    if best.is_none_or(|(best_dist, best_idx)| (dist, idx) < (best_dist, best_idx))
+                    {
+                        best = Some((dist, idx));
+                    }
+                }
+            }
+        }
+        best.map(|(_, idx)| idx)
+    }
+
+    /// The vertex with edges sitting exactly at `coord`, if any.
+    fn at(&self, vertices: &[Vertex<Coord>], coord: &Coord) -> Option<usize> {
+        let column = (coord.x / Self::CELL_SIZE) as usize;
+        let row = (coord.y / Self::CELL_SIZE) as usize;
+        if column >= self.columns || row >= self.rows {
+            return None;
+        }
+        self.cells[row * self.columns + column]
+            .iter()
+            .copied()
+            .find(|&idx| vertices[idx].pos == *coord)
+    }
+}
+
+/// Per-pixel open/wall mask of the source image. Reduction throws away the
+/// pixels between junctions, this keeps them around for algorithms that need
+/// to reason about walls.
+#[derive(Clone)]
+pub struct Grid {
+    width: u32,
+    height: u32,
+    open: Vec<bool>,
+}
+
+impl Grid {
+    #[cfg(feature = "image")]
+    fn from_image(img: &RgbaImage, walls: WallDetect) -> Self {
+        Self {
+            width: img.width(),
+            height: img.height(),
+            open: img.pixels().map(|pixel| walls.is_open(pixel)).collect(),
+        }
+    }
+
+    /// Sets every pixel to whatever the majority of the `(2r+1)`x`(2r+1)` window
+    /// around it is, with pixels outside the image counting as walls. This drops
+    /// isolated specks and bridges small gaps in noisy scans, but it also erases
+    /// passages narrower than the window, so it is only useful on mazes with
+    /// wide passages.
+    pub fn majority_filter(&self, radius: u32) -> Grid {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let radius = radius as usize;
+
+        // Summed-area table of open pixels, padded with a zero row and column
+        let mut sums = vec![0u32; (width + 1) * (height + 1)];
+        for y in 0..height {
+            for x in 0..width {
+                let open = self.open[y * width + x] as u32;
+                sums[(y + 1) * (width + 1) + x + 1] = open + sums[y * (width + 1) + x + 1]
+                    - sums[y * (width + 1) + x]
+                    + sums[(y + 1) * (width + 1) + x];
+            }
+        }
+
+        let window = ((2 * radius + 1) * (2 * radius + 1)) as u32;
+        let mut open = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let (y0, y1) = (y.saturating_sub(radius), (y + radius + 1).min(height));
+            for x in 0..width {
+                let (x0, x1) = (x.saturating_sub(radius), (x + radius + 1).min(width));
+                let count = sums[y1 * (width + 1) + x1] + sums[y0 * (width + 1) + x0]
+                    - sums[y0 * (width + 1) + x1]
+                    - sums[y1 * (width + 1) + x0];
+                open.push(2 * count > window);
+            }
+        }
+
+        Grid {
+            width: self.width,
+            height: self.height,
+            open,
+        }
+    }
+
+    /// Thins every passage down to a one pixel wide centerline (Zhang-Suen),
+    /// then reopens a pixel wherever the centerline only connects diagonally
+    /// since the graph only links orthogonal neighbors. Pixels outside the
+    /// image repeat the nearest border pixel, so a passage leaving the image
+    /// keeps its centerline all the way to the border.
+    pub fn skeletonize(&self) -> Grid {
+        let (width, height) = (self.width as i64, self.height as i64);
+        let mut open = self.open.clone();
+        let get = |open: &[bool], x: i64, y: i64| {
+            let x = x.clamp(0, width - 1);
+            let y = y.clamp(0, height - 1);
+            open[(y * width + x) as usize]
+        };
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for step in 0..2 {
+                let mut remove = Vec::new();
+                for y in 0..height {
+                    for x in 0..width {
+                        if !open[(y * width + x) as usize] {
+                            continue;
+                        }
+                        // P2 to P9, clockwise starting above the pixel
+                        let p = [
+                            get(&open, x, y - 1),
+                            get(&open, x + 1, y - 1),
+                            get(&open, x + 1, y),
+                            get(&open, x + 1, y + 1),
+                            get(&open, x, y + 1),
+                            get(&open, x - 1, y + 1),
+                            get(&open, x - 1, y),
+                            get(&open, x - 1, y - 1),
+                        ];
+                        let neighbors = p.iter().filter(|open| **open).count();
+                        let transitions = (0..8).filter(|&i| !p[i] && p[(i + 1) % 8]).count();
+                        let (p2, p4, p6, p8) = (p[0], p[2], p[4], p[6]);
+                        let erodes = if step == 0 {
+                            !(p4 && p6 && (p2 || p8))
+                        } else {
+                            !(p2 && p8 && (p4 || p6))
+                        };
+                        if (2..=6).contains(&neighbors) && transitions == 1 && erodes {
+                            remove.push((y * width + x) as usize);
+                        }
+                    }
+                }
+                changed |= !remove.is_empty();
+                for idx in remove {
+                    open[idx] = false;
+                }
+            }
+        }
+
+        for y in 0..height - 1 {
+            for x in 0..width {
+                if !open[(y * width + x) as usize] {
+                    continue;
+                }
+                for dx in [-1, 1] {
+                    let (nx, ny) = (x + dx, y + 1);
+                    if nx < 0 || nx >= width || !open[(ny * width + nx) as usize] {
+                        continue;
+                    }
+                    let side = (y * width + nx) as usize;
+                    let below = (ny * width + x) as usize;
+                    if !open[side] && !open[below] {
+                        open[side] = true;
+                    }
+                }
+            }
+        }
+
+        Grid {
+            width: self.width,
+            height: self.height,
+            open,
+        }
+    }
+
+    /// A grid from rows of pixels, `rows[y][x]` is `true` for an open pixel.
+    /// Returns `None` if the rows have different lengths.
+    pub fn from_rows(rows: &[Vec<bool>]) -> Option<Grid> {
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Grid {
+            width: width as u32,
+            height: rows.len() as u32,
+            open: rows.concat(),
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Out of bounds positions count as walls.
+    pub fn is_open(&self, x: u32, y: u32) -> bool {
+        x < self.width && y < self.height && self.open[(y * self.width + x) as usize]
+    }
+
+    /// Open pixels reachable in one step from `pos` with the step's cost. With
+    /// `diagonals` set the four diagonal pixels are included too, as far as
+    /// the policy allows.
+    fn open_neighbors(&self, pos: &Coord, diagonals: Option<CornerPolicy>) -> Vec<(Coord, f32)> {
+        let mut neighbors: Vec<(Coord, f32)> = pos
+            .potential_neighbors()
+            .filter(|n| self.is_open(n.x, n.y))
+            .map(|n| (n, 1.0))
+            .collect();
+
+        let Some(policy) = diagonals else {
+            return neighbors;
+        };
+        for (dx, dy) in [(-1, -1), (1, -1), (-1, 1), (1, 1)] {
+            let (Some(x), Some(y)) = (pos.x.checked_add_signed(dx), pos.y.checked_add_signed(dy))
+            else {
+                continue;
+            };
+            if !self.is_open(x, y) {
+                continue;
+            }
+
+            let walls = [(x, pos.y), (pos.x, y)]
+                .iter()
+                .filter(|(x, y)| !self.is_open(*x, *y))
+                .count();
+            let allowed = match policy {
+                CornerPolicy::Always => true,
+                CornerPolicy::NoCornerCutting => walls == 0,
+                CornerPolicy::NoSqueezing => walls < 2,
+            };
+            if allowed {
+                neighbors.push((Coord { x, y }, std::f32::consts::SQRT_2));
+            }
+        }
+        neighbors
+    }
+
+    /// Walks the Bresenham line between `a` and `b` and checks that every pixel
+    /// on it is open. Diagonal steps also require both orthogonal pixels to be
+    /// open so the line never squeezes between two walls touching at a corner.
+    pub fn line_of_sight(&self, a: &Coord, b: &Coord) -> bool {
+        let mut x0 = a.x as i64;
+        let mut y0 = a.y as i64;
+        let x1 = b.x as i64;
+        let y1 = b.y as i64;
+
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        let open = |x: i64, y: i64| self.is_open(x as u32, y as u32);
+
+        loop {
+            if !open(x0, y0) {
+                return false;
+            }
+            if x0 == x1 && y0 == y1 {
+                return true;
+            }
+            let e2 = 2 * err;
+            let step_x = e2 >= dy;
+            let step_y = e2 <= dx;
+            if step_x && step_y && (!open(x0 + sx, y0) || !open(x0, y0 + sy)) {
+                return false;
+            }
+            if step_x {
+                err += dy;
+                x0 += sx;
+            }
+            if step_y {
+                err += dx;
+                y0 += sy;
+            }
+        }
+    }
+}
+
+impl fmt::Display for Graph<Coord> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, vertex) in self.vertices.iter().enumerate() {
+            writeln!(f, "[{i:2}]: {vertex}, ")?;
+        }
+        Ok(())
+    }
+}
+
+// Written by hand so the per-pixel grid and edge pixels don't drown out the vertices
+impl<T: fmt::Debug> fmt::Debug for Graph<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Graph")
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .field("heuristic", &self.heuristic)
+            .field("vertices", &self.vertices)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Graph<T> {
+    pub fn get_vertices(&self) -> &Vec<Vertex<T>> {
+        &self.vertices
+    }
+
+    /// The pixel grid the graph was built from, if it came from an image.
+    pub fn get_grid(&self) -> Option<&Grid> {
+        self.grid.as_ref()
+    }
+
+    /// Every two-way edge once as `(a, b, weight)` with `a < b` and every
+    /// one-way edge as `(from, to, weight)`, ordered by `a` and then by the
+    /// order `a` stores its neighbors.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, f32)> + '_ {
+        self.vertices
+            .iter()
+            .enumerate()
+            .flat_map(move |(a, vertex)| {
+                vertex
+                    .neighbors
+                    .iter()
+                    .filter(move |(b, _)| a < *b || (a > *b && !self.has_edge(*b, a)))
+                    .map(move |&(b, weight)| (a, b, weight))
+            })
+    }
+
+    /// Whether `to` can be reached from `from` in one step.
+    pub fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.vertices[from]
+            .neighbors
+            .iter()
+            .any(|(idx, _)| *idx == to)
+    }
+
+    /// Whether any edge can only be walked in one direction, see `set_one_way`.
+    pub fn has_one_way_edges(&self) -> bool {
+        self.vertices
+            .iter()
+            .enumerate()
+            .any(|(a, vertex)| vertex.neighbors.iter().any(|&(b, _)| !self.has_edge(b, a)))
+    }
+
+    /// Whether some edge costs something else than the way back, as happens
+    /// with a `CostMap`.
+    pub fn has_asymmetric_weights(&self) -> bool {
+        self.vertices.iter().enumerate().any(|(a, vertex)| {
+            vertex.neighbors.iter().any(|&(b, weight)| {
+                self.vertices[b]
+                    .neighbors
+                    .iter()
+                    .any(|&(back, back_weight)| back == a && back_weight != weight)
+            })
+        })
+    }
+
+    /// Sets the weight of every edge between `a` and `b`, in whichever
+    /// directions they exist, e.g. to make a corridor less attractive. Weights
+    /// have to be finite like for the solvers, negative ones are only handled
+    /// by Bellman-Ford. A `DStarLite` copies the weights when it's created, use
+    /// its `update_edge` for changes while it's planning.
+    pub fn set_edge_weight(&mut self, a: usize, b: usize, weight: f32) -> Result<(), SolveError> {
+        if !weight.is_finite() {
+            return Err(SolveError::InvalidWeight {
+                from: a,
+                to: b,
+                weight,
+            });
+        }
+
+        let mut found = false;
+        for (from, to) in [(a, b), (b, a)] {
+            let Some(vertex) = self.vertices.get_mut(from) else {
+                continue;
+            };
+            for (_, w) in vertex.neighbors.iter_mut().filter(|(idx, _)| *idx == to) {
+                *w = weight;
+                found = true;
+            }
+        }
+        if found {
+            Ok(())
+        } else {
+            Err(SolveError::MissingEdge { from: a, to: b })
+        }
+    }
+
+    /// Connects `a` and `b` with a two-way edge of the given weight. Fails
+    /// without changing anything if either vertex doesn't exist, `a == b`,
+    /// the weight isn't finite or the two are already connected in either
+    /// direction. The new edge has no known pixels, so drawing it draws a
+    /// straight line.
+    pub fn add_edge(&mut self, a: usize, b: usize, weight: f32) -> Result<(), SolveError> {
+        self.check_in_range(&[a, b])?;
+        if a == b {
+            return Err(SolveError::SelfLoop { vertex: a });
+        }
+        if !weight.is_finite() {
+            return Err(SolveError::InvalidWeight {
+                from: a,
+                to: b,
+                weight,
+            });
+        }
+        if self.has_edge(a, b) || self.has_edge(b, a) {
+            return Err(SolveError::DuplicateEdge { from: a, to: b });
+        }
+
+        self.vertices[a].neighbors.push((b, weight));
+        self.vertices[b].neighbors.push((a, weight));
+        self.spatial_index.take();
+        Ok(())
+    }
+
+    /// Removes the edges between `a` and `b` in both directions, together
+    /// with their pixels. Fails if there is none. `start` or `end` can be
+    /// left without edges, which the solvers report as a `DeadEndpoint`.
+    pub fn remove_edge(&mut self, a: usize, b: usize) -> Result<(), SolveError> {
+        self.check_in_range(&[a, b])?;
+        if !self.has_edge(a, b) && !self.has_edge(b, a) {
+            return Err(SolveError::MissingEdge { from: a, to: b });
+        }
+        self.vertices[a].neighbors.retain(|(idx, _)| *idx != b);
+        self.vertices[b].neighbors.retain(|(idx, _)| *idx != a);
+        self.edge_pixels.remove(&(a, b));
+        self.edge_pixels.remove(&(b, a));
+        self.spatial_index.take();
+        Ok(())
+    }
+
+    fn check_in_range(&self, indices: &[usize]) -> Result<(), SolveError> {
+        let vertex_count = self.vertices.len();
+        match indices.iter().find(|&&idx| idx >= vertex_count) {
+            Some(&index) => Err(SolveError::VertexOutOfRange {
+                index,
+                vertex_count,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Turns the edge between `from` and `to` into a one-way passage by
+    /// dropping every edge from `to` back to `from`. Returns false and leaves
+    /// the graph alone if there is no edge from `from` to `to`.
+    ///
+    /// The graph solvers, `calculate_cost` and `validate_path` follow edges in
+    /// their direction only. `theta_star` and `smooth_path` take shortcuts
+    /// through the grid and ignore it, `tremaux` can get stuck since it has
+    /// to walk back out of dead ends.
+    pub fn set_one_way(&mut self, from: usize, to: usize) -> bool {
+        if !self.has_edge(from, to) {
+            return false;
+        }
+        self.vertices[to].neighbors.retain(|(idx, _)| *idx != from);
+        self.edge_pixels.remove(&(to, from));
+        self.spatial_index.take();
+        true
+    }
+
+    /// Whether there is exactly one path between any two cells, so the maze
+    /// has no loops and is in one piece. That is, the edges form a single tree
+    /// over `start`, `end` and every vertex that has edges, the vertices
+    /// reduction emptied out don't count. One-way edges count like two-way
+    /// ones. When this is true `k_shortest_paths` never finds more than one
+    /// path.
+    pub fn is_perfect(&self) -> bool {
+        fn find(roots: &mut [usize], mut idx: usize) -> usize {
+            while roots[idx] != idx {
+                roots[idx] = roots[roots[idx]];
+                idx = roots[idx];
+            }
+            idx
+        }
+
+        // Union-find over the edges, joining two vertices that are already
+        // connected closes a loop
+        let mut roots: Vec<usize> = (0..self.vertices.len()).collect();
+        let mut in_tree = vec![false; self.vertices.len()];
+        in_tree[self.start] = true;
+        in_tree[self.end] = true;
+        let mut edge_count = 0;
+        for (a, b, _) in self.edges() {
+            let (root_a, root_b) = (find(&mut roots, a), find(&mut roots, b));
+            if root_a == root_b {
+                return false;
+            }
+            roots[root_a] = root_b;
+            in_tree[a] = true;
+            in_tree[b] = true;
+            edge_count += 1;
+        }
+
+        // A forest without loops has one tree per vertex it has more than edges
+        let vertex_count = in_tree.iter().filter(|&&used| used).count();
+        edge_count + 1 == vertex_count
+    }
+
+    /// The longest of all shortest paths in a perfect maze, from one end of
+    /// the tree to the farthest other end, following edge weights so reduced
+    /// corridors count by their length. In a tree two passes are enough: the
+    /// vertex farthest from `start` is an end of the longest path, and the
+    /// vertex farthest from that the other end. `None` unless `is_perfect`,
+    /// or if the maze has one-way edges, since with loops this is the
+    /// NP-hard longest path problem.
+    pub fn diameter_path(&self) -> Option<Vec<usize>> {
+        if !self.is_perfect() || self.has_one_way_edges() {
+            return None;
+        }
+        let farthest = |from: usize| {
+            let (dists, parents) = shortest_paths_from(self, from);
+            let mut best = from;
+            for (idx, &dist) in dists.iter().enumerate() {
+                if dist.is_finite() && dist > dists[best] {
+                    best = idx;
+                }
+            }
+            (best, parents)
+        };
+        let (a, _) = farthest(self.start);
+        let (b, parents) = farthest(a);
+        let mut path = vec![b];
+        while let Some(parent) = parents[*path.last().unwrap()] {
+            path.push(parent);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// A rough score for how hard the maze is to solve, higher is harder, for
+    /// sorting mazes rather than for its absolute value. It multiplies
+    ///
+    /// - `log2(1 + length)` of the shortest solution, so size counts but
+    ///   doesn't dominate,
+    /// - `1 + decisions`, the vertices along that solution with more than two
+    ///   neighbors, each one a chance to take a wrong turn,
+    /// - `1 + dead_end_ratio`, the share of dead ends (one neighbor, not
+    ///   `start` or `end`) among the vertices with other than two neighbors,
+    ///   how much of the maze leads nowhere.
+    ///
+    /// Vertices with exactly two neighbors don't count anywhere, so a reduced
+    /// graph scores the same as the full one as long as both find the same
+    /// solution. An unsolvable maze scores 0.
+    pub fn difficulty(&self) -> f32 {
+        let (dists, parents) = shortest_paths_from(self, self.start);
+        let length = dists[self.end];
+        if !length.is_finite() {
+            return 0.0;
+        }
+
+        let mut decisions = 0;
+        let mut current = self.end;
+        while let Some(parent) = parents[current] {
+            if self.vertices[parent].neighbors.len() > 2 {
+                decisions += 1;
+            }
+            current = parent;
+        }
+
+        // Corridor vertices are left out, so reduction doesn't dilute the ratio
+        let places = self
+            .vertices
+            .iter()
+            .filter(|v| !v.neighbors.is_empty() && v.neighbors.len() != 2)
+            .count();
+        let dead_ends = (0..self.vertices.len())
+            .filter(|&i| i != self.start && i != self.end && self.vertices[i].neighbors.len() == 1)
+            .count();
+        let dead_end_ratio = dead_ends as f32 / places.max(1) as f32;
+
+        (1.0 + length.max(0.0)).log2() * (1 + decisions) as f32 * (1.0 + dead_end_ratio)
+    }
+
+    /// The component every vertex belongs to, treating one-way edges as
+    /// two-way. Components are numbered from 0 in the order of their lowest
+    /// vertex index, so the numbering is the same every run. Vertices that
+    /// reduction emptied out get `None`, unless they are `start` or `end`.
+    pub fn connected_components(&self) -> Vec<Option<usize>> {
+        let mut adjacent = vec![Vec::new(); self.vertices.len()];
+        for (a, b, _) in self.edges() {
+            adjacent[a].push(b);
+            adjacent[b].push(a);
+        }
+
+        let mut components = vec![None; self.vertices.len()];
+        let mut count = 0;
+        for first in 0..self.vertices.len() {
+            let used = !adjacent[first].is_empty() || first == self.start || first == self.end;
+            if !used || components[first].is_some() {
+                continue;
+            }
+            components[first] = Some(count);
+            let mut stack = vec![first];
+            while let Some(current) = stack.pop() {
+                for &next in &adjacent[current] {
+                    if components[next].is_none() {
+                        components[next] = Some(count);
+                        stack.push(next);
+                    }
+                }
+            }
+            count += 1;
+        }
+        components
+    }
+
+    /// Removes the vertices that reduction left without any edges and renumbers
+    /// the rest (including `start` and `end`) so indices are contiguous again.
+    /// Solvers allocate their bookkeeping per vertex, so this shrinks all of it.
+    pub fn compact(&mut self) {
+        let mut remap = vec![None; self.vertices.len()];
+        let mut next = 0;
+        for (i, vertex) in self.vertices.iter().enumerate() {
+            if !vertex.neighbors.is_empty() || i == self.start || i == self.end {
+                remap[i] = Some(next);
+                next += 1;
+            }
+        }
+
+        let vertices = std::mem::take(&mut self.vertices);
+        self.vertices = vertices
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| remap[*i].is_some())
+            .map(|(_, mut vertex)| {
+                vertex.neighbors = vertex
+                    .neighbors
+                    .iter()
+                    .filter_map(|&(idx, weight)| remap[idx].map(|idx| (idx, weight)))
+                    .collect();
+                vertex
+            })
+            .collect();
+
+        self.edge_pixels = std::mem::take(&mut self.edge_pixels)
+            .into_iter()
+            .filter_map(|((a, b), pixels)| Some(((remap[a]?, remap[b]?), pixels)))
+            .collect();
+
+        self.start = remap[self.start].unwrap();
+        self.end = remap[self.end].unwrap();
+        self.spatial_index.take();
+    }
+
+    /// Appends a vertex without any edges and returns its index.
+    pub(crate) fn push_vertex(&mut self, pos: T) -> usize {
+        self.vertices.push(Vertex {
+            pos,
+            neighbors: Vec::new(),
+        });
+        self.spatial_index.take();
+        self.vertices.len() - 1
+    }
+
+    /// The positions along the edge from `a` to `b` that reduction collapsed
+    /// into it, both ends included, so the first is `a`'s and the last `b`'s.
+    /// Asking for `(b, a)` walks the corridor the other way. Where several
+    /// corridors join the two vertices this is the shortest one. `None` if
+    /// there is no edge from `a` to `b` or the graph wasn't built from an
+    /// image.
+    pub fn edge_pixels(&self, a: usize, b: usize) -> Option<&[T]> {
+        self.edge_pixels.get(&(a, b)).map(Vec::as_slice)
+    }
+}
+
+impl Graph<Coord3D> {
+    /// Stacks 2D slices into a 3D maze. `layers[z][y][x]` is `true` for an
+    /// open cell, moving up or down a layer is allowed wherever both cells
+    /// are open. Layers and rows may have different lengths.
+    ///
+    /// The start is the first open cell and the end the last one, scanning
+    /// layer by layer, row by row. Overwrite `start`/`end` to pick others.
+    /// Returns `None` if there is no open cell at all.
+    pub fn from_layers(layers: &[Vec<Vec<bool>>]) -> Option<Self> {
+        let mut vertices = Vec::new();
+        let mut lookup = HashMap::new();
+        for (z, layer) in layers.iter().enumerate() {
+            for (y, row) in layer.iter().enumerate() {
+                for (x, _) in row.iter().enumerate().filter(|(_, open)| **open) {
+                    let pos = Coord3D {
+                        x: x as u32,
+                        y: y as u32,
+                        z: z as u32,
+                    };
+                    lookup.insert(pos, vertices.len());
+                    vertices.push(Vertex {
+                        pos,
+                        neighbors: Vec::with_capacity(6),
+                    });
+                }
+            }
+        }
+
+        let start = 0;
+        let end = vertices.len().checked_sub(1)?;
+        populate_vertex_neighbors(&mut vertices, |pos| lookup.get(pos).copied(), |_, _| 1.0);
+        reduce_vertex_count(&mut vertices, &[start, end]);
+
+        Some(Self {
+            start,
+            end,
+            heuristic: Heuristic::Manhattan,
+            vertices,
+            grid: None,
+            border: None,
+            edge_pixels: HashMap::new(),
+            spatial_index: OnceLock::new(),
+            #[cfg(feature = "image")]
+            source: None,
+        })
+    }
+}
+
+#[cfg(feature = "image")]
+impl Graph<HexCoord> {
+    /// Reads an image of a hexagonal maze drawn as pointy-top hexagons with
+    /// the given `radius` (center to corner, in pixels), odd rows shifted half
+    /// a cell to the right and the first cell's corners touching the top and
+    /// left edges of the image. A cell is open if the pixel at its center is,
+    /// and two neighboring open cells are connected if the pixel halfway
+    /// between their centers is open too, so walls drawn along the shared side
+    /// separate them. Every step costs 1.
+    ///
+    /// Like `Graph::from_layers` the start is the first open cell and the end
+    /// the last one, row by row, overwrite `start`/`end` to pick others.
+    pub fn from_hex_png<P: AsRef<Path>>(path: P, radius: f32) -> Result<Self, ImageError> {
+        if !(radius.is_finite() && radius >= 1.0) {
+            return Err(parameter_error("hex cell radius must be at least 1 pixel"));
+        }
+        let img = decode(ImageReader::open(path)?.with_guessed_format()?)?.into_rgba8();
+        let walls = WallDetect::default();
+        let (width, height) = (img.width() as f32, img.height() as f32);
+
+        let spacing = 3f32.sqrt() * radius;
+        let center = |cell: &HexCoord| {
+            let shift = if cell.row.is_multiple_of(2) { 0.5 } else { 1.0 };
+            let x = spacing * (cell.col as f32 + shift);
+            let y = radius * (1.5 * cell.row as f32 + 1.0);
+            (x, y)
+        };
+        let open_at = |(x, y): (f32, f32)| {
+            let (x, y) = (x.round(), y.round());
+            x < width && y < height && walls.is_open(img.get_pixel(x as u32, y as u32))
+        };
+
+        let columns = (width / spacing).ceil() as u32;
+        let rows = (height / (1.5 * radius)).ceil() as u32;
+        let mut vertices = Vec::new();
+        let mut lookup = HashMap::new();
+        for row in 0..rows {
+            for col in 0..columns {
+                let pos = HexCoord { col, row };
+                if open_at(center(&pos)) {
+                    lookup.insert(pos, vertices.len());
+                    vertices.push(Vertex {
+                        pos,
+                        neighbors: Vec::with_capacity(6),
+                    });
+                }
+            }
+        }
+
+        let start = 0;
+        let end = vertices
+            .len()
+            .checked_sub(1)
+            .ok_or_else(|| parameter_error("no open hex cell in the image"))?;
+        for vertex in vertices.iter_mut() {
+            let (x, y) = center(&vertex.pos);
+            for neighbor in vertex.pos.potential_neighbors() {
+                let Some(&neighbor_idx) = lookup.get(&neighbor) else {
+                    continue;
+                };
+                let (nx, ny) = center(&neighbor);
+                if open_at(((x + nx) / 2.0, (y + ny) / 2.0)) {
+                    vertex.neighbors.push((neighbor_idx, 1.0));
+                }
+            }
+        }
+        reduce_vertex_count(&mut vertices, &[start, end]);
+
+        Ok(Self {
+            start,
+            end,
+            heuristic: Heuristic::Chebyshev,
+            vertices,
+            grid: None,
+            border: None,
+            edge_pixels: HashMap::new(),
+            spatial_index: OnceLock::new(),
+            #[cfg(feature = "image")]
+            source: None,
+        })
+    }
+}
+
+/// Why a maze couldn't be turned into a graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphError {
+    /// Every pixel is a wall, e.g. an all black image or the wrong
+    /// `LoadOptions::walls`
+    NoWalkablePixels,
+    /// Only one pixel is open, so there is nowhere to go from it
+    SingleWalkablePixel,
+    /// The maze has more than `MAX_PIXELS` pixels
+    TooLarge { width: u32, height: u32 },
+    /// Drawing a `width`x`height` maze at `DrawOptions::scale` would give an
+    /// image wider or taller than `u32::MAX`
+    ScaleTooLarge { width: u32, height: u32, scale: u32 },
+}
+
+/// Most pixels a maze can have. Pixels are indexed as `y * width + x` in
+/// `u32` throughout loading, this keeps every such index in range.
+pub const MAX_PIXELS: u64 = u32::MAX as u64;
+
+// Those indices are then cast to `usize` for the pixel buffers
+const _: () = assert!(MAX_PIXELS <= usize::MAX as u64);
+
+fn check_size(width: u32, height: u32) -> Result<(), GraphError> {
+    if u64::from(width) * u64::from(height) > MAX_PIXELS {
+        return Err(GraphError::TooLarge { width, height });
+    }
+    Ok(())
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::NoWalkablePixels => write!(
+                f,
+                "the maze has no walkable pixels, check that passages are light and walls dark"
+            ),
+            GraphError::SingleWalkablePixel => {
+                write!(f, "the maze has a single walkable pixel, a path needs two")
+            }
+            GraphError::TooLarge { width, height } => write!(
+                f,
+                "the maze is {width}x{height} pixels, at most {MAX_PIXELS} pixels are supported"
+            ),
+            GraphError::ScaleTooLarge {
+                width,
+                height,
+                scale,
+            } => write!(
+                f,
+                "a {width}x{height} maze scaled {scale} times is too large to draw"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+#[cfg(feature = "image")]
+impl From<GraphError> for ImageError {
+    fn from(err: GraphError) -> Self {
+        parameter_error(&err.to_string())
+    }
+}
+
+/// Decodes the image unless its header already says it has more than
+/// `MAX_PIXELS` pixels, which would only fail later after a huge allocation.
+#[cfg(feature = "image")]
+fn decode<R: BufRead + Seek>(reader: ImageReader<R>) -> Result<DynamicImage, ImageError> {
+    let mut decoder = reader.into_decoder()?;
+    let (width, height) = decoder.dimensions();
+    check_size(width, height)?;
+    // The same allocation limit `ImageReader::decode` enforces
+    let mut limits = Limits::default();
+    limits.reserve(decoder.total_bytes())?;
+    decoder.set_limits(limits)?;
+    DynamicImage::from_decoder(decoder)
+}
+
+#[cfg(feature = "image")]
+fn parameter_error(message: &str) -> ImageError {
+    ImageError::Parameter(ParameterError::from_kind(ParameterErrorKind::Generic(
+        message.to_string(),
+    )))
+}
+
+#[cfg(feature = "image")]
+impl Graph<Coord> {
+    /// Loads a maze from any image format the `image` crate was built with:
+    /// PNG, BMP, JPEG, GIF, TIFF, WebP, PNM, TGA, ICO, QOI and the rest of its
+    /// default set. The format is detected from the file contents rather than
+    /// the extension. A pixel is open when its red channel is anything but 0
+    /// unless `LoadOptions::walls` says otherwise. Lossy formats such as JPEG
+    /// smear a little red into the walls, load those with a threshold, e.g.
+    /// `WallDetect::RedChannel(128)`.
+    pub fn from_image<P: AsRef<Path>>(path: P) -> Result<Self, ImageError> {
+        Self::from_png_with_options(path, &LoadOptions::default())
+    }
+
+    /// Same as `from_image`, but also reports the format the file was decoded as.
+    pub fn from_image_with_format<P: AsRef<Path>>(
+        path: P,
+        options: &LoadOptions,
+    ) -> Result<(Self, ImageFormat), ImageError> {
+        let reader = ImageReader::open(path)?.with_guessed_format()?;
+        let format = reader.format();
+        let img = decode(reader)?.into_rgba8();
+        // Decoding fails without a known format, so it is always set here
+        let format = format.expect("a decoded image has a format");
+        let costs = match &options.cost_map {
+            Some(cost_map) => Some(cost_map.load(img.dimensions())?),
+            None => None,
+        };
+        Ok((Self::from_rgba(&img, options, costs.as_deref())?, format))
+    }
+
+    /// Kept for existing callers, despite the name it accepts the same formats
+    /// as `from_image`.
+    pub fn from_png<P: AsRef<Path>>(path: P) -> Result<Self, ImageError> {
+        Self::from_image(path)
+    }
+
+    /// Same as `from_png`, but when the start/end points have to be picked at
+    /// random the choice is driven by `seed` so it can be reproduced.
+    pub fn from_png_seeded<P: AsRef<Path>>(path: P, seed: u64) -> Result<Self, ImageError> {
+        let options = LoadOptions {
+            seed: Some(seed),
+            ..Default::default()
+        };
+        Self::from_png_with_options(path, &options)
+    }
+
+    pub fn from_png_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &LoadOptions,
+    ) -> Result<Self, ImageError> {
+        Self::from_image_with_format(path, options).map(|(graph, _)| graph)
+    }
+
+    /// Same as `from_png`, but `start`/`end` are picked following `endpoints`.
+    pub fn from_png_with_endpoint_strategy<P: AsRef<Path>>(
+        path: P,
+        endpoints: EndpointStrategy,
+    ) -> Result<Self, ImageError> {
+        let options = LoadOptions {
+            endpoints,
+            ..LoadOptions::default()
+        };
+        Self::from_png_with_options(path, &options)
+    }
+
+    /// Same as `from_png`, but walls are told apart from passages following
+    /// `walls`.
+    pub fn from_png_with_walls<P: AsRef<Path>>(
+        path: P,
+        walls: WallDetect,
+    ) -> Result<Self, ImageError> {
+        let options = LoadOptions {
+            walls,
+            ..LoadOptions::default()
+        };
+        Self::from_png_with_options(path, &options)
+    }
+
+    /// Builds the graph from an encoded image held in memory, the format is
+    /// guessed from the data. Any bytes give either a graph or an error,
+    /// truncated or corrupt data included, never a panic. The `from_bytes`
+    /// fuzz target under `fuzz/` checks that.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ImageError> {
+        let img = decode(ImageReader::new(Cursor::new(bytes)).with_guessed_format()?)?.into_rgba8();
+        Self::from_rgba(&img, &LoadOptions::default(), None)
+    }
+
+    /// `costs` holds the price of stepping onto each pixel, row by row, see
+    /// `LoadOptions::cost_map`.
+    fn from_rgba(
+        img: &RgbaImage,
+        options: &LoadOptions,
+        costs: Option<&[f32]>,
+    ) -> Result<Self, ImageError> {
+        let save =
+            |stage: Stage, grid: &Grid, vertices: &[Vertex<Coord>]| match &options.debug_stages {
+                Some(dir) => save_stage(dir, stage, grid, vertices),
+                None => Ok(()),
+            };
+        let markers = |vertices: &[Vertex<Coord>], lookup: &dyn Fn(&Coord) -> Option<usize>| {
+            find_marker_vertices(img, vertices, lookup)
+        };
+        let grid = Grid::from_image(img, options.walls);
+        let mut graph = Self::build(grid, options, costs, save, markers)?;
+        if options.keep_source {
+            graph.source = Some(Arc::new(DynamicImage::ImageRgba8(img.clone()).into_rgb8()));
+        }
+        Ok(graph)
+    }
+}
+
+impl Graph<Coord> {
+    /// Builds the graph from a grid of open and blocked pixels the same way
+    /// the image loaders do once they told walls from passages, for mazes
+    /// that don't come from an image. `LoadOptions::cost_map` and
+    /// `LoadOptions::debug_stages` only apply to images and are ignored.
+    /// Fails if the grid has fewer than two open pixels or more than
+    /// `MAX_PIXELS` pixels.
+    pub fn from_grid(grid: Grid, options: &LoadOptions) -> Result<Self, GraphError> {
+        let save = |_: Stage, _: &Grid, _: &[Vertex<Coord>]| Ok(());
+        Self::build(grid, options, None, save, |_, _| None)
+    }
+
+    /// The part of loading shared by images and grids. `save` is handed the
+    /// vertices after each `Stage`, `markers` gets to pick the endpoints when
+    /// the border doesn't have exactly two openings.
+    fn build<E, S, M>(
+        mut grid: Grid,
+        options: &LoadOptions,
+        costs: Option<&[f32]>,
+        save: S,
+        markers: M,
+    ) -> Result<Self, E>
+    where
+        E: From<GraphError>,
+        S: Fn(Stage, &Grid, &[Vertex<Coord>]) -> Result<(), E>,
+        M: FnOnce(&[Vertex<Coord>], &dyn Fn(&Coord) -> Option<usize>) -> Option<[usize; 2]>,
+    {
+        check_size(grid.width, grid.height)?;
+        let build_start = Instant::now();
+        let mut rng = match options.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+
+        if options.cleanup_radius > 0 {
+            grid = grid.majority_filter(options.cleanup_radius);
+        }
+        if options.skeletonize {
+            grid = grid.skeletonize();
+        }
+        let mut vertices = create_vertices(&grid, options.diagonals, options.low_memory);
+        match vertices.len() {
+            0 => return Err(GraphError::NoWalkablePixels.into()),
+            1 => return Err(GraphError::SingleWalkablePixel.into()),
+            _ => {}
+        }
+        log::debug!(
+            "{} walkable pixels in the {}x{} grid",
+            vertices.len(),
+            grid.width,
+            grid.height
+        );
+        save(Stage::Vertices, &grid, &vertices)?;
+
+        let width = grid.width;
+        let height = grid.height;
+
+        let open_rank = options.low_memory.then(|| OpenRank::new(&grid));
+        let mut grid_lookup = Vec::new();
+        if open_rank.is_none() {
+            grid_lookup.resize((width * height) as usize, None);
+            for (i, v) in vertices.iter().enumerate() {
+                let idx = (v.pos.y * width + v.pos.x) as usize;
+                grid_lookup[idx] = Some(i);
+            }
+        }
+
+        let lookup = |pos: &Coord| {
+            if pos.x >= width || pos.y >= height {
+                return None;
+            }
+            match &open_rank {
+                Some(rank) => rank.index(&grid, pos),
+                None => grid_lookup[(pos.y * width + pos.x) as usize],
+            }
+        };
+        let diagonal_cost = if options.king_moves {
+            1.0
+        } else {
+            std::f32::consts::SQRT_2
+        };
+        let weight = |from: &Coord, to: &Coord| match &options.weight {
+            Some(weight) => (weight.0)(from, to),
+            None if from.x != to.x && from.y != to.y => diagonal_cost,
+            None => 1.0,
+        };
+        populate_vertex_neighbors(&mut vertices, lookup, weight);
+        if let Some(policy) = options.diagonals {
+            add_diagonal_neighbors(&mut vertices, &grid, policy, lookup, weight);
+        }
+        save(Stage::Neighbors, &grid, &vertices)?;
+        if let Some(costs) = costs {
+            // A step costs as much as the pixel it lands on
+            let step_costs: Vec<f32> = vertices
+                .iter()
+                .map(|v| costs[(v.pos.y * width + v.pos.x) as usize])
+                .collect();
+            for vertex in vertices.iter_mut() {
+                for (idx, weight) in vertex.neighbors.iter_mut() {
+                    *weight *= step_costs[*idx];
+                }
+            }
+        }
+
+        // Keep the entrances even if they sit in the middle of a corridor,
+        // which happens with diagonals or openings wider than a pixel. All of
+        // them, so any can become an endpoint or be looked up later on
+        let mut boundary_vertices = border_openings(&vertices, width, height, options.border);
+        let max_diameter = options.endpoints == EndpointStrategy::MaxDiameter;
+        if !options.skip_reduction {
+            let before = count_alive(&vertices);
+            reduce_vertex_count(&mut vertices, &boundary_vertices);
+            log::debug!(
+                "Reduction kept {} of {before} vertices",
+                count_alive(&vertices)
+            );
+        }
+        save(Stage::Reduced, &grid, &vertices)?;
+
+        let edge_pixels = trace_edge_pixels(&vertices, &grid, options.diagonals, lookup);
+        let heuristic = match options.diagonals {
+            Some(_) if options.king_moves => Heuristic::Chebyshev,
+            Some(_) => Heuristic::Octile,
+            None => Heuristic::Manhattan,
+        };
+        let mut graph = Self {
+            start: 0,
+            end: 0,
+            heuristic,
+            vertices,
+            grid: None,
+            border: options.border,
+            edge_pixels,
+            spatial_index: OnceLock::new(),
+            #[cfg(feature = "image")]
+            source: None,
+        };
+
+        let farthest = max_diameter
+            .then(|| graph.farthest_pair(&boundary_vertices))
+            .flatten();
+        if let Some(pair) = farthest {
+            boundary_vertices = pair.to_vec();
+        } else if boundary_vertices.len() != 2 {
+            if let Some(markers) = markers(&graph.vertices, &lookup) {
+                boundary_vertices = markers.to_vec();
+            } else {
+                log::warn!(
+                    "Could not find definitive start/endpoints for this graph, using two at random"
+                );
+                boundary_vertices =
+                    pick_random_endpoints(&graph.vertices, &boundary_vertices, &mut rng);
+            }
+        }
+
+        let [start, end] = boundary_vertices[..] else {
+            unreachable!("two endpoints are always picked from two or more vertices");
+        };
+        graph.start = start;
+        graph.end = end;
+        graph.grid = Some(grid);
+        log::info!(
+            "Built a graph of {} vertices in {}ms",
+            count_alive(&graph.vertices),
+            build_start.elapsed().as_millis()
+        );
+        Ok(graph)
+    }
+
+    /// The two `candidates` with the longest shortest path between them, or
+    /// `None` if no two of them are connected. Runs Dijkstra once per candidate.
+    fn farthest_pair(&self, candidates: &[usize]) -> Option<[usize; 2]> {
+        let mut best = None;
+        let mut best_dist = 0.0;
+        for (i, &a) in candidates.iter().enumerate() {
+            let (dists, _) = shortest_paths_from(self, a);
+            for &b in &candidates[i + 1..] {
+                if dists[b].is_finite() && dists[b] > best_dist {
+                    best = Some([a, b]);
+                    best_dist = dists[b];
+                }
+            }
+        }
+        best
+    }
+
+    /// The openings in the border of the image, as found while loading, in the
+    /// order `find_boundary_vertices` returns them. Empty for graphs that
+    /// weren't built from an image.
+    pub fn boundary_openings(&self) -> Vec<usize> {
+        match &self.grid {
+            Some(grid) => border_openings(&self.vertices, grid.width, grid.height, self.border),
+            None => Vec::new(),
+        }
+    }
+
+    /// Shortest path costs between every pair of `boundary_openings`, for
+    /// getting an overview of a maze with many entrances. Only the first
+    /// `MAX_OPENINGS` openings are looked at, since this runs Dijkstra once
+    /// per opening.
+    pub fn opening_costs(&self) -> OpeningCosts {
+        let mut openings = self.boundary_openings();
+        openings.truncate(MAX_OPENINGS);
+        let costs = openings
+            .iter()
+            .map(|&from| {
+                let (dists, _) = shortest_paths_from(self, from);
+                openings
+                    .iter()
+                    .map(|&to| Some(dists[to]).filter(|dist| dist.is_finite()))
+                    .collect()
+            })
+            .collect();
+        OpeningCosts { openings, costs }
+    }
+
+    /// One graph per connected component, for images holding several separate
+    /// mazes, in the order `connected_components` numbers them. Each one gets
+    /// the two of its border openings farthest apart by path cost as `start`
+    /// and `end`, or its first and last vertex if it has fewer than two
+    /// openings. Vertices without edges are left out, the pixel grid is
+    /// shared so every graph still covers the whole image.
+    pub fn split_components(&self) -> Vec<Graph<Coord>> {
+        let components = self.connected_components();
+        let count = components.iter().flatten().max().map_or(0, |&max| max + 1);
+
+        // Vertices keep their relative order, so each one's index in its
+        // component is how many of the component came before it
+        let mut members = vec![Vec::new(); count];
+        let mut new_index = vec![0; self.vertices.len()];
+        for (idx, component) in components.iter().enumerate() {
+            if let Some(component) = *component {
+                new_index[idx] = members[component].len();
+                members[component].push(idx);
+            }
+        }
+        let mut edge_pixels = vec![HashMap::new(); count];
+        for (&(from, to), pixels) in &self.edge_pixels {
+            if let Some(component) = components[from] {
+                edge_pixels[component].insert((new_index[from], new_index[to]), pixels.clone());
+            }
+        }
+        let mut openings = vec![Vec::new(); count];
+        for idx in self.boundary_openings() {
+            if let Some(component) = components[idx] {
+                openings[component].push(new_index[idx]);
+            }
+        }
+
+        members
+            .into_iter()
+            .zip(edge_pixels)
+            .zip(openings)
+            .map(|((members, edge_pixels), openings)| {
+                let vertices = members
+                    .iter()
+                    .map(|&idx| Vertex {
+                        pos: self.vertices[idx].pos,
+                        neighbors: self.vertices[idx]
+                            .neighbors
+                            .iter()
+                            .map(|&(to, weight)| (new_index[to], weight))
+                            .collect(),
+                    })
+                    .collect::<Vec<_>>();
+                let mut graph = Graph {
+                    start: 0,
+                    end: vertices.len() - 1,
+                    heuristic: self.heuristic,
+                    vertices,
+                    grid: self.grid.clone(),
+                    border: self.border,
+                    edge_pixels,
+                    spatial_index: OnceLock::new(),
+                    #[cfg(feature = "image")]
+                    source: self.source.clone(),
+                };
+                if let Some([start, end]) = graph.farthest_pair(&openings) {
+                    graph.start = start;
+                    graph.end = end;
+                }
+                graph
+            })
+            .collect()
+    }
+
+    /// The vertex with edges closest to `coord` walking through open pixels,
+    /// at most `max_radius` orthogonal steps away, so a vertex on the other
+    /// side of a wall is never picked over one in the same passage. A click
+    /// on a wall pixel walks from the open pixels next to it. Meant for
+    /// turning an imprecise click or a corridor pixel that reduction merged
+    /// away into something that can be used as `start` or `end`. Equally close
+    /// vertices resolve to the lower index. Graphs without a `Grid` fall back
+    /// to the straight line distance, ignoring walls.
+    pub fn snap_to_vertex(&self, coord: &Coord, max_radius: u32) -> Option<usize> {
+        let Some(grid) = &self.grid else {
+            return self.nearest(coord).filter(|&idx| {
+                coord.euclidean_distance(&self.vertices[idx].pos) <= max_radius as f32
+            });
+        };
+        if coord.x >= grid.width() || coord.y >= grid.height() {
+            return None;
+        }
+        let index = self
+            .spatial_index
+            .get_or_init(|| SpatialIndex::new(&self.vertices));
+
+        let mut seen = HashSet::from([*coord]);
+        let mut layer = vec![*coord];
+        for _ in 0..=max_radius {
+            let found = layer
+                .iter()
+                .filter_map(|pixel| index.at(&self.vertices, pixel))
+                .min();
+            if found.is_some() {
+                return found;
+            }
+            let mut next = Vec::new();
+            for pixel in &layer {
+                for neighbor in pixel.potential_neighbors() {
+                    if grid.is_open(neighbor.x, neighbor.y) && seen.insert(neighbor) {
+                        next.push(neighbor);
+                    }
+                }
+            }
+            layer = next;
+        }
+        None
+    }
+
+    /// The vertex with edges closest to `coord` in a straight line, however far
+    /// away, ignoring walls. Equally close vertices resolve to the lower index.
+    /// The first call buckets the vertices by position, later ones only look
+    /// at the buckets around `coord`.
+    pub fn nearest(&self, coord: &Coord) -> Option<usize> {
+        self.spatial_index
+            .get_or_init(|| SpatialIndex::new(&self.vertices))
+            .nearest(&self.vertices, coord)
+    }
+
+    /// Pixel coordinates walked along `path`, corridors included, as `(x, y)`
+    /// pairs from the first vertex to the last. Edges without known pixels,
+    /// like the shortcuts of `theta_star`, only contribute their endpoints.
+    pub fn path_to_coords(&self, path: &[usize]) -> Vec<(u32, u32)> {
+        let mut coords: Vec<(u32, u32)> = path
+            .first()
+            .map(|&first| {
+                let pos = &self.vertices[first].pos;
+                (pos.x, pos.y)
+            })
+            .into_iter()
+            .collect();
+
+        for pair in path.windows(2) {
+            match self.edge_pixels(pair[0], pair[1]) {
+                // The first pixel is where the previous edge ended
+                Some(pixels) => coords.extend(pixels[1..].iter().map(|p| (p.x, p.y))),
+                None => {
+                    let pos = &self.vertices[pair[1]].pos;
+                    coords.push((pos.x, pos.y));
+                }
+            }
+        }
+        coords
+    }
+
+    /// The steps walked along `path` as moves, one per pixel with corridors
+    /// included, e.g. for driving a robot through the maze. Edges without
+    /// known pixels, like the shortcuts of `theta_star`, become a single move
+    /// in their rough direction.
+    pub fn path_to_moves(&self, path: &[usize]) -> Vec<Move> {
+        let coords: Vec<Coord> = self
+            .path_to_coords(path)
+            .into_iter()
+            .map(|(x, y)| Coord { x, y })
+            .collect();
+        coords
+            .windows(2)
+            .filter_map(|pair| Move::between(&pair[0], &pair[1]))
+            .collect()
+    }
+
+    /// The steps walked along `path` as commands for a turtle that starts on
+    /// its first vertex facing `heading`, see `turtle_commands`.
+    pub fn path_to_turtle(&self, path: &[usize], heading: Move) -> Vec<TurtleCommand> {
+        turtle_commands(&self.path_to_moves(path), heading)
+    }
+
+    /// Vertices with exactly one neighbor and their positions, in index order.
+    /// Entrances are usually among them, since `start` and `end` are only
+    /// left out of the count in `difficulty`, not here.
+    pub fn dead_ends(&self) -> Vec<(usize, Coord)> {
+        self.vertices_by_degree(|degree| degree == 1)
+    }
+
+    /// Vertices with three or more neighbors and their positions, in index
+    /// order. These are the places a solver has to choose where to go.
+    pub fn junctions(&self) -> Vec<(usize, Coord)> {
+        self.vertices_by_degree(|degree| degree >= 3)
+    }
+
+    fn vertices_by_degree<F>(&self, keep: F) -> Vec<(usize, Coord)>
+    where
+        F: Fn(usize) -> bool,
+    {
+        self.vertices
+            .iter()
+            .enumerate()
+            .filter(|(_, vertex)| keep(vertex.neighbors.len()))
+            .map(|(idx, vertex)| (idx, vertex.pos))
+            .collect()
+    }
+
+    /// `path_to_coords` as a JSON array of `[x, y]` pairs.
+    pub fn path_to_json(&self, path: &[usize]) -> String {
+        let pairs: Vec<String> = self
+            .path_to_coords(path)
+            .iter()
+            .map(|(x, y)| format!("[{x},{y}]"))
+            .collect();
+        format!("[{}]", pairs.join(","))
+    }
+}
+
+#[cfg(feature = "image")]
+impl Graph<Coord> {
+    /// Renders the maze as the graph sees it, open pixels white and walls
+    /// black. This is the wall mask after any cleanup or skeletonization, not
+    /// necessarily the original image. Without a grid the image is rebuilt
+    /// from the vertex and corridor positions instead.
+    pub fn to_image(&self) -> RgbImage {
+        let white = Rgb([255, 255, 255]);
+        if let Some(grid) = &self.grid {
+            return RgbImage::from_fn(grid.width, grid.height, |x, y| {
+                if grid.is_open(x, y) {
+                    white
+                } else {
+                    Rgb([0, 0, 0])
+                }
+            });
+        }
+
+        let positions = || {
+            self.vertices
+                .iter()
+                .map(|v| &v.pos)
+                .chain(self.edge_pixels.values().flatten())
+        };
+        let width = positions().map(|pos| pos.x + 1).max().unwrap_or(0);
+        let height = positions().map(|pos| pos.y + 1).max().unwrap_or(0);
+        let mut img = RgbImage::new(width, height);
+        for pos in positions() {
+            img.put_pixel(pos.x, pos.y, white);
+        }
+        img
+    }
+
+    /// Saves a maze with only the pixels of `path` open, white on black, the
+    /// size of `to_image`. Corridors are filled in from the pixels behind each
+    /// edge, so a shortest path loads back as one corridor between the same
+    /// two openings. Paths with diagonal steps need `LoadOptions::diagonals`
+    /// to load back connected.
+    pub fn export_solution_only<Q: AsRef<Path>>(
+        &self,
+        path: &[usize],
+        output_path: Q,
+    ) -> Result<(), ImageError> {
+        let mut img = self.to_image();
+        for pixel in img.pixels_mut() {
+            *pixel = Rgb([0, 0, 0]);
+        }
+        for (x, y) in self.path_to_coords(path) {
+            if x < img.width() && y < img.height() {
+                img.put_pixel(x, y, Rgb([255, 255, 255]));
+            }
+        }
+        img.save(output_path)
+    }
+
+    /// Draws the path on top of the original image and saves it to `output_path`.
+    pub fn draw_path<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        path_indices: &[usize],
+        original_image_path: P,
+        output_path: Q,
+        options: &DrawOptions,
+    ) -> Result<(), ImageError> {
+        let img = image::open(original_image_path)?.into_rgb8();
+        self.draw_path_on(path_indices, img, output_path, options)
+    }
+
+    /// Like `draw_path`, but draws on the image kept by
+    /// `LoadOptions::keep_source` instead of reading it from disk again, so
+    /// the output may even replace the source file. Without a kept image it
+    /// draws on `to_image` instead.
+    pub fn draw_path_on_source<Q: AsRef<Path>>(
+        &self,
+        path_indices: &[usize],
+        output_path: Q,
+        options: &DrawOptions,
+    ) -> Result<(), ImageError> {
+        let img = match &self.source {
+            Some(source) => RgbImage::clone(source),
+            None => self.to_image(),
+        };
+        self.draw_path_on(path_indices, img, output_path, options)
+    }
+
+    /// Like `draw_path` but draws on an image that has already been decoded.
+    pub fn draw_path_on<Q: AsRef<Path>>(
+        &self,
+        path_indices: &[usize],
+        img: RgbImage,
+        output_path: Q,
+        options: &DrawOptions,
+    ) -> Result<(), ImageError> {
+        self.draw_paths_on(
+            &[(path_indices, options.path_color)],
+            img,
+            output_path,
+            options,
+        )
     }
-}
 
-pub struct Graph<T> {
-    pub start: usize,
-    pub end: usize,
-    vertices: Vec<Vertex<T>>,
-}
+    /// Draws several paths on top of the original image, each in its own
+    /// color, and saves it to `output_path`. Later paths are drawn over earlier
+    /// ones where they overlap, so the pixels that keep a path's color are the
+    /// ones no later path passes through. `options.path_color` is unused.
+    pub fn draw_paths<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        paths: &[(&[usize], Rgb<u8>)],
+        original_image_path: P,
+        output_path: Q,
+        options: &DrawOptions,
+    ) -> Result<(), ImageError> {
+        let img = image::open(original_image_path)?.into_rgb8();
+        self.draw_paths_on(paths, img, output_path, options)
+    }
 
-impl fmt::Display for Graph<Coord> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for (i, vertex) in self.vertices.iter().enumerate() {
-            writeln!(f, "[{i:2}]: {}, {:?}, ", vertex.pos, vertex.neighbors)?;
+    /// Renders a search trace from `solve_graph_traced` as animation frames,
+    /// for handing to whatever encoder the caller likes. A frame is taken
+    /// after every `every`th visited vertex, plus a last one after the whole
+    /// trace with the path found, so there are `visits / every + 1` frames.
+    /// Visited vertices, and the corridor they were reached through, are
+    /// drawn in `options.visited_color` over the image kept by
+    /// `LoadOptions::keep_source`, or `to_image` without one. A restart of the
+    /// iterative searches clears them again. `transparent_background` is
+    /// ignored.
+    pub fn trace_frames(
+        &self,
+        events: &[SearchEvent],
+        every: usize,
+        options: &DrawOptions,
+    ) -> Result<Vec<RgbImage>, GraphError> {
+        let every = every.max(1);
+        let background = match &self.source {
+            Some(source) => RgbImage::clone(source),
+            None => self.to_image(),
+        };
+        let mut img = background.clone();
+        let mut reached_from = vec![None; self.vertices.len()];
+        let mut found: &[usize] = &[];
+        let mut visits = 0usize;
+        let mut frames = Vec::new();
+        for event in events {
+            match event {
+                SearchEvent::Visit { vertex } => {
+                    let pos = &self.vertices[*vertex].pos;
+                    img.put_pixel(pos.x, pos.y, options.visited_color);
+                    if let Some(from) = reached_from[*vertex] {
+                        self.stroke_path(&mut img, &[from, *vertex], options.visited_color);
+                    }
+                    visits += 1;
+                    if visits.is_multiple_of(every) {
+                        frames.push(self.render_paths(&[], img.clone(), options)?);
+                    }
+                }
+                SearchEvent::Relax { vertex, from, .. } => reached_from[*vertex] = Some(*from),
+                SearchEvent::Backtrack { .. } => {}
+                SearchEvent::Restart { .. } => {
+                    img = background.clone();
+                    reached_from.fill(None);
+                }
+                SearchEvent::Found { path } => found = path,
+            }
         }
-        Ok(())
+        frames.push(self.render_paths(&[(found, options.path_color)], img, options)?);
+        Ok(frames)
     }
-}
 
-impl<T> Graph<T> {
-    pub fn get_vertices(&self) -> &Vec<Vertex<T>> {
-        return &self.vertices;
+    /// Saves the maze with every connected component in its own color, see
+    /// `connected_components`. Corridors take the color of the vertices they
+    /// join, walls stay black. When `start` and `end` come out in different
+    /// colors there is no path between them.
+    pub fn draw_components<Q: AsRef<Path>>(&self, output_path: Q) -> Result<(), ImageError> {
+        let components = self.connected_components();
+        let mut img = self.to_image();
+        for (idx, vertex) in self.vertices.iter().enumerate() {
+            if let Some(component) = components[idx] {
+                img.put_pixel(vertex.pos.x, vertex.pos.y, component_color(component));
+            }
+        }
+        for (&(a, _), pixels) in &self.edge_pixels {
+            if let Some(component) = components[a] {
+                for pixel in pixels {
+                    img.put_pixel(pixel.x, pixel.y, component_color(component));
+                }
+            }
+        }
+        img.save(output_path)?;
+        Ok(())
     }
-}
 
-impl Graph<Coord> {
-    pub fn from_png<P: AsRef<Path>>(path: P) -> Result<Self, ImageError> {
-        let path = path.as_ref();
-        let img = ImageReader::open(path)?.decode()?.into_rgb8();
-        let mut vertices = create_vertices(&img);
+    /// Like `draw_paths` but draws on an image that has already been decoded.
+    pub fn draw_paths_on<Q: AsRef<Path>>(
+        &self,
+        paths: &[(&[usize], Rgb<u8>)],
+        img: RgbImage,
+        output_path: Q,
+        options: &DrawOptions,
+    ) -> Result<(), ImageError> {
+        if !options.transparent_background {
+            return self.render_paths(paths, img, options)?.save(output_path);
+        }
+
+        // Drawn once over black and once over white, a pixel that comes out
+        // the same both times is fully covered by the drawing, one that
+        // follows the background isn't drawn at all, anything in between is
+        // partly covered
+        let (width, height) = img.dimensions();
+        let dark = self.render_paths(paths, RgbImage::new(width, height), options)?;
+        let light = self.render_paths(
+            paths,
+            RgbImage::from_pixel(width, height, Rgb([255, 255, 255])),
+            options,
+        )?;
+        let overlay = RgbaImage::from_fn(dark.width(), dark.height(), |x, y| {
+            let (dark, light) = (dark.get_pixel(x, y).0, light.get_pixel(x, y).0);
+            let shows_through = (0..3)
+                .map(|c| light[c].saturating_sub(dark[c]))
+                .max()
+                .unwrap_or(0);
+            let alpha = 255 - shows_through;
+            if alpha == 0 {
+                return Rgba([0, 0, 0, 0]);
+            }
+            // Over black the drawing's color is scaled down by its coverage
+            let color = dark.map(|c| (u32::from(c) * 255 / u32::from(alpha)).min(255) as u8);
+            Rgba([color[0], color[1], color[2], alpha])
+        });
+        overlay.save(output_path)
+    }
 
-        let width = img.width();
-        let height = img.height();
+    /// Everything `draw_paths_on` draws, on top of `img`.
+    fn render_paths(
+        &self,
+        paths: &[(&[usize], Rgb<u8>)],
+        mut img: RgbImage,
+        options: &DrawOptions,
+    ) -> Result<RgbImage, GraphError> {
+        for &(path_indices, color) in paths {
+            self.stroke_path(&mut img, path_indices, color);
+        }
 
-        let mut grid_lookup = vec![None; (width * height) as usize];
-        for (i, v) in vertices.iter().enumerate() {
-            let idx = (v.pos.y * width + v.pos.x) as usize;
-            grid_lookup[idx] = Some(i);
+        if let Some(outline_color) = options.outline_color {
+            // The paths again, in white on black, to tell which pixels the
+            // outline has to leave alone
+            let on_path = Rgb([255, 255, 255]);
+            let mut mask = RgbImage::new(img.width(), img.height());
+            for &(path_indices, _) in paths {
+                self.stroke_path(&mut mask, path_indices, on_path);
+            }
+            for (x, y, pixel) in mask.enumerate_pixels() {
+                if *pixel == on_path {
+                    continue;
+                }
+                let touches_path = (x.saturating_sub(1)..=x + 1)
+                    .flat_map(|nx| (y.saturating_sub(1)..=y + 1).map(move |ny| (nx, ny)))
+                    .any(|(nx, ny)| mask.get_pixel_checked(nx, ny) == Some(&on_path));
+                if touches_path {
+                    img.put_pixel(x, y, outline_color);
+                }
+            }
         }
 
-        populate_vertex_neighbors(&mut vertices, |pos| {
-            let idx = (pos.y * width + pos.x) as usize;
-            grid_lookup.get(idx).copied().flatten()
-        });
-        reduce_vertex_count(&mut vertices);
+        // Markers go on top of the path so the direction of travel is visible
+        let start_pos = &self.vertices[self.start].pos;
+        let end_pos = &self.vertices[self.end].pos;
+        draw_marker(
+            &mut img,
+            start_pos,
+            options.marker_radius,
+            options.start_color,
+        );
+        draw_marker(&mut img, end_pos, options.marker_radius, options.end_color);
 
-        let boundary_vertices = find_boundary_vertices(&vertices, img.width(), img.height());
-        if boundary_vertices.len() != 2 {
-            println!(
-                "Could not find definitive start/endpoints for this graph, using two at random"
-            )
+        // Scaling after drawing keeps the path on exactly the cells it passes through
+        let scale = options.scale.max(1);
+        if scale > 1 {
+            let (width, height) = img.dimensions();
+            let (Some(scaled_width), Some(scaled_height)) =
+                (width.checked_mul(scale), height.checked_mul(scale))
+            else {
+                return Err(GraphError::ScaleTooLarge {
+                    width,
+                    height,
+                    scale,
+                });
+            };
+            img = image::imageops::resize(
+                &img,
+                scaled_width,
+                scaled_height,
+                image::imageops::FilterType::Nearest,
+            );
         }
 
-        Ok(Self {
-            start: boundary_vertices[0],
-            end: boundary_vertices[1],
-            vertices,
-        })
+        // The label goes on last so it stays crisp and readable at any scale
+        if let Some(label) = &options.label {
+            draw_label(&mut img, label);
+        }
+        Ok(img)
     }
 
-    pub fn draw_path<P: AsRef<Path>>(
-        &self,
-        path_indices: &[usize],
-        original_image_path: P,
-    ) -> Result<(), ImageError> {
-        let mut img = image::open(original_image_path)?.into_rgb8();
-        let highlight_color = image::Rgb([255, 0, 0]); // Bright Red
-
+    /// Colors the pixels `path_indices` walks through in `highlight_color`.
+    fn stroke_path(&self, img: &mut RgbImage, path_indices: &[usize], highlight_color: Rgb<u8>) {
         // Iterate through the path in pairs (A -> B, B -> C)
         for window in path_indices.windows(2) {
             let start_node = &self.vertices[window[0]];
             let end_node = &self.vertices[window[1]];
 
+            // Follow the corridor the edge was reduced from when we know it
+            if let Some(pixels) = self.edge_pixels(window[0], window[1]) {
+                for pixel in pixels {
+                    img.put_pixel(pixel.x, pixel.y, highlight_color);
+                }
+                continue;
+            }
+
             // Draw the junction points
             img.put_pixel(start_node.pos.x, start_node.pos.y, highlight_color);
             img.put_pixel(end_node.pos.x, end_node.pos.y, highlight_color);
 
             // Draw the line between them
             draw_line(
-                &mut img,
+                img,
                 start_node.pos.x,
                 start_node.pos.y,
                 end_node.pos.x,
@@ -138,48 +2252,702 @@ impl Graph<Coord> {
                 highlight_color,
             );
         }
+    }
+}
 
-        img.save("solved_maze.png")?;
-        Ok(())
+/// How many border openings `Graph::opening_costs` compares at most.
+pub const MAX_OPENINGS: usize = 32;
+
+/// Result of `Graph::opening_costs`.
+#[derive(Clone, Debug)]
+pub struct OpeningCosts {
+    /// Vertex of each opening, in the order of the rows and columns of `costs`
+    pub openings: Vec<usize>,
+    /// `costs[i][j]` is the cost of the shortest path from opening `i` to
+    /// opening `j`, `None` if there is none. Symmetric unless the graph has
+    /// one-way edges.
+    pub costs: Vec<Vec<Option<f32>>>,
+}
+
+/// How an image is turned into a graph by `Graph::from_png_with_options`.
+#[derive(Clone, Debug, Default)]
+pub struct LoadOptions {
+    /// Seed for picking start/end at random when they can't be detected,
+    /// `None` seeds from the operating system
+    pub seed: Option<u64>,
+    /// Radius of the majority filter applied to the wall mask before building
+    /// the graph, see `Grid::majority_filter`. 0 disables the cleanup.
+    pub cleanup_radius: u32,
+    /// Thin passages wider than a pixel down to their centerline, see
+    /// `Grid::skeletonize`. Runs after the cleanup.
+    pub skeletonize: bool,
+    /// Also connect diagonally adjacent pixels, with a cost of √2, following
+    /// the given rule for corners. `None` only connects orthogonal neighbors.
+    /// Switches the graph's heuristic to `Heuristic::Octile`.
+    pub diagonals: Option<CornerPolicy>,
+    /// Price diagonal steps at 1 like orthogonal ones, the way a chess king
+    /// moves. Only matters together with `diagonals` and switches the
+    /// heuristic to `Heuristic::Chebyshev` instead.
+    pub king_moves: bool,
+    /// Keep one live vertex per open pixel instead of collapsing corridors
+    /// into single edges. The graph gets much bigger, but every vertex maps
+    /// to exactly one pixel, which grid based algorithms rely on.
+    pub skip_reduction: bool,
+    /// How `start` and `end` are chosen among the openings in the border
+    pub endpoints: EndpointStrategy,
+    /// Treat the edge of the image as a wall or as open space, for mazes
+    /// drawn without a frame. `None` finds openings from the border pixels
+    /// as `find_boundary_vertices` does.
+    pub border: Option<BorderMode>,
+    /// Find the vertex of a pixel by counting open pixels instead of through
+    /// a table with an entry per pixel, and size every neighbor list exactly.
+    /// Cuts peak memory on very large images at the cost of a slower build,
+    /// the resulting graph is the same.
+    pub low_memory: bool,
+    /// Which pixels are walls, by default the ones without any red.
+    #[cfg(feature = "image")]
+    pub walls: WallDetect,
+    /// Prices every step by a second image, `None` makes every step cost 1.
+    #[cfg(feature = "image")]
+    pub cost_map: Option<CostMap>,
+    /// Prices each step from the pixel it leaves and the one it enters,
+    /// instead of 1 for orthogonal steps and √2 (or 1 with `king_moves`) for
+    /// diagonal ones. A `cost_map` still multiplies on top. The A* style
+    /// solvers assume no step is cheaper than its default, lower weights
+    /// need a weaker `Graph::heuristic` to keep their paths optimal.
+    pub weight: Option<WeightFn>,
+    /// Directory to save a picture of each stage of building the graph to,
+    /// for seeing what reduction did to a maze. Walls are black, passages
+    /// gray and the vertices alive at that stage red: `1_vertices.png` has
+    /// one per open pixel, `2_neighbors.png` the ones that got an edge and
+    /// `3_reduced.png` the ones reduction kept. The directory is created if
+    /// needed.
+    #[cfg(feature = "image")]
+    pub debug_stages: Option<PathBuf>,
+    /// Keep the decoded image in the graph, so `Graph::draw_path_on_source`
+    /// can draw on it without reading the file again. Costs 3 bytes per
+    /// pixel for as long as the graph lives.
+    #[cfg(feature = "image")]
+    pub keep_source: bool,
+}
+
+/// Builds a graph from `LoadOptions` set one at a time, starting from the
+/// defaults `Graph::from_png` uses, so only the options that matter have to
+/// be spelled out, e.g.
+/// `GraphBuilder::new().diagonals(policy).seed(7).build_from_png(path)`.
+#[derive(Clone, Debug, Default)]
+pub struct GraphBuilder {
+    options: LoadOptions,
+}
+
+impl GraphBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts from `options` instead of the defaults.
+    pub fn from_options(options: LoadOptions) -> Self {
+        Self { options }
+    }
+
+    /// The options set so far.
+    pub fn options(&self) -> &LoadOptions {
+        &self.options
+    }
+
+    /// See `LoadOptions::seed`.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.options.seed = Some(seed);
+        self
+    }
+
+    /// See `LoadOptions::cleanup_radius`.
+    pub fn cleanup_radius(mut self, radius: u32) -> Self {
+        self.options.cleanup_radius = radius;
+        self
+    }
+
+    /// See `LoadOptions::skeletonize`.
+    pub fn skeletonize(mut self, skeletonize: bool) -> Self {
+        self.options.skeletonize = skeletonize;
+        self
+    }
+
+    /// Connects diagonal neighbors too, see `LoadOptions::diagonals`.
+    pub fn diagonals(mut self, policy: CornerPolicy) -> Self {
+        self.options.diagonals = Some(policy);
+        self
+    }
+
+    /// See `LoadOptions::king_moves`.
+    pub fn king_moves(mut self, king_moves: bool) -> Self {
+        self.options.king_moves = king_moves;
+        self
+    }
+
+    /// See `LoadOptions::skip_reduction`.
+    pub fn skip_reduction(mut self, skip: bool) -> Self {
+        self.options.skip_reduction = skip;
+        self
+    }
+
+    /// See `LoadOptions::endpoints`.
+    pub fn endpoints(mut self, endpoints: EndpointStrategy) -> Self {
+        self.options.endpoints = endpoints;
+        self
+    }
+
+    /// See `LoadOptions::border`.
+    pub fn border(mut self, border: BorderMode) -> Self {
+        self.options.border = Some(border);
+        self
+    }
+
+    /// See `LoadOptions::low_memory`.
+    pub fn low_memory(mut self, low_memory: bool) -> Self {
+        self.options.low_memory = low_memory;
+        self
+    }
+
+    /// See `LoadOptions::walls`.
+    #[cfg(feature = "image")]
+    pub fn walls(mut self, walls: WallDetect) -> Self {
+        self.options.walls = walls;
+        self
+    }
+
+    /// See `LoadOptions::cost_map`.
+    #[cfg(feature = "image")]
+    pub fn cost_map(mut self, cost_map: CostMap) -> Self {
+        self.options.cost_map = Some(cost_map);
+        self
+    }
+
+    /// See `LoadOptions::weight`.
+    pub fn weight(mut self, weight: WeightFn) -> Self {
+        self.options.weight = Some(weight);
+        self
+    }
+
+    /// See `LoadOptions::debug_stages`.
+    #[cfg(feature = "image")]
+    pub fn debug_stages<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.options.debug_stages = Some(dir.into());
+        self
+    }
+
+    /// See `LoadOptions::keep_source`.
+    #[cfg(feature = "image")]
+    pub fn keep_source(mut self, keep: bool) -> Self {
+        self.options.keep_source = keep;
+        self
+    }
+
+    /// Same as `Graph::from_png_with_options` with the options set so far.
+    #[cfg(feature = "image")]
+    pub fn build_from_png<P: AsRef<Path>>(&self, path: P) -> Result<Graph<Coord>, ImageError> {
+        Graph::from_png_with_options(path, &self.options)
+    }
+
+    /// Same as `Graph::from_grid` with the options set so far. The options
+    /// that only apply to images, such as `walls` and `cost_map`, are ignored.
+    pub fn build_from_grid(&self, grid: Grid) -> Result<Graph<Coord>, GraphError> {
+        Graph::from_grid(grid, &self.options)
+    }
+}
+
+/// A step weight computed from the two pixels of the step, see
+/// `LoadOptions::weight`.
+#[derive(Clone)]
+pub struct WeightFn(Arc<StepWeight>);
+
+type StepWeight = dyn Fn(&Coord, &Coord) -> f32 + Send + Sync;
+
+impl WeightFn {
+    pub fn new<F>(weight: F) -> Self
+    where
+        F: Fn(&Coord, &Coord) -> f32 + Send + Sync + 'static,
+    {
+        Self(Arc::new(weight))
+    }
+}
+
+impl fmt::Debug for WeightFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WeightFn")
+    }
+}
+
+/// A second image the size of the maze whose brightness sets the cost of
+/// stepping onto each pixel, for terrain where some passages are slower than
+/// others. Black costs 1 and white `max_cost`, with grays in between. Costs
+/// never drop below 1, so the distance heuristics stay admissible.
+#[cfg(feature = "image")]
+#[derive(Clone, Debug)]
+pub struct CostMap {
+    pub path: PathBuf,
+    pub max_cost: f32,
+}
+
+#[cfg(feature = "image")]
+impl CostMap {
+    pub fn new<P: AsRef<Path>>(path: P, max_cost: f32) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            max_cost,
+        }
+    }
+
+    /// The cost of every pixel, row by row. Fails if the image can't be
+    /// read, its size isn't `dimensions` or `max_cost` is below 1.
+    fn load(&self, dimensions: (u32, u32)) -> Result<Vec<f32>, ImageError> {
+        if !(self.max_cost.is_finite() && self.max_cost >= 1.0) {
+            return Err(parameter_error("cost map max_cost must be at least 1"));
+        }
+        let img = image::open(&self.path)?.into_luma8();
+        if img.dimensions() != dimensions {
+            return Err(ImageError::Parameter(ParameterError::from_kind(
+                ParameterErrorKind::DimensionMismatch,
+            )));
+        }
+        let scale = (self.max_cost - 1.0) / 255.0;
+        Ok(img
+            .pixels()
+            .map(|pixel| 1.0 + f32::from(pixel.0[0]) * scale)
+            .collect())
+    }
+}
+
+/// How pixels are told apart into walls and passages.
+#[cfg(feature = "image")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WallDetect {
+    /// Open when the red channel is at least the threshold. Black and white
+    /// mazes come out the same as with `Luminance`, but a solution drawn in
+    /// red on top of a maze stays passable. The default threshold of 1 opens
+    /// every pixel with any red at all.
+    RedChannel(u8),
+    /// Open when the (Rec. 601) luma is at least the threshold, for mazes with
+    /// dark walls of any hue.
+    Luminance(u8),
+    /// Open when the relative luminance is at least that of a gray pixel of
+    /// the threshold's value. Uses the Rec. 709 weights on linearized sRGB
+    /// channels, which judges saturated colors closer to how bright they look
+    /// than `Luminance` does, e.g. for photographed mazes drawn in color.
+    /// Grays are classified the same by both.
+    RelativeLuminance(u8),
+    /// Walls are the pixels with every channel within the tolerance of the
+    /// color, everything else is open. For mazes with walls of one color on a
+    /// background that isn't white.
+    Color(Rgb<u8>, u8),
+}
+
+#[cfg(feature = "image")]
+impl Default for WallDetect {
+    fn default() -> Self {
+        WallDetect::RedChannel(1)
     }
 }
 
-/// Finds the entry/exit points by scanning the image boundaries.
+#[cfg(feature = "image")]
+impl WallDetect {
+    /// Fully transparent pixels are open regardless of their color, since
+    /// `into_rgb8` would otherwise turn a transparent passage into a black wall.
+    fn is_open(self, pixel: &Rgba<u8>) -> bool {
+        let [red, green, blue, alpha] = pixel.0;
+        if alpha == 0 {
+            return true;
+        }
+        match self {
+            WallDetect::RedChannel(threshold) => red >= threshold,
+            WallDetect::Luminance(threshold) => {
+                let luma =
+                    (299 * u32::from(red) + 587 * u32::from(green) + 114 * u32::from(blue)) / 1000;
+                luma >= u32::from(threshold)
+            }
+            WallDetect::RelativeLuminance(threshold) => {
+                let linear = srgb_to_linear();
+                let luminance = 0.2126 * linear[usize::from(red)]
+                    + 0.7152 * linear[usize::from(green)]
+                    + 0.0722 * linear[usize::from(blue)];
+                // The weights add up to 1, so a gray's luminance is its
+                // linear value
+                luminance >= linear[usize::from(threshold)] * (1.0 - 1e-6)
+            }
+            WallDetect::Color(wall, tolerance) => [red, green, blue]
+                .iter()
+                .zip(wall.0)
+                .any(|(&channel, wall)| channel.abs_diff(wall) > tolerance),
+        }
+    }
+}
+
+/// Linear light intensity of every 8-bit sRGB channel value, from 0 to 1.
+#[cfg(feature = "image")]
+fn srgb_to_linear() -> &'static [f32; 256] {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        std::array::from_fn(|value| {
+            let encoded = value as f32 / 255.0;
+            if encoded <= 0.04045 {
+                encoded / 12.92
+            } else {
+                ((encoded + 0.055) / 1.055).powf(2.4)
+            }
+        })
+    })
+}
+
+/// Which border openings become `start` and `end`. Whatever the strategy,
+/// colored markers and finally a random pick are used when it can't decide.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EndpointStrategy {
+    /// Use the openings only if there are exactly two of them
+    #[default]
+    Boundary,
+    /// Use the two openings that are farthest apart by path cost, which gives
+    /// the longest solution when a maze has more than two. This runs Dijkstra
+    /// once per opening, so it gets slow on mazes with many openings.
+    MaxDiameter,
+}
+
+/// What lies beyond the edge of the image, see `LoadOptions::border`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BorderMode {
+    /// A wall all around, so the border has no openings however its pixels
+    /// look. `start` and `end` come from colored markers, or at random.
+    Wall,
+    /// Open space all around, so every open border pixel is an opening of
+    /// its own. There are usually many, pair this with
+    /// `EndpointStrategy::MaxDiameter` or markers to pick two of them.
+    Open,
+}
+
+/// When a diagonal step past the corner of a wall is allowed. The two pixels
+/// orthogonally next to both ends of the step decide it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CornerPolicy {
+    /// Every diagonal step between two open pixels
+    Always,
+    /// Only if both orthogonal pixels are open, so a path never clips a wall
+    NoCornerCutting,
+    /// Unless both orthogonal pixels are walls, which would mean squeezing
+    /// through the gap where two walls touch at their corners
+    NoSqueezing,
+}
+
+/// Appearance of the image produced by `Graph::draw_path`.
+#[cfg(feature = "image")]
+#[derive(Clone, Debug)]
+pub struct DrawOptions {
+    /// Every maze pixel becomes a `scale`x`scale` block in the output
+    pub scale: u32,
+    /// Half the side length of the square start/end markers, 0 draws a single pixel
+    pub marker_radius: u32,
+    pub path_color: Rgb<u8>,
+    pub start_color: Rgb<u8>,
+    pub end_color: Rgb<u8>,
+    /// Color of the vertices a search visited, see `Graph::trace_frames`
+    pub visited_color: Rgb<u8>,
+    /// Color of a 1px border drawn around the path, so it stands out against
+    /// busy backgrounds. The border only covers pixels next to the path, never
+    /// the path itself. `None` draws no border.
+    pub outline_color: Option<Rgb<u8>>,
+    /// Save an RGBA image with only what is drawn (path, outline, markers
+    /// and label) on a fully transparent background, for overlaying it on
+    /// the maze elsewhere. The original image only decides the size.
+    pub transparent_background: bool,
+    /// Text written in the top left corner, e.g. the algorithm and path cost.
+    /// Drawn white on black so it reads over walls and passages alike, and
+    /// cut off where the image ends.
+    pub label: Option<String>,
+}
+
+#[cfg(feature = "image")]
+impl Default for DrawOptions {
+    fn default() -> Self {
+        Self {
+            scale: 1,
+            marker_radius: 1,
+            path_color: Rgb([255, 0, 0]),
+            start_color: Rgb([0, 255, 0]),
+            end_color: Rgb([0, 0, 255]),
+            visited_color: Rgb([255, 200, 120]),
+            outline_color: None,
+            transparent_background: false,
+            label: None,
+        }
+    }
+}
+
+/// Finds the entry/exit points by scanning the image boundaries. The border
+/// is walked once as a loop (clockwise from the top left corner), so every
+/// border pixel, corners included, is looked at exactly once. Neighboring
+/// open pixels along the loop form a single opening, which matters at the
+/// corners: an opening in a corner always spans two sides, since the corner
+/// pixel's only neighbors are border pixels too. Each opening gives one
+/// vertex, its corner pixel if it has one and otherwise its middle pixel.
 pub fn find_boundary_vertices(vertices: &[Vertex<Coord>], width: u32, height: u32) -> Vec<usize> {
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
     let pos_map: HashMap<(u32, u32), usize> = vertices
         .iter()
         .enumerate()
         .map(|(i, v)| ((v.pos.x, v.pos.y), i))
         .collect();
 
-    let mut boundary_indices = Vec::new();
+    let (right, bottom) = (width - 1, height - 1);
+    let border = border_loop(width, height);
+
+    // Runs of open pixels, a run still open at the end of the loop continues
+    // the one it started with
+    let mut runs: Vec<Vec<(u32, u32)>> = Vec::new();
+    let mut previous_open = false;
+    for &pos in &border {
+        let open = pos_map.contains_key(&pos);
+        if open {
+            match runs.last_mut() {
+                Some(run) if previous_open => run.push(pos),
+                _ => runs.push(vec![pos]),
+            }
+        }
+        previous_open = open;
+    }
+    let wraps = runs.len() > 1 && pos_map.contains_key(&border[0]) && previous_open;
+    if wraps {
+        let mut first = runs.remove(0);
+        runs.last_mut().unwrap().append(&mut first);
+    }
+
+    let is_corner = |&(x, y): &(u32, u32)| (x == 0 || x == right) && (y == 0 || y == bottom);
+    let mut openings: Vec<(u32, u32)> = runs
+        .iter()
+        .map(|run| {
+            *run.iter()
+                .find(|pos| is_corner(pos))
+                .unwrap_or(&run[run.len() / 2])
+        })
+        .collect();
+    sort_openings(&mut openings, bottom);
+    openings.iter().map(|pos| pos_map[pos]).collect()
+}
+
+/// The openings under `mode`, see `LoadOptions::border`.
+fn border_openings(
+    vertices: &[Vertex<Coord>],
+    width: u32,
+    height: u32,
+    mode: Option<BorderMode>,
+) -> Vec<usize> {
+    match mode {
+        None => find_boundary_vertices(vertices, width, height),
+        Some(BorderMode::Wall) => Vec::new(),
+        Some(BorderMode::Open) if width == 0 || height == 0 => Vec::new(),
+        Some(BorderMode::Open) => {
+            let pos_map: HashMap<(u32, u32), usize> = vertices
+                .iter()
+                .enumerate()
+                .map(|(i, v)| ((v.pos.x, v.pos.y), i))
+                .collect();
+            let mut openings: Vec<(u32, u32)> = border_loop(width, height)
+                .into_iter()
+                .filter(|pos| pos_map.contains_key(pos))
+                .collect();
+            sort_openings(&mut openings, height - 1);
+            openings.iter().map(|pos| pos_map[pos]).collect()
+        }
+    }
+}
+
+/// Every border pixel once, clockwise from the top left corner.
+fn border_loop(width: u32, height: u32) -> Vec<(u32, u32)> {
+    let (right, bottom) = (width - 1, height - 1);
+    let mut border: Vec<(u32, u32)> = (0..width).map(|x| (x, 0)).collect();
+    if bottom > 0 {
+        border.extend((1..height).map(|y| (right, y)));
+        if right > 0 {
+            border.extend((0..right).rev().map(|x| (x, bottom)));
+            border.extend((1..bottom).rev().map(|y| (0, y)));
+        }
+    }
+    border
+}
+
+/// Top and bottom row by x first, then the side columns by y. The first
+/// opening becomes `start`, this keeps the order the endpoints always had.
+fn sort_openings(openings: &mut [(u32, u32)], bottom: u32) {
+    openings.sort_by_key(|&(x, y)| {
+        if y == 0 || y == bottom {
+            (0, x, y)
+        } else {
+            (1, y, x)
+        }
+    });
+}
+
+/// Looks for colored markers on the image border. Border pixels that aren't a
+/// shade of gray are grouped by (coarsely quantized) color and the rarest
+/// groups are taken as markers, so two orange cells in a black and white
+/// frame stand out. Of the candidate pixels the two furthest apart are used,
+/// which keeps a multi-pixel marker from providing both endpoints. Returns
+/// `None` unless both endpoints map to vertices that have edges.
+#[cfg(feature = "image")]
+fn find_marker_vertices<F>(
+    img: &RgbaImage,
+    vertices: &[Vertex<Coord>],
+    lookup: F,
+) -> Option<[usize; 2]>
+where
+    F: Fn(&Coord) -> Option<usize>,
+{
+    let (width, height) = img.dimensions();
+    if width == 0 || height == 0 {
+        return None;
+    }
 
+    let mut border = Vec::new();
     for x in 0..width {
-        if let Some(&idx) = pos_map.get(&(x, 0)) {
-            boundary_indices.push(idx);
+        border.push(Coord { x, y: 0 });
+        border.push(Coord { x, y: height - 1 });
+    }
+    for y in 1..height.saturating_sub(1) {
+        border.push(Coord { x: 0, y });
+        border.push(Coord { x: width - 1, y });
+    }
+
+    // Groups in order of first appearance so ties between equally rare colors are deterministic
+    let mut groups: Vec<([u8; 3], Vec<Coord>)> = Vec::new();
+    for pos in border {
+        let [r, g, b, alpha] = img.get_pixel(pos.x, pos.y).0;
+        let chroma = r.max(g).max(b) - r.min(g).min(b);
+        if alpha == 0 || chroma < 64 {
+            continue;
         }
-        if let Some(&idx) = pos_map.get(&(x, height - 1)) {
-            boundary_indices.push(idx);
+        let key = [r / 32, g / 32, b / 32];
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, pixels)) => pixels.push(pos),
+            None => groups.push((key, vec![pos])),
         }
     }
+    groups.sort_by_key(|(_, pixels)| pixels.len());
 
-    for y in 1..height - 1 {
-        if let Some(&idx) = pos_map.get(&(0, y)) {
-            boundary_indices.push(idx);
+    let mut candidates: Vec<usize> = Vec::new();
+    for (_, pixels) in &groups {
+        candidates.extend(
+            pixels
+                .iter()
+                .filter_map(&lookup)
+                .filter(|&i| !vertices[i].neighbors.is_empty()),
+        );
+        if candidates.len() >= 2 {
+            break;
         }
-        if let Some(&idx) = pos_map.get(&(width - 1, y)) {
-            boundary_indices.push(idx);
+    }
+
+    let mut best: Option<(f32, [usize; 2])> = None;
+    for (i, &a) in candidates.iter().enumerate() {
+        for &b in &candidates[i + 1..] {
+            let dist = vertices[a].pos.euclidean_distance(&vertices[b].pos);
+            if best.is_none_or(|(best_dist, _)| dist > best_dist) {
+                best = Some((dist, [a, b]));
+            }
         }
     }
+    best.map(|(_, pair)| pair)
+}
+
+/// Picks two distinct endpoints, preferring boundary openings and falling back
+/// to any vertex that survived reduction when there are fewer than two of them.
+fn pick_random_endpoints<T>(
+    vertices: &[Vertex<T>],
+    boundary: &[usize],
+    rng: &mut StdRng,
+) -> Vec<usize> {
+    let candidates: Vec<usize> = if boundary.len() >= 2 {
+        boundary.to_vec()
+    } else {
+        let live: Vec<usize> = (0..vertices.len())
+            .filter(|&i| !vertices[i].neighbors.is_empty())
+            .collect();
+        if live.len() >= 2 {
+            live
+        } else {
+            (0..vertices.len()).collect()
+        }
+    };
+
+    let amount = candidates.len().min(2);
+    rand::seq::index::sample(rng, candidates.len(), amount)
+        .into_iter()
+        .map(|i| candidates[i])
+        .collect()
+}
+
+/// Recovers the pixels that reduction collapsed into each edge. Starting from
+/// every live vertex it walks each open direction along the corridor until it
+/// hits another live vertex. Corridor pixels have exactly two reachable neighbors,
+/// so the walk never has to choose. Parallel edges keep the shortest corridor.
+fn trace_edge_pixels<F>(
+    vertices: &[Vertex<Coord>],
+    grid: &Grid,
+    diagonals: Option<CornerPolicy>,
+    lookup: F,
+) -> HashMap<(usize, usize), Vec<Coord>>
+where
+    F: Fn(&Coord) -> Option<usize>,
+{
+    let live = |pos: &Coord| lookup(pos).filter(|&i| !vertices[i].neighbors.is_empty());
+    let open_neighbors = |pos: &Coord| {
+        grid.open_neighbors(pos, diagonals)
+            .into_iter()
+            .map(|(n, _)| n)
+            .collect::<Vec<_>>()
+    };
+
+    let mut edge_pixels: HashMap<(usize, usize), Vec<Coord>> = HashMap::new();
+    for (a, vertex) in vertices.iter().enumerate() {
+        if vertex.neighbors.is_empty() {
+            continue;
+        }
+
+        for first in open_neighbors(&vertex.pos) {
+            let mut pixels = vec![vertex.pos, first];
+            let b = loop {
+                let current = pixels[pixels.len() - 1];
+                if let Some(b) = live(&current) {
+                    break Some(b);
+                }
+                let previous = pixels[pixels.len() - 2];
+                let next: Vec<Coord> = open_neighbors(&current)
+                    .into_iter()
+                    .filter(|n| *n != previous)
+                    .collect();
+                if next.len() != 1 {
+                    break None;
+                }
+                pixels.push(next[0]);
+            };
 
-    boundary_indices
+            if let Some(b) = b {
+                let shorter = edge_pixels
+                    .get(&(a, b))
+                    .is_none_or(|known| pixels.len() < known.len());
+                if shorter {
+                    edge_pixels.insert((a, b), pixels);
+                }
+            }
+        }
+    }
+    edge_pixels
 }
 
+#[cfg(feature = "image")]
 fn draw_line(img: &mut image::RgbImage, x0: u32, y0: u32, x1: u32, y1: u32, color: image::Rgb<u8>) {
-    let mut x0 = x0 as i32;
-    let mut y0 = y0 as i32;
-    let x1 = x1 as i32;
-    let y1 = y1 as i32;
+    let mut x0 = i64::from(x0);
+    let mut y0 = i64::from(y0);
+    let x1 = i64::from(x1);
+    let y1 = i64::from(y1);
 
     let dx = (x1 - x0).abs();
     let dy = -(y1 - y0).abs();
@@ -188,7 +2956,7 @@ fn draw_line(img: &mut image::RgbImage, x0: u32, y0: u32, x1: u32, y1: u32, colo
     let mut err = dx + dy;
 
     loop {
-        if x0 >= 0 && x0 < img.width() as i32 && y0 >= 0 && y0 < img.height() as i32 {
+        if x0 >= 0 && x0 < i64::from(img.width()) && y0 >= 0 && y0 < i64::from(img.height()) {
             img.put_pixel(x0 as u32, y0 as u32, color);
         }
         if x0 == x1 && y0 == y1 {
@@ -206,48 +2974,278 @@ fn draw_line(img: &mut image::RgbImage, x0: u32, y0: u32, x1: u32, y1: u32, colo
     }
 }
 
-fn create_vertices(img: &RgbImage) -> Vec<Vertex<Coord>> {
-    img.enumerate_pixels()
-        .filter(|(_, _, pixel)| pixel.0[0] != 0)
-        .map(|(x, y, _)| Vertex {
-            pos: Coord { x, y },
-            neighbors: Vec::with_capacity(4),
+#[cfg(feature = "image")]
+fn draw_label(img: &mut RgbImage, text: &str) {
+    let padding = 1;
+    let advance = font::GLYPH_WIDTH + 1;
+    let text_width = (text.chars().count() as u32 * advance).saturating_sub(1);
+    let box_width = (text_width + 2 * padding).min(img.width());
+    let box_height = (font::GLYPH_HEIGHT + 2 * padding).min(img.height());
+
+    for y in 0..box_height {
+        for x in 0..box_width {
+            img.put_pixel(x, y, Rgb([0, 0, 0]));
+        }
+    }
+
+    for (i, c) in text.chars().enumerate() {
+        let left = padding + i as u32 * advance;
+        for (row, bits) in font::glyph(c).iter().enumerate() {
+            for col in 0..font::GLYPH_WIDTH {
+                let x = left + col;
+                let y = padding + row as u32;
+                let set = bits & (1 << (font::GLYPH_WIDTH - 1 - col)) != 0;
+                if set && x < img.width() && y < img.height() {
+                    img.put_pixel(x, y, Rgb([255, 255, 255]));
+                }
+            }
+        }
+    }
+}
+
+/// A color for the `index`th component. Hues are spread by the golden angle so
+/// components found one after the other look clearly different, and the fixed
+/// saturation and brightness keep every color apart from black walls and
+/// white passages.
+#[cfg(feature = "image")]
+fn component_color(index: usize) -> Rgb<u8> {
+    let hue = (index as f32 * 0.618_034).fract() * 6.0;
+    let (saturation, value) = (0.7, 0.9);
+    let chroma = value * saturation;
+    let rising = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, rising, 0.0),
+        1 => (rising, chroma, 0.0),
+        2 => (0.0, chroma, rising),
+        3 => (0.0, rising, chroma),
+        4 => (rising, 0.0, chroma),
+        _ => (chroma, 0.0, rising),
+    };
+    let lightest = value - chroma;
+    let channel = |c: f32| ((c + lightest) * 255.0).round() as u8;
+    Rgb([channel(r), channel(g), channel(b)])
+}
+
+/// Points while building a graph that `LoadOptions::debug_stages` saves a
+/// picture of.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    /// One vertex per open pixel
+    Vertices,
+    /// Neighbors found, diagonals included
+    Neighbors,
+    /// Corridors collapsed into edges
+    Reduced,
+}
+
+/// Saves the `LoadOptions::debug_stages` picture of `stage` into `dir`, with
+/// the vertices alive at that point in red.
+#[cfg(feature = "image")]
+fn save_stage(
+    dir: &Path,
+    stage: Stage,
+    grid: &Grid,
+    vertices: &[Vertex<Coord>],
+) -> Result<(), ImageError> {
+    let name = match stage {
+        Stage::Vertices => {
+            std::fs::create_dir_all(dir)?;
+            "1_vertices.png"
+        }
+        Stage::Neighbors => "2_neighbors.png",
+        Stage::Reduced => "3_reduced.png",
+    };
+    // Before neighbors are found every vertex counts as alive
+    let alive = |vertex: &Vertex<Coord>| stage == Stage::Vertices || !vertex.neighbors.is_empty();
+    let mut img = RgbImage::from_fn(grid.width, grid.height, |x, y| {
+        if grid.is_open(x, y) {
+            Rgb([128, 128, 128])
+        } else {
+            Rgb([0, 0, 0])
+        }
+    });
+    for vertex in vertices.iter().filter(|vertex| alive(vertex)) {
+        img.put_pixel(vertex.pos.x, vertex.pos.y, Rgb([255, 0, 0]));
+    }
+    img.save(dir.join(name))
+}
+
+#[cfg(feature = "image")]
+fn draw_marker(img: &mut RgbImage, center: &Coord, radius: u32, color: Rgb<u8>) {
+    let x_max = center.x.saturating_add(radius).min(img.width() - 1);
+    let y_max = center.y.saturating_add(radius).min(img.height() - 1);
+    for x in center.x.saturating_sub(radius)..=x_max {
+        for y in center.y.saturating_sub(radius)..=y_max {
+            img.put_pixel(x, y, color);
+        }
+    }
+}
+
+/// Vertices are created for the open pixels in row-major order, so the index
+/// of a pixel's vertex is the number of open pixels before it. This keeps a
+/// running count every `OPEN_RANK_BLOCK` pixels and counts the rest on demand.
+struct OpenRank {
+    block_starts: Vec<usize>,
+}
+
+const OPEN_RANK_BLOCK: usize = 64;
+
+impl OpenRank {
+    fn new(grid: &Grid) -> Self {
+        let mut open_before = 0;
+        let block_starts = grid
+            .open
+            .chunks(OPEN_RANK_BLOCK)
+            .map(|block| {
+                let start = open_before;
+                open_before += block.iter().filter(|open| **open).count();
+                start
+            })
+            .collect();
+        Self { block_starts }
+    }
+
+    /// `pos` must be inside the grid.
+    fn index(&self, grid: &Grid, pos: &Coord) -> Option<usize> {
+        let idx = (pos.y * grid.width + pos.x) as usize;
+        if !grid.open[idx] {
+            return None;
+        }
+        let block = idx / OPEN_RANK_BLOCK;
+        let in_block = grid.open[block * OPEN_RANK_BLOCK..idx]
+            .iter()
+            .filter(|open| **open)
+            .count();
+        Some(self.block_starts[block] + in_block)
+    }
+}
+
+/// One vertex per open pixel in row-major order. With `exact_capacity` each
+/// neighbor list is allocated for exactly the neighbors it will get, which is
+/// slower but avoids reserving four slots for every corridor pixel.
+fn create_vertices(
+    grid: &Grid,
+    diagonals: Option<CornerPolicy>,
+    exact_capacity: bool,
+) -> Vec<Vertex<Coord>> {
+    (0..grid.height)
+        .flat_map(|y| (0..grid.width).map(move |x| Coord { x, y }))
+        .filter(|pos| grid.is_open(pos.x, pos.y))
+        .map(|pos| {
+            let capacity = if exact_capacity {
+                grid.open_neighbors(&pos, diagonals).len()
+            } else {
+                4
+            };
+            Vertex {
+                pos,
+                neighbors: Vec::with_capacity(capacity),
+            }
         })
         .collect()
 }
 
-fn populate_vertex_neighbors<T, F>(vertices: &mut Vec<Vertex<T>>, lookup: F)
+/// Connects every vertex to the neighbors `lookup` finds, `weight` prices the
+/// step from a vertex's position to its neighbor's.
+fn populate_vertex_neighbors<T, F, W>(vertices: &mut [Vertex<T>], lookup: F, weight: W)
 where
     T: Adjacent + Clone,
     F: Fn(&T) -> Option<usize>,
+    W: Fn(&T, &T) -> f32,
 {
     for vertex in vertices.iter_mut() {
         for potential_neighbor in vertex.pos.potential_neighbors() {
             if let Some(neighbor_idx) = lookup(&potential_neighbor) {
-                vertex.neighbors.push((neighbor_idx, 1.0));
+                let weight = weight(&vertex.pos, &potential_neighbor);
+                vertex.neighbors.push((neighbor_idx, weight));
+            }
+        }
+    }
+}
+
+fn add_diagonal_neighbors<F, W>(
+    vertices: &mut [Vertex<Coord>],
+    grid: &Grid,
+    policy: CornerPolicy,
+    lookup: F,
+    weight: W,
+) where
+    F: Fn(&Coord) -> Option<usize>,
+    W: Fn(&Coord, &Coord) -> f32,
+{
+    for vertex in vertices.iter_mut() {
+        for (neighbor, _) in grid.open_neighbors(&vertex.pos, Some(policy)) {
+            let diagonal = neighbor.x != vertex.pos.x && neighbor.y != vertex.pos.y;
+            if diagonal && let Some(neighbor_idx) = lookup(&neighbor) {
+                vertex
+                    .neighbors
+                    .push((neighbor_idx, weight(&vertex.pos, &neighbor)));
             }
         }
     }
 }
 
-fn reduce_vertex_count<T>(vertices: &mut Vec<Vertex<T>>) {
+/// Vertices that still have edges, the ones reduction emptied out don't count.
+fn count_alive<T>(vertices: &[Vertex<T>]) -> usize {
+    vertices
+        .iter()
+        .filter(|vertex| !vertex.neighbors.is_empty())
+        .count()
+}
+
+/// Collapses vertices with exactly two neighbors into a single edge, except
+/// for the ones listed in `keep`.
+fn reduce_vertex_count<T>(vertices: &mut [Vertex<T>], keep: &[usize]) {
+    let mut kept = vec![false; vertices.len()];
+    for &i in keep {
+        kept[i] = true;
+    }
+    // Merging a vertex away moves its edges over to its neighbors, so every
+    // other vertex keeps the number of edges leading into it
+    let mut in_degree = vec![0; vertices.len()];
+    for vertex in vertices.iter() {
+        for &(idx, _) in &vertex.neighbors {
+            in_degree[idx] += 1;
+        }
+    }
+
     for i in 0..vertices.len() {
+        if kept[i] {
+            continue;
+        }
         // If a vertex only connects two other vertexes then it is redundant
         // We remove it by connecting the two other vertices directly
-        // Note that this would not work if we had a 2x2 square
         let vertex = &vertices[i];
         if vertex.neighbors.len() == 2 {
             // Connect the two neighbors together
             let (idx_a, weight_a) = vertex.neighbors[0];
             let (idx_b, weight_b) = vertex.neighbors[1];
 
-            // Update the vertices to point at eachother
+            // Both edges have to be two-way and nothing else may lead in,
+            // otherwise merging would lose which way the passage goes
+            let two_way = |idx: usize| vertices[idx].neighbors.iter().any(|(n, _)| *n == i);
+            if in_degree[i] != 2 || !two_way(idx_a) || !two_way(idx_b) {
+                continue;
+            }
+
+            // In a 2x2 square (or a triangle of diagonal steps) the two
+            // neighbors already share an edge, merging would add a second one
+            // between them and eventually fold the loop into a self-loop
+            let adjacent =
+                |from: usize, to: usize| vertices[from].neighbors.iter().any(|(n, _)| *n == to);
+            if idx_a == idx_b || adjacent(idx_a, idx_b) || adjacent(idx_b, idx_a) {
+                continue;
+            }
+
+            // Update the vertices to point at eachother. Each direction
+            // costs its own way in plus the other edge's way out, which only
+            // differ when steps are priced by a cost map
             if let Some(edge) = vertices[idx_a]
                 .neighbors
                 .iter_mut()
                 .find(|(idx, _)| *idx == i)
             {
-                *edge = (idx_b, weight_a + weight_b);
+                *edge = (idx_b, edge.1 + weight_b);
             }
 
             if let Some(edge) = vertices[idx_b]
@@ -255,10 +3253,36 @@ fn reduce_vertex_count<T>(vertices: &mut Vec<Vertex<T>>) {
                 .iter_mut()
                 .find(|(idx, _)| *idx == i)
             {
-                *edge = (idx_a, weight_b + weight_b);
+                *edge = (idx_a, edge.1 + weight_a);
             }
 
             vertices[i].neighbors.clear();
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduction_keeps_one_way_passages() {
+        // 0 <-> 1 -> 2 <-> 3 along a row, 1 and 2 both have two neighbors
+        let mut vertices: Vec<Vertex<Coord>> = (0..4)
+            .map(|x| Vertex {
+                pos: Coord::new(x, 0),
+                neighbors: Vec::new(),
+            })
+            .collect();
+        for (from, to) in [(0, 1), (1, 0), (1, 2), (2, 3), (3, 2)] {
+            vertices[from].neighbors.push((to, 1.0));
+        }
+        reduce_vertex_count(&mut vertices, &[0, 3]);
+
+        let edges: Vec<Vec<usize>> = vertices
+            .iter()
+            .map(|vertex| vertex.neighbors.iter().map(|(to, _)| *to).collect())
+            .collect();
+        assert_eq!(edges, [vec![1], vec![0, 2], vec![3], vec![2]]);
+    }
+}
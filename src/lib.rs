@@ -0,0 +1,4 @@
+#[cfg(feature = "image")]
+mod font;
+pub mod graph;
+pub mod pathfinding;
@@ -1,47 +1,234 @@
 use std::env;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
-mod graph;
-mod pathfinding;
-use pathfinding::PathfindingAlgorithm;
+use maze_solving::graph;
+use maze_solving::pathfinding::{self, PathfindingAlgorithm};
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let tot_runtime = Instant::now();
+const USAGE: &str =
+    "Usage: maze-solving <path-to-maze-image>... [--algo <name>[,<name>...]]... [--draw]
+                    [--start <x>,<y>] [--end <x>,<y>]
+
+  Every maze is solved in turn, a failure is reported and the rest still run.
+  Pass - as a path to read the image from stdin. PNG, BMP, JPEG, GIF, TIFF,
+  WebP and the other formats of the image crate are accepted, the format is
+  detected from the contents.
+
+  --algo  Algorithm(s) to run: bfs, dfs, dijkstra, astar, iddfs, idastar,
+          bellmanford, tremaux.
+          Defaults to bfs, dfs and dijkstra.
+  --draw  Save the path of the first selected algorithm to <name>_solved.png
+          next to each maze. Without --algo the dijkstra path is always drawn.
+  --start Solve from the vertex nearest to this pixel instead of the opening
+          found in the border, walking around walls. Has to lie inside
+          the image.
+  --end   Solve to the vertex nearest to this pixel, like --start.";
+
+struct Options {
+    filenames: Vec<String>,
+    solvers: Vec<PathfindingAlgorithm>,
+    draw: Option<PathfindingAlgorithm>,
+    start: Option<(u32, u32)>,
+    end: Option<(u32, u32)>,
+}
+
+/// Parses the `x,y` value of `--start` or `--end`.
+fn parse_point(flag: &str, value: Option<&String>) -> Result<(u32, u32), String> {
+    let value = value.ok_or(format!("{flag} expects a pixel as x,y"))?;
+    let point = value
+        .split_once(',')
+        .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)));
+    point.ok_or(format!("{flag} expects a pixel as x,y, got '{value}'"))
+}
+
+fn parse_args(args: &[String]) -> Result<Options, Box<dyn std::error::Error>> {
+    let mut filenames = Vec::new();
+    let mut solvers = Vec::new();
+    let mut draw = false;
+    let mut start = None;
+    let mut end = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--algo" => {
+                let names = args.next().ok_or("--algo expects an algorithm name")?;
+                for name in names.split(',') {
+                    solvers.push(name.parse()?);
+                }
+            }
+            "--draw" => draw = true,
+            "--start" => start = Some(parse_point(arg, args.next())?),
+            "--end" => end = Some(parse_point(arg, args.next())?),
+            flag if flag.starts_with("--") => return Err(format!("unknown flag '{flag}'").into()),
+            _ => filenames.push(arg.clone()),
+        }
+    }
 
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 2 {
-        println!("Usage: maze-solving <path-to-maze-png>");
-        std::process::exit(1);
+    if filenames.is_empty() {
+        return Err("missing path to maze".into());
+    }
+    if solvers.is_empty() {
+        return Ok(Options {
+            filenames,
+            solvers: vec![
+                PathfindingAlgorithm::BreadthFirst,
+                PathfindingAlgorithm::DepthFirst,
+                PathfindingAlgorithm::Dijkstra,
+            ],
+            draw: Some(PathfindingAlgorithm::Dijkstra),
+            start,
+            end,
+        });
     }
 
-    let filename = &args[1];
+    let draw = draw.then(|| solvers[0]);
+    Ok(Options {
+        filenames,
+        solvers,
+        draw,
+        start,
+        end,
+    })
+}
+
+/// How many steps through open pixels `--start` and `--end` may be from the
+/// vertex they snap to.
+const SNAP_RADIUS: u32 = 64;
+
+/// The vertex nearest to the pixel given to `flag` without crossing walls,
+/// see `Graph::snap_to_vertex`. The pixel has to lie inside the image the
+/// graph was built from.
+fn snap_point(
+    g: &graph::Graph<graph::Coord>,
+    flag: &str,
+    (x, y): (u32, u32),
+) -> Result<usize, Box<dyn std::error::Error>> {
+    if let Some(grid) = g.get_grid()
+        && (x >= grid.width() || y >= grid.height())
+    {
+        return Err(format!(
+            "{flag} {x},{y} is outside the {}x{} image",
+            grid.width(),
+            grid.height()
+        )
+        .into());
+    }
+    let vertex = g
+        .snap_to_vertex(&graph::Coord::new(x, y), SNAP_RADIUS)
+        .ok_or_else(|| {
+            format!("{flag} {x},{y} has no open vertex within {SNAP_RADIUS} steps to snap to")
+        })?;
+    println!(
+        "{flag} {x},{y} snapped to {}",
+        g.get_vertices()[vertex].get_pos()
+    );
+    Ok(vertex)
+}
+
+/// `dir/maze.png` is drawn to `dir/maze_solved.png`, stdin to `solved_maze.png`.
+fn output_path(filename: &str) -> PathBuf {
+    if filename == "-" {
+        return PathBuf::from("solved_maze.png");
+    }
+    let path = Path::new(filename);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{stem}_solved.png"))
+}
+
+fn solve_file(filename: &str, options: &Options) -> Result<(), Box<dyn std::error::Error>> {
     let graph_create_now = Instant::now();
-    let g = graph::Graph::from_png(filename)?;
+    let stdin_bytes = if filename == "-" {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes)?;
+        Some(bytes)
+    } else {
+        None
+    };
+    let mut g = match &stdin_bytes {
+        Some(bytes) => graph::Graph::from_bytes(bytes)?,
+        None => {
+            // Drawing reuses the decoded image instead of reading the file again
+            let load_options = graph::LoadOptions {
+                keep_source: options.draw.is_some(),
+                ..graph::LoadOptions::default()
+            };
+            let (graph, format) = graph::Graph::from_image_with_format(filename, &load_options)?;
+            println!("Decoded {filename} as {format:?}");
+            graph
+        }
+    };
+    g.compact();
     println!(
         "Graph creation took {}ms",
         graph_create_now.elapsed().as_millis()
     );
+    if let Some(point) = options.start {
+        g.start = snap_point(&g, "--start", point)?;
+    }
+    if let Some(point) = options.end {
+        g.end = snap_point(&g, "--end", point)?;
+    }
 
-    let solvers = &[
-        PathfindingAlgorithm::BreadthFirst,
-        PathfindingAlgorithm::DepthFirst,
-        PathfindingAlgorithm::Dijkstra,
-    ];
-
-    for solver in solvers {
+    for solver in &options.solvers {
         let graph_solve = Instant::now();
-        let result = pathfinding::solve_graph(&g, solver).unwrap();
+        let result = pathfinding::solve_graph(&g, solver)?
+            .ok_or_else(|| format!("{solver:?} found no path"))?;
         println!(
-            "Graph solved using {solver:?} took {}ms with cost {}",
+            "Graph solved using {solver:?} took {}ms with cost {} ({} hops, {} pixels)",
             graph_solve.elapsed().as_millis(),
-            pathfinding::calculate_cost(&g, &result)
+            pathfinding::calculate_cost(&g, &result),
+            pathfinding::path_hops(&result),
+            pathfinding::path_pixel_length(&g, &result)
         );
 
-        if let PathfindingAlgorithm::Dijkstra = solver {
-            g.draw_path(&result, filename)?;
+        if options.draw == Some(*solver) {
+            let draw_options = graph::DrawOptions {
+                label: Some(format!(
+                    "{solver:?} {}",
+                    pathfinding::calculate_cost(&g, &result)
+                )),
+                ..graph::DrawOptions::default()
+            };
+            let output = output_path(filename);
+            match &stdin_bytes {
+                Some(bytes) => {
+                    let img = image::load_from_memory(bytes)?.into_rgb8();
+                    g.draw_path_on(&result, img, &output, &draw_options)?;
+                }
+                None => g.draw_path_on_source(&result, &output, &draw_options)?,
+            }
+            println!("Path drawn to {}", output.display());
+        }
+    }
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let tot_runtime = Instant::now();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(err) => {
+            println!("{err}\n\n{USAGE}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut failed = 0;
+    for filename in &options.filenames {
+        println!("== {filename} ==");
+        if let Err(err) = solve_file(filename, &options) {
+            println!("Failed to solve {filename}: {err}");
+            failed += 1;
         }
     }
 
     println!("Total runtime was {}ms", tot_runtime.elapsed().as_millis());
+    if failed > 0 {
+        return Err(format!("{failed} of {} mazes failed", options.filenames.len()).into());
+    }
     Ok(())
 }
@@ -1,129 +1,2685 @@
-use crate::graph::Graph;
+use crate::graph::{Coord, Distance, Graph, Heuristic};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, VecDeque};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+use std::time::Instant;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PathfindingAlgorithm {
     DepthFirst,
     BreadthFirst,
     Dijkstra,
+    /// A* guided by the graph's heuristic, Manhattan distance to `end` unless
+    /// the graph was loaded with diagonals. Searches in plain Dijkstra order
+    /// when some edge is cheaper than the heuristic's estimate for it.
+    AStar,
+    /// Depth-first searches with a growing depth limit, giving the path with
+    /// the fewest hops. Each round only goes deeper into a vertex it reached
+    /// in fewer hops than before, so a round costs O(V * E) at worst and
+    /// usually close to O(E), and there are as many rounds as hops in the
+    /// path. Much slower than `BreadthFirst` on large mazes all the same.
+    IterativeDeepening,
+    IdaStar,
+    BellmanFord,
+    Tremaux,
+}
+
+/// Names accepted by `PathfindingAlgorithm::from_str`.
+pub const ALGORITHM_NAMES: &[(&str, PathfindingAlgorithm)] = &[
+    ("bfs", PathfindingAlgorithm::BreadthFirst),
+    ("dfs", PathfindingAlgorithm::DepthFirst),
+    ("dijkstra", PathfindingAlgorithm::Dijkstra),
+    ("astar", PathfindingAlgorithm::AStar),
+    ("iddfs", PathfindingAlgorithm::IterativeDeepening),
+    ("idastar", PathfindingAlgorithm::IdaStar),
+    ("bellmanford", PathfindingAlgorithm::BellmanFord),
+    ("tremaux", PathfindingAlgorithm::Tremaux),
+];
+
+impl PathfindingAlgorithm {
+    /// Whether the returned path is always one of the cheapest. For `IdaStar`
+    /// this assumes the graph's heuristic never overestimates.
+    pub fn finds_cheapest(&self) -> bool {
+        matches!(
+            self,
+            PathfindingAlgorithm::Dijkstra
+                | PathfindingAlgorithm::AStar
+                | PathfindingAlgorithm::IdaStar
+                | PathfindingAlgorithm::BellmanFord
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownAlgorithm(pub String);
+
+impl fmt::Display for UnknownAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = ALGORITHM_NAMES.iter().map(|(name, _)| *name).collect();
+        write!(
+            f,
+            "unknown algorithm '{}', expected one of: {}",
+            self.0,
+            names.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for UnknownAlgorithm {}
+
+impl FromStr for PathfindingAlgorithm {
+    type Err = UnknownAlgorithm;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.to_ascii_lowercase();
+        ALGORITHM_NAMES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, algo)| *algo)
+            .ok_or_else(|| UnknownAlgorithm(s.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+    /// A cycle with negative total weight is reachable from `start`, so there is no shortest path
+    NegativeCycle,
+    /// `start` or `end` is not a valid vertex index
+    EndpointOutOfRange {
+        endpoint: &'static str,
+        index: usize,
+        vertex_count: usize,
+    },
+    /// `start` or `end` is a vertex without any edges, such as one removed by reduction
+    DeadEndpoint {
+        endpoint: &'static str,
+        index: usize,
+    },
+    /// An edge weight is NaN or infinite, the searches can't order such costs
+    InvalidWeight { from: usize, to: usize, weight: f32 },
+    /// The graph has more vertices than the algorithm is meant for
+    TooManyVertices { vertex_count: usize, limit: usize },
+    /// There is no edge between the two vertices in either direction
+    MissingEdge { from: usize, to: usize },
+    /// There already is an edge between the two vertices
+    DuplicateEdge { from: usize, to: usize },
+    /// An edge would lead from a vertex back to itself
+    SelfLoop { vertex: usize },
+    /// A vertex index past the end of the graph
+    VertexOutOfRange { index: usize, vertex_count: usize },
+    /// Every path the search could still find costs more than `max_cost`, see
+    /// `Solver::set_max_cost`
+    PathTooLong { max_cost: f32 },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::NegativeCycle => write!(f, "graph contains a negative cycle"),
+            SolveError::EndpointOutOfRange {
+                endpoint,
+                index,
+                vertex_count,
+            } => write!(
+                f,
+                "{endpoint} vertex {index} is out of range for a graph with {vertex_count} vertices"
+            ),
+            SolveError::DeadEndpoint { endpoint, index } => {
+                write!(f, "{endpoint} vertex {index} has no edges")
+            }
+            SolveError::InvalidWeight { from, to, weight } => {
+                write!(f, "edge from {from} to {to} has invalid weight {weight}")
+            }
+            SolveError::TooManyVertices {
+                vertex_count,
+                limit,
+            } => write!(
+                f,
+                "graph has {vertex_count} vertices, at most {limit} are supported here"
+            ),
+            SolveError::MissingEdge { from, to } => {
+                write!(f, "there is no edge between {from} and {to}")
+            }
+            SolveError::DuplicateEdge { from, to } => {
+                write!(f, "there already is an edge between {from} and {to}")
+            }
+            SolveError::SelfLoop { vertex } => {
+                write!(f, "vertex {vertex} can't have an edge to itself")
+            }
+            SolveError::VertexOutOfRange {
+                index,
+                vertex_count,
+            } => write!(
+                f,
+                "vertex {index} is out of range for a graph with {vertex_count} vertices"
+            ),
+            SolveError::PathTooLong { max_cost } => {
+                write!(f, "no path costs at most {max_cost}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Why `solve_verified` didn't trust a solver's answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerifyError {
+    Solve(SolveError),
+    /// There is no path from `start` to `end`
+    NoPath,
+    /// The solver found nothing although Dijkstra reaches `end` at this cost
+    MissedPath {
+        expected: f32,
+    },
+    /// The path doesn't run from `start` to `end`
+    WrongEndpoints {
+        first: Option<usize>,
+        last: Option<usize>,
+    },
+    /// Two consecutive vertices of the path aren't connected
+    MissingEdge {
+        from: usize,
+        to: usize,
+    },
+    /// The solver promises the cheapest path but its cost differs from the
+    /// one Dijkstra found
+    CostMismatch {
+        path_cost: f32,
+        expected: f32,
+    },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::Solve(err) => write!(f, "{err}"),
+            VerifyError::NoPath => write!(f, "no path from start to end"),
+            VerifyError::MissedPath { expected } => {
+                write!(f, "no path returned but one of cost {expected} exists")
+            }
+            VerifyError::WrongEndpoints { first, last } => write!(
+                f,
+                "path runs from {first:?} to {last:?} instead of start to end"
+            ),
+            VerifyError::MissingEdge { from, to } => {
+                write!(f, "path steps from {from} to {to} without an edge")
+            }
+            VerifyError::CostMismatch {
+                path_cost,
+                expected,
+            } => write!(
+                f,
+                "path costs {path_cost} but the cheapest one costs {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VerifyError::Solve(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<SolveError> for VerifyError {
+    fn from(err: SolveError) -> Self {
+        VerifyError::Solve(err)
+    }
+}
+
+/// Checks that `path` starts at `start`, ends at `end` and only steps along
+/// edges of the graph.
+pub fn validate_path<T>(graph: &Graph<T>, path: &[usize]) -> Result<(), VerifyError> {
+    let (first, last) = (path.first().copied(), path.last().copied());
+    if first != Some(graph.start) || last != Some(graph.end) {
+        return Err(VerifyError::WrongEndpoints { first, last });
+    }
+
+    for pair in path.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let connected = graph
+            .get_vertices()
+            .get(from)
+            .is_some_and(|v| v.get_neighbors().iter().any(|(idx, _)| *idx == to));
+        if !connected {
+            return Err(VerifyError::MissingEdge { from, to });
+        }
+    }
+    Ok(())
+}
+
+/// Solves with `algo` and double checks the answer: the path has to pass
+/// `validate_path`, and for algorithms that promise the cheapest path its
+/// cost has to match an independent Dijkstra run. Meant for checking solvers
+/// against whole sets of mazes, it costs an extra Dijkstra per call.
+pub fn solve_verified<T: Distance>(
+    graph: &Graph<T>,
+    algo: &PathfindingAlgorithm,
+) -> Result<PathResult, VerifyError> {
+    let path = solve_graph(graph, algo)?;
+    verify(graph, algo, path)
+}
+
+/// The checks `solve_verified` runs on the path `algo` returned.
+fn verify<T>(
+    graph: &Graph<T>,
+    algo: &PathfindingAlgorithm,
+    path: Option<Vec<usize>>,
+) -> Result<PathResult, VerifyError> {
+    let (dists, _) = shortest_paths_from(graph, graph.start);
+    let expected = dists[graph.end];
+
+    let Some(path) = path else {
+        return Err(if expected.is_finite() {
+            VerifyError::MissedPath { expected }
+        } else {
+            VerifyError::NoPath
+        });
+    };
+    validate_path(graph, &path)?;
+
+    let cost = calculate_cost(graph, &path);
+    let tolerance = 1e-4 * expected.abs().max(1.0);
+    if algo.finds_cheapest() && (cost - expected).abs() > tolerance {
+        return Err(VerifyError::CostMismatch {
+            path_cost: cost,
+            expected,
+        });
+    }
+
+    Ok(PathResult {
+        path,
+        cost,
+        expansions: 0,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PathResult {
+    pub path: Vec<usize>,
+    pub cost: f32,
+    /// Vertices taken off the frontier while searching, 0 when not tracked
+    pub expansions: usize,
+}
+
+/// Returns `Ok(None)` when `end` can't be reached from `start`.
+pub fn solve_graph<T: Distance>(
+    graph: &Graph<T>,
+    algo: &PathfindingAlgorithm,
+) -> Result<Option<Vec<usize>>, SolveError> {
+    Solver::new(*algo).solve(graph)
+}
+
+/// Runs one algorithm repeatedly while reusing its bookkeeping buffers, so
+/// only the first solve (or a larger graph) pays for allocating them.
+pub struct Solver {
+    algo: PathfindingAlgorithm,
+    scratch: Scratch,
+    max_cost: f32,
+}
+
+impl Solver {
+    pub fn new(algo: PathfindingAlgorithm) -> Self {
+        Self {
+            algo,
+            scratch: Scratch::default(),
+            max_cost: f32::INFINITY,
+        }
+    }
+
+    /// Returns `Ok(None)` when `end` can't be reached from `start`.
+    pub fn solve<T: Distance>(
+        &mut self,
+        graph: &Graph<T>,
+    ) -> Result<Option<Vec<usize>>, SolveError> {
+        self.scratch.blocked.clear();
+        self.run(graph, graph.start, graph.end)
+    }
+
+    /// Like `solve`, but the search treats the `blocked` vertices as if they
+    /// weren't there, without touching the graph. Blocking `start` or `end`,
+    /// or every way between them, gives `Ok(None)`.
+    pub fn solve_avoiding<T: Distance>(
+        &mut self,
+        graph: &Graph<T>,
+        blocked: &HashSet<usize>,
+    ) -> Result<Option<Vec<usize>>, SolveError> {
+        let vertex_count = graph.get_vertices().len();
+        if let Some(&index) = blocked.iter().find(|&&index| index >= vertex_count) {
+            return Err(SolveError::VertexOutOfRange {
+                index,
+                vertex_count,
+            });
+        }
+        self.scratch.blocked.clear();
+        reset(&mut self.scratch.blocked.vertices, vertex_count, false);
+        for &index in blocked {
+            self.scratch.blocked.vertices[index] = true;
+        }
+        if blocked.contains(&graph.start) || blocked.contains(&graph.end) {
+            validate_endpoints(graph, graph.start, graph.end)?;
+            return Ok(None);
+        }
+        self.run(graph, graph.start, graph.end)
+    }
+
+    /// Searches from `start` to `end`, which need not be the graph's own,
+    /// around whatever `Scratch::blocked` holds.
+    fn run<T: Distance>(
+        &mut self,
+        graph: &Graph<T>,
+        start: usize,
+        end: usize,
+    ) -> Result<Option<Vec<usize>>, SolveError> {
+        validate_endpoints(graph, start, end)?;
+        validate_weights(graph)?;
+
+        let solve_start = Instant::now();
+        let scratch = &mut self.scratch;
+        if let Some(events) = &mut scratch.trace.events {
+            events.clear();
+        }
+        let path = match self.algo {
+            PathfindingAlgorithm::DepthFirst => dfs_iterative(graph, start, end, scratch),
+            PathfindingAlgorithm::BreadthFirst => bfs(graph, start, end, scratch),
+            PathfindingAlgorithm::Dijkstra => {
+                // Vertices come off the heap cheapest first, once one costs
+                // more than the limit so does every path to `end`
+                let max_cost = self.max_cost;
+                let reached = dijkstra_search(
+                    graph,
+                    start,
+                    |idx, cost| idx == end || cost > max_cost,
+                    scratch,
+                );
+                mark_reached(scratch);
+                match reached {
+                    Some(idx) if scratch.dists[idx] > max_cost => {
+                        return Err(SolveError::PathTooLong { max_cost });
+                    }
+                    Some(idx) => Some(reconstruct_path(&scratch.parent_map, idx)),
+                    None => None,
+                }
+            }
+            PathfindingAlgorithm::AStar => {
+                // Custom step weights can make the heuristic overestimate an
+                // edge, and closed vertices are never reopened, so fall back
+                // to Dijkstra order to keep the path the cheapest
+                let epsilon = if heuristic_is_consistent(graph) {
+                    1.0
+                } else {
+                    0.0
+                };
+                // With either weight the priority never overestimates the
+                // cost of a path through the vertex, so once it passes the
+                // limit every path left to `end` costs more too
+                let max_cost = self.max_cost;
+                let (reached, _) = astar_search(
+                    graph,
+                    start,
+                    goal_distance(graph, end),
+                    epsilon,
+                    |idx, priority| idx == end || priority > max_cost,
+                    scratch,
+                );
+                match reached {
+                    Some(idx) if idx != end || scratch.dists[idx] > max_cost => {
+                        return Err(SolveError::PathTooLong { max_cost });
+                    }
+                    Some(idx) => Some(reconstruct_path(&scratch.parent_map, idx)),
+                    None => None,
+                }
+            }
+            PathfindingAlgorithm::IterativeDeepening => iddfs(graph, start, end, scratch),
+            PathfindingAlgorithm::IdaStar => ida_star(graph, start, end, scratch),
+            PathfindingAlgorithm::BellmanFord => {
+                let path = bellman_ford_search(graph, start, end, scratch);
+                mark_reached(scratch);
+                path?
+            }
+            PathfindingAlgorithm::Tremaux => tremaux(graph, start, end, scratch),
+        };
+        if log::log_enabled!(log::Level::Debug) {
+            log::debug!(
+                "{:?} visited {} of {} vertices in {}ms",
+                self.algo,
+                scratch.visited.iter().filter(|&&visited| visited).count(),
+                graph.get_vertices().len(),
+                solve_start.elapsed().as_millis()
+            );
+        }
+        if let Some(path) = &path {
+            if self.max_cost.is_finite() && calculate_cost(graph, path) > self.max_cost {
+                return Err(SolveError::PathTooLong {
+                    max_cost: self.max_cost,
+                });
+            }
+            scratch
+                .trace
+                .record(|| SearchEvent::Found { path: path.clone() });
+        }
+        Ok(path)
+    }
+
+    /// Gives up with `SolveError::PathTooLong` on paths costing more than
+    /// `max_cost` in every following `solve`, `None` lifts the limit again.
+    /// Dijkstra and A* stop as soon as the cheapest vertex left, counting
+    /// A*'s estimate of the way to `end`, costs more, without searching the
+    /// rest of the maze, so `Ok(None)` still means `end` can't be reached at
+    /// all. The other algorithms search as usual and the limit is checked
+    /// against the path they return, for the ones that don't find the
+    /// cheapest path a cheaper one may still exist.
+    pub fn set_max_cost(&mut self, max_cost: Option<f32>) {
+        self.max_cost = max_cost.unwrap_or(f32::INFINITY);
+    }
+
+    /// Turns recording of a `SearchEvent` trace for every following `solve`
+    /// on or off. Off by default, a trace holds an event per edge looked at.
+    pub fn set_tracing(&mut self, enabled: bool) {
+        self.scratch.trace.events = enabled.then(Vec::new);
+    }
+
+    /// Events of the last `solve`, empty unless tracing is on.
+    pub fn trace(&self) -> &[SearchEvent] {
+        self.scratch.trace.events.as_deref().unwrap_or_default()
+    }
+
+    /// Vertices the last `solve` marked as visited, indexed like the graph's
+    /// vertices. Empty before the first solve.
+    pub fn visited(&self) -> &[bool] {
+        &self.scratch.visited
+    }
+}
+
+/// Like `solve_graph`, but paths costing more than `max_cost` give
+/// `SolveError::PathTooLong`, see `Solver::set_max_cost`.
+pub fn solve_graph_within<T: Distance>(
+    graph: &Graph<T>,
+    algo: &PathfindingAlgorithm,
+    max_cost: f32,
+) -> Result<Option<Vec<usize>>, SolveError> {
+    let mut solver = Solver::new(*algo);
+    solver.set_max_cost(Some(max_cost));
+    solver.solve(graph)
+}
+
+/// Like `solve_graph`, but the search treats the `blocked` vertices as if they
+/// weren't there, see `Solver::solve_avoiding`.
+pub fn solve_graph_avoiding<T: Distance>(
+    graph: &Graph<T>,
+    algo: &PathfindingAlgorithm,
+    blocked: &HashSet<usize>,
+) -> Result<Option<Vec<usize>>, SolveError> {
+    Solver::new(*algo).solve_avoiding(graph, blocked)
+}
+
+/// Like `solve_graph`, but also returns which vertices the algorithm visited,
+/// e.g. to measure how much of the maze it had to explore. What counts as
+/// visited follows each algorithm's own bookkeeping: DFS marks vertices as it
+/// expands them, BFS as it enqueues them, Dijkstra and Bellman-Ford every
+/// vertex they found a distance for, A* the vertices it expanded, and the
+/// depth-first ones everything they ever stepped onto.
+pub fn solve_graph_with_visited<T: Distance>(
+    graph: &Graph<T>,
+    algo: &PathfindingAlgorithm,
+) -> Result<(Option<Vec<usize>>, Vec<bool>), SolveError> {
+    let mut solver = Solver::new(*algo);
+    let path = solver.solve(graph)?;
+    Ok((path, std::mem::take(&mut solver.scratch.visited)))
+}
+
+/// The vertex each vertex was reached from in a search, see `solve_graph_tree`.
+pub type SearchTree = Vec<Option<usize>>;
+
+/// Like `solve_graph`, but also returns the search tree the path was read
+/// off, for drawing which vertex each one was reached from. `tree[v]` is the
+/// parent of `v`, `None` for `start` and for vertices the search never
+/// reached. DFS, BFS, Dijkstra, A* and Bellman-Ford fill it for everything
+/// they reached. Iterative deepening, IDA* and Trémaux keep no parents, for them
+/// the tree only holds the steps of the path, so `end` still traces back to
+/// `start`.
+pub fn solve_graph_tree<T: Distance>(
+    graph: &Graph<T>,
+    algo: &PathfindingAlgorithm,
+) -> Result<(Option<Vec<usize>>, SearchTree), SolveError> {
+    let mut solver = Solver::new(*algo);
+    let path = solver.solve(graph)?;
+    let tree = match algo {
+        PathfindingAlgorithm::DepthFirst
+        | PathfindingAlgorithm::BreadthFirst
+        | PathfindingAlgorithm::Dijkstra
+        | PathfindingAlgorithm::AStar
+        | PathfindingAlgorithm::BellmanFord => std::mem::take(&mut solver.scratch.parent_map),
+        PathfindingAlgorithm::IterativeDeepening
+        | PathfindingAlgorithm::IdaStar
+        | PathfindingAlgorithm::Tremaux => {
+            // Trémaux paths can revisit a vertex, keep where it was first
+            // reached so the tree stays free of loops
+            let mut tree = vec![None; graph.get_vertices().len()];
+            for step in path.iter().flat_map(|path| path.windows(2)) {
+                if step[1] != graph.start && tree[step[1]].is_none() {
+                    tree[step[1]] = Some(step[0]);
+                }
+            }
+            tree
+        }
+    };
+    Ok((path, tree))
+}
+
+/// Like `solve_graph`, but also returns the search as a list of
+/// `SearchEvent`s, see `trace_to_json` for handing it to a visualizer.
+pub fn solve_graph_traced<T: Distance>(
+    graph: &Graph<T>,
+    algo: &PathfindingAlgorithm,
+) -> Result<(Option<Vec<usize>>, Vec<SearchEvent>), SolveError> {
+    let mut solver = Solver::new(*algo);
+    solver.set_tracing(true);
+    let path = solver.solve(graph)?;
+    let events = solver.scratch.trace.events.take().unwrap_or_default();
+    Ok((path, events))
+}
+
+/// A breadth-first or Dijkstra search run one vertex expansion at a time,
+/// for stepping through it in a debugger or UI. The state can be looked at
+/// between steps, and the path at the end is the one `solve_graph` finds
+/// with the same algorithm.
+pub struct SearchState<'a, T> {
+    graph: &'a Graph<T>,
+    frontier: Frontier,
+    /// Cost of the best path found so far to each vertex, the sum of edge
+    /// weights for BFS too
+    dists: Vec<f32>,
+    parent_map: Vec<Option<usize>>,
+    /// BFS marks vertices as it enqueues them, like `bfs`
+    visited: Vec<bool>,
+    current: Option<usize>,
+    done: bool,
+    expansions: usize,
+}
+
+enum Frontier {
+    Queue(VecDeque<usize>),
+    Heap(BinaryHeap<State>),
+}
+
+impl<'a, T> SearchState<'a, T> {
+    /// A breadth-first search from `start`, nothing expanded yet.
+    pub fn bfs(graph: &'a Graph<T>) -> Result<Self, SolveError> {
+        let mut state = Self::new(graph, Frontier::Queue(VecDeque::from([graph.start])))?;
+        state.visited[graph.start] = true;
+        Ok(state)
+    }
+
+    /// A Dijkstra search from `start`, nothing expanded yet.
+    pub fn dijkstra(graph: &'a Graph<T>) -> Result<Self, SolveError> {
+        let heap = BinaryHeap::from([State {
+            cost: 0.0,
+            position: graph.start,
+        }]);
+        Self::new(graph, Frontier::Heap(heap))
+    }
+
+    fn new(graph: &'a Graph<T>, frontier: Frontier) -> Result<Self, SolveError> {
+        validate_endpoints(graph, graph.start, graph.end)?;
+        validate_weights(graph)?;
+        let len = graph.get_vertices().len();
+        let mut dists = vec![f32::INFINITY; len];
+        dists[graph.start] = 0.0;
+        Ok(Self {
+            graph,
+            frontier,
+            dists,
+            parent_map: vec![None; len],
+            visited: vec![false; len],
+            current: None,
+            done: false,
+            expansions: 0,
+        })
+    }
+
+    /// Takes the next vertex off the frontier and, unless it is `end`, adds
+    /// its neighbors. Returns true once the search is over, either because
+    /// `end` was reached or the frontier ran out, and does nothing after that.
+    pub fn step(&mut self) -> bool {
+        if self.done {
+            return true;
+        }
+        let next = match &mut self.frontier {
+            Frontier::Queue(queue) => queue.pop_front(),
+            Frontier::Heap(heap) => loop {
+                match heap.pop() {
+                    Some(State { cost, position }) if cost > self.dists[position] => continue,
+                    entry => break entry.map(|entry| entry.position),
+                }
+            },
+        };
+        let Some(current) = next else {
+            self.done = true;
+            return true;
+        };
+        self.current = Some(current);
+        if current == self.graph.end {
+            self.done = true;
+            return true;
+        }
+
+        self.expansions += 1;
+        for &(neighbor, weight) in self.graph.get_vertices()[current].get_neighbors() {
+            let next_dist = self.dists[current] + weight;
+            match &mut self.frontier {
+                Frontier::Queue(queue) => {
+                    if !self.visited[neighbor] {
+                        self.visited[neighbor] = true;
+                        self.dists[neighbor] = next_dist;
+                        self.parent_map[neighbor] = Some(current);
+                        queue.push_back(neighbor);
+                    }
+                }
+                Frontier::Heap(heap) => {
+                    if next_dist < self.dists[neighbor] {
+                        self.dists[neighbor] = next_dist;
+                        self.parent_map[neighbor] = Some(current);
+                        heap.push(State {
+                            cost: next_dist,
+                            position: neighbor,
+                        });
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Steps until the search is over and returns the path, `None` if `end`
+    /// can't be reached.
+    pub fn run(&mut self) -> Option<Vec<usize>> {
+        while !self.step() {}
+        self.path()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// The vertex the last step took off the frontier.
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// Vertices whose neighbors have been added so far. `end` isn't expanded
+    /// when it is reached.
+    pub fn expansions(&self) -> usize {
+        self.expansions
+    }
+
+    /// Vertices waiting on the frontier, in the order they will be taken off
+    /// it.
+    pub fn frontier(&self) -> Vec<usize> {
+        match &self.frontier {
+            Frontier::Queue(queue) => queue.iter().copied().collect(),
+            Frontier::Heap(heap) => {
+                let mut entries: Vec<State> = heap
+                    .iter()
+                    .filter(|entry| entry.cost <= self.dists[entry.position])
+                    .copied()
+                    .collect();
+                // `State` orders the cheapest entry as the greatest
+                entries.sort_unstable_by(|a, b| b.cmp(a));
+                entries.iter().map(|entry| entry.position).collect()
+            }
+        }
+    }
+
+    /// Cost of the best path to `vertex` found so far, `None` if it hasn't
+    /// been reached.
+    pub fn distance(&self, vertex: usize) -> Option<f32> {
+        Some(self.dists[vertex]).filter(|dist| dist.is_finite())
+    }
+
+    /// The vertex `vertex` was last reached from.
+    pub fn parent(&self, vertex: usize) -> Option<usize> {
+        self.parent_map[vertex]
+    }
+
+    /// The path from `start` to `end` once the search has reached `end`.
+    pub fn path(&self) -> Option<Vec<usize>> {
+        (self.current == Some(self.graph.end) && self.done)
+            .then(|| reconstruct_path(&self.parent_map, self.graph.end))
+    }
+}
+
+/// Checks that `start` and `end` point at vertices a search can use, so a bad
+/// index fails here instead of panicking somewhere inside a solver.
+fn validate_endpoints<T>(graph: &Graph<T>, start: usize, end: usize) -> Result<(), SolveError> {
+    let vertices = graph.get_vertices();
+    for (endpoint, index) in [("start", start), ("end", end)] {
+        if index >= vertices.len() {
+            return Err(SolveError::EndpointOutOfRange {
+                endpoint,
+                index,
+                vertex_count: vertices.len(),
+            });
+        }
+        if start == end {
+            continue;
+        }
+        // The end of a one-way passage has no edges of its own
+        let dead = vertices[index].get_neighbors().is_empty()
+            && (endpoint == "start"
+                || !vertices
+                    .iter()
+                    .any(|vertex| vertex.get_neighbors().iter().any(|(idx, _)| *idx == index)));
+        if dead {
+            return Err(SolveError::DeadEndpoint { endpoint, index });
+        }
+    }
+    Ok(())
+}
+
+/// Rejects NaN and infinite edge weights up front. Negative weights are left
+/// to the solvers, Bellman-Ford handles them and the others simply assume
+/// there are none.
+fn validate_weights<T>(graph: &Graph<T>) -> Result<(), SolveError> {
+    for (from, vertex) in graph.get_vertices().iter().enumerate() {
+        for &(to, weight) in vertex.get_neighbors() {
+            if !weight.is_finite() {
+                return Err(SolveError::InvalidWeight { from, to, weight });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Per-vertex bookkeeping shared by the solvers. Each solver resets the
+/// buffers it uses, which keeps their capacity between runs.
+#[derive(Default)]
+struct Scratch {
+    visited: Vec<bool>,
+    parent_map: Vec<Option<usize>>,
+    dists: Vec<f32>,
+    is_goal: Vec<bool>,
+    stack: Vec<usize>,
+    queue: VecDeque<usize>,
+    heap: BinaryHeap<State>,
+    blocked: Blocked,
+    trace: Trace,
+}
+
+/// One step of a search, recorded by a `Solver` with tracing turned on. The
+/// frontier starts out as just `start`, replaying the events in order
+/// rebuilds it at every step.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SearchEvent {
+    /// `vertex` is taken off the frontier and its edges are looked at
+    Visit { vertex: usize },
+    /// `vertex` joins the frontier, or gets a cheaper `cost`, through `from`.
+    /// The cost is the sum of edge weights even for the unweighted searches
+    Relax {
+        vertex: usize,
+        from: usize,
+        cost: f32,
+    },
+    /// The depth-first searches step back off `vertex`, it leaves their path
+    Backtrack { vertex: usize },
+    /// `iddfs` and `ida_star` start over from `start` with a larger depth or
+    /// cost `limit`, the frontier is back to just `start`
+    Restart { limit: f32 },
+    /// The search reached `end` along `path`, always the last event when a
+    /// path was found
+    Found { path: Vec<usize> },
+}
+
+/// Serializes a trace as a JSON array for a frontend to replay, one object
+/// per event: `{"visit":3}`, `{"relax":[5,2.5],"from":3}`, `{"backtrack":5}`,
+/// `{"restart":4}` and `{"found":[0,3,5]}`.
+pub fn trace_to_json(events: &[SearchEvent]) -> String {
+    let mut json = String::from("[");
+    for (i, event) in events.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        match event {
+            SearchEvent::Visit { vertex } => json.push_str(&format!("{{\"visit\":{vertex}}}")),
+            SearchEvent::Relax { vertex, from, cost } => {
+                json.push_str(&format!("{{\"relax\":[{vertex},{cost}],\"from\":{from}}}"))
+            }
+            SearchEvent::Backtrack { vertex } => {
+                json.push_str(&format!("{{\"backtrack\":{vertex}}}"))
+            }
+            SearchEvent::Restart { limit } => json.push_str(&format!("{{\"restart\":{limit}}}")),
+            SearchEvent::Found { path } => {
+                let path: Vec<String> = path.iter().map(usize::to_string).collect();
+                json.push_str(&format!("{{\"found\":[{}]}}", path.join(",")))
+            }
+        }
+    }
+    json.push(']');
+    json
+}
+
+/// Collects `SearchEvent`s when turned on, otherwise recording is a no-op so
+/// the solvers don't pay for it.
+#[derive(Default)]
+struct Trace {
+    events: Option<Vec<SearchEvent>>,
+}
+
+impl Trace {
+    fn is_on(&self) -> bool {
+        self.events.is_some()
+    }
+
+    fn record<F: FnOnce() -> SearchEvent>(&mut self, event: F) {
+        if let Some(events) = &mut self.events {
+            events.push(event());
+        }
+    }
+
+    /// Records a relaxation for the searches that don't keep distances of
+    /// their own, tracking them in `dists` only while tracing.
+    fn relax(&mut self, dists: &mut [f32], vertex: usize, from: usize, weight: f32) {
+        if self.is_on() {
+            let cost = dists[from] + weight;
+            dists[vertex] = cost;
+            self.record(|| SearchEvent::Relax { vertex, from, cost });
+        }
+    }
+}
+
+fn reset<V: Clone>(buffer: &mut Vec<V>, len: usize, value: V) {
+    buffer.clear();
+    buffer.resize(len, value);
+}
+
+/// Vertices and directed edges to search around, see
+/// `Solver::solve_avoiding`. Both are empty when none are.
+#[derive(Default)]
+struct Blocked {
+    vertices: Vec<bool>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl Blocked {
+    fn clear(&mut self) {
+        self.vertices.clear();
+        self.edges.clear();
+    }
+
+    /// Whether the step from `from` to `to` is ruled out.
+    fn blocks(&self, from: usize, to: usize) -> bool {
+        self.vertices.get(to).is_some_and(|&blocked| blocked) || self.edges.contains(&(from, to))
+    }
+}
+
+/// Marks every vertex the distance based solvers reached as visited.
+fn mark_reached(scratch: &mut Scratch) {
+    scratch.visited.clear();
+    scratch
+        .visited
+        .extend(scratch.dists.iter().map(|dist| dist.is_finite()));
+}
+
+impl<T: Distance + Clone> Graph<T> {
+    /// The cheapest route from any of `entrances` to any of `exits`, found
+    /// with a single search: a virtual source gets a free one-way edge to
+    /// every entrance, every exit a free one-way edge to a virtual sink, and
+    /// `algo` solves from the source to the sink on a copy of the graph. The
+    /// virtual vertices are left out of the returned path. Only as cheap as
+    /// `algo` finds, so use an optimal one. `IdaStar` aims its heuristic at
+    /// the first exit, with several exits it may miss the cheapest one.
+    pub fn solve_any_entrance_to_any_exit(
+        &self,
+        entrances: &[usize],
+        exits: &[usize],
+        algo: &PathfindingAlgorithm,
+    ) -> Result<Option<Vec<usize>>, SolveError> {
+        let (Some(&first_entrance), Some(&first_exit)) = (entrances.first(), exits.first()) else {
+            return Ok(None);
+        };
+        let vertex_count = self.get_vertices().len();
+        if let Some(&index) = entrances
+            .iter()
+            .chain(exits)
+            .find(|&&idx| idx >= vertex_count)
+        {
+            return Err(SolveError::VertexOutOfRange {
+                index,
+                vertex_count,
+            });
+        }
+
+        let mut graph = self.clone();
+        let vertices = self.get_vertices();
+        let source = graph.push_vertex(vertices[first_entrance].get_pos().clone());
+        let sink = graph.push_vertex(vertices[first_exit].get_pos().clone());
+        for &entrance in entrances {
+            if !graph.has_edge(source, entrance) {
+                graph.add_edge(source, entrance, 0.0)?;
+                graph.set_one_way(source, entrance);
+            }
+        }
+        for &exit in exits {
+            if !graph.has_edge(exit, sink) {
+                graph.add_edge(exit, sink, 0.0)?;
+                graph.set_one_way(exit, sink);
+            }
+        }
+        graph.start = source;
+        graph.end = sink;
+
+        let path = solve_graph(&graph, algo)?;
+        Ok(path.map(|path| path[1..path.len() - 1].to_vec()))
+    }
+
+    /// Yen's algorithm. Returns up to `k` loopless paths from `start` to `end`
+    /// ordered by increasing cost. `algo` is used for every spur search, so it
+    /// should be one of the optimal solvers for the ordering to hold.
+    pub fn k_shortest_paths(
+        &self,
+        k: usize,
+        algo: &PathfindingAlgorithm,
+    ) -> Result<Vec<PathResult>, SolveError> {
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+        let mut solver = Solver::new(*algo);
+        let mut found: Vec<PathResult> = Vec::new();
+        let Some(first) = solver.solve(self)? else {
+            return Ok(found);
+        };
+        found.push(PathResult {
+            cost: calculate_cost(self, &first),
+            path: first,
+            expansions: 0,
+        });
+
+        let vertex_count = self.get_vertices().len();
+        let mut candidates: Vec<PathResult> = Vec::new();
+        while found.len() < k {
+            let previous = &found[found.len() - 1].path;
+
+            for i in 0..previous.len() - 1 {
+                let spur = previous[i];
+                let root = &previous[..=i];
+
+                // Block the edges already used by found paths sharing this root,
+                // and every root vertex except the spur so the result stays loopless
+                let blocked = &mut solver.scratch.blocked;
+                blocked.clear();
+                for result in &found {
+                    if result.path.len() > i + 1 && result.path[..=i] == *root {
+                        let (a, b) = (result.path[i], result.path[i + 1]);
+                        blocked.edges.extend([(a, b), (b, a)]);
+                    }
+                }
+                reset(&mut blocked.vertices, vertex_count, false);
+                for &idx in &root[..i] {
+                    blocked.vertices[idx] = true;
+                }
+
+                let spur_path = solver.run(self, spur, self.end)?;
+                if let Some(spur_path) = spur_path {
+                    let mut path = root[..i].to_vec();
+                    path.extend(spur_path);
+                    if !candidates.iter().any(|c| c.path == path) {
+                        candidates.push(PathResult {
+                            cost: calculate_cost(self, &path),
+                            path,
+                            expansions: 0,
+                        });
+                    }
+                }
+            }
+
+            let Some((best, _)) = candidates
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| a.cost.partial_cmp(&b.cost).unwrap_or(Ordering::Equal))
+            else {
+                break;
+            };
+            found.push(candidates.swap_remove(best));
+        }
+
+        Ok(found)
+    }
+}
+
+impl<T> Graph<T> {
+    /// Every vertex whose shortest path from `source` costs at most
+    /// `max_cost`, `source` included, in increasing index order. The search
+    /// stops at the first vertex past the budget, so a small budget only
+    /// explores the neighborhood of `source`.
+    pub fn reachable_within(&self, source: usize, max_cost: f32) -> Vec<usize> {
+        let mut scratch = Scratch::default();
+        dijkstra_search(self, source, |_, cost| cost > max_cost, &mut scratch);
+        // Anything still on the heap costs more than the vertex that stopped
+        // the search, so every vertex within budget has been settled
+        (0..scratch.dists.len())
+            .filter(|&idx| scratch.dists[idx] <= max_cost)
+            .collect()
+    }
+
+    /// Shortest path from `start` to whichever of `goals` is closest.
+    /// Returns the path together with the goal it ends at, `Ok(None)` if no
+    /// goal can be reached. Fails on an out of range `start` or goal.
+    pub fn dijkstra_multi(
+        &self,
+        goals: &[usize],
+    ) -> Result<Option<(Vec<usize>, usize)>, SolveError> {
+        let vertex_count = self.get_vertices().len();
+        if self.start >= vertex_count {
+            return Err(SolveError::EndpointOutOfRange {
+                endpoint: "start",
+                index: self.start,
+                vertex_count,
+            });
+        }
+        if let Some(&index) = goals.iter().find(|&&index| index >= vertex_count) {
+            return Err(SolveError::VertexOutOfRange {
+                index,
+                vertex_count,
+            });
+        }
+        validate_weights(self)?;
+        Ok(dijkstra_to_any(self, goals, &mut Scratch::default()))
+    }
+
+    /// Floyd-Warshall. `dists[a][b]` is the cost of the cheapest path from `a`
+    /// to `b`, `f32::INFINITY` if there is none. Takes O(V³) time and O(V²)
+    /// memory, so it refuses graphs with more than `ALL_PAIRS_VERTEX_LIMIT`
+    /// vertices. Dead vertices count too, `compact` the graph first.
+    pub fn all_pairs_shortest_paths(&self) -> Result<Vec<Vec<f32>>, SolveError> {
+        let vertices = self.get_vertices();
+        if vertices.len() > ALL_PAIRS_VERTEX_LIMIT {
+            return Err(SolveError::TooManyVertices {
+                vertex_count: vertices.len(),
+                limit: ALL_PAIRS_VERTEX_LIMIT,
+            });
+        }
+        validate_weights(self)?;
+
+        let mut dists = vec![vec![f32::INFINITY; vertices.len()]; vertices.len()];
+        for (a, vertex) in vertices.iter().enumerate() {
+            dists[a][a] = 0.0;
+            for &(b, weight) in vertex.get_neighbors() {
+                dists[a][b] = dists[a][b].min(weight);
+            }
+        }
+
+        for via in 0..vertices.len() {
+            let via_row = dists[via].clone();
+            for row in dists.iter_mut() {
+                let to_via = row[via];
+                if to_via == f32::INFINITY {
+                    continue;
+                }
+                for (dist, from_via) in row.iter_mut().zip(&via_row) {
+                    *dist = dist.min(to_via + from_via);
+                }
+            }
+        }
+        Ok(dists)
+    }
+}
+
+/// Precomputed shortest distances from a few landmark vertices, see
+/// `Graph::precompute_landmarks`. By the triangle inequality
+/// `|d(L, t) - d(L, v)|` never overestimates `d(v, t)`, the largest such bound
+/// over all landmarks is used as the A* heuristic. With one-way edges, or
+/// weights that differ by direction, only `d(L, t) - d(L, v)` is a bound,
+/// which is weaker but still admissible.
+#[derive(Debug, Clone)]
+pub struct Landmarks {
+    landmarks: Vec<usize>,
+    dists: Vec<Vec<f32>>,
+    one_way: bool,
+}
+
+impl Landmarks {
+    pub fn get_landmarks(&self) -> &[usize] {
+        &self.landmarks
+    }
+
+    fn lower_bound(&self, from: usize, to: usize) -> f32 {
+        self.dists
+            .iter()
+            .map(|dists| {
+                let bound = dists[to] - dists[from];
+                if self.one_way { bound } else { bound.abs() }
+            })
+            // Landmarks in another component tell us nothing
+            .filter(|bound| bound.is_finite())
+            .fold(0.0, f32::max)
+    }
+}
+
+impl<T> Graph<T> {
+    /// Picks up to `n` landmarks spread out over the graph and computes their
+    /// distance tables, for use with `alt_astar`. Each landmark is the vertex
+    /// farthest from the ones already chosen, the first is the one farthest
+    /// from `start`. Only the part of the graph connected to `start` gets
+    /// landmarks, so fewer than `n` may be returned. This costs a full
+    /// Dijkstra run per landmark and pays off when the same graph is queried
+    /// many times.
+    pub fn precompute_landmarks(&self, n: usize) -> Landmarks {
+        let vertex_count = self.get_vertices().len();
+        let mut landmarks = Vec::with_capacity(n);
+        let mut dists: Vec<Vec<f32>> = Vec::with_capacity(n);
+        // Distance from every vertex to its closest landmark so far
+        let mut closest = if vertex_count > self.start {
+            shortest_paths_from(self, self.start).0
+        } else {
+            vec![f32::INFINITY; vertex_count]
+        };
+
+        while landmarks.len() < n {
+            let Some(next) = (0..vertex_count)
+                .filter(|idx| closest[*idx].is_finite() && !landmarks.contains(idx))
+                .max_by(|a, b| closest[*a].total_cmp(&closest[*b]))
+            else {
+                break;
+            };
+
+            let (landmark_dists, _) = shortest_paths_from(self, next);
+            for (closest, dist) in closest.iter_mut().zip(&landmark_dists) {
+                *closest = closest.min(*dist);
+            }
+            landmarks.push(next);
+            dists.push(landmark_dists);
+        }
+
+        Landmarks {
+            landmarks,
+            dists,
+            one_way: self.has_one_way_edges() || self.has_asymmetric_weights(),
+        }
+    }
+}
+
+/// Largest graph `Graph::all_pairs_shortest_paths` accepts.
+pub const ALL_PAIRS_VERTEX_LIMIT: usize = 1000;
+
+/// Number of edges travelled along the path.
+pub fn path_hops(path: &[usize]) -> usize {
+    path.len().saturating_sub(1)
+}
+
+/// Length of the path in pixel steps. After reduction a single hop can span a
+/// long corridor, so this follows the pixels each edge was reduced from. Edges
+/// without known pixels, like the shortcuts taken by `theta_star`, count as a
+/// straight line between their endpoints.
+pub fn path_pixel_length(graph: &Graph<Coord>, path: &[usize]) -> f32 {
+    path.windows(2)
+        .map(|pair| match graph.edge_pixels(pair[0], pair[1]) {
+            Some(pixels) => (pixels.len() - 1) as f32,
+            None => {
+                let a = graph.get_vertices()[pair[0]].get_pos();
+                let b = graph.get_vertices()[pair[1]].get_pos();
+                a.euclidean_distance(b)
+            }
+        })
+        .sum()
+}
+
+fn reconstruct_path(parent_map: &[Option<usize>], target: usize) -> Vec<usize> {
+    let mut path = vec![target];
+    let mut current = target;
+
+    while let Some(parent) = parent_map[current] {
+        path.push(parent);
+        current = parent;
+    }
+
+    path.reverse();
+    path
+}
+
+/// The part of `path` from the first visit of `from` up to the next visit of
+/// `to`, both included. `None` if either isn't on the path or `to` only
+/// comes before `from`.
+pub fn subpath(path: &[usize], from: usize, to: usize) -> Option<Vec<usize>> {
+    let first = path.iter().position(|&v| v == from)?;
+    let last = first + path[first..].iter().position(|&v| v == to)?;
+    Some(path[first..=last].to_vec())
+}
+
+/// How two paths between the same endpoints differ, see `diff_paths`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PathDiff {
+    /// Number of vertices both paths start with
+    pub shared_prefix: usize,
+    /// Number of vertices both paths end with, never overlapping the prefix
+    pub shared_suffix: usize,
+    /// Stretches where the paths go separate ways, as index ranges into `a`
+    /// and `b`. Each range holds the vertices only that path visits there,
+    /// the shared vertices around it are left out, so one of the two may be
+    /// empty when a path takes a direct edge the other detours around.
+    pub segments: Vec<(Range<usize>, Range<usize>)>,
+    /// Cost of `b` minus cost of `a`
+    pub cost_delta: f32,
+}
+
+impl PathDiff {
+    /// Whether the paths visit the same vertices in the same order.
+    pub fn is_same(&self) -> bool {
+        self.segments.is_empty()
+    }
+}
+
+/// Compares two paths, such as the solutions of two algorithms or of the
+/// same one before and after changing weights. Finds the shared start and
+/// end, and in between every stretch where the paths part ways before they
+/// meet again at a common vertex. Paths are expected to be loopless, a path
+/// that visits a vertex twice may have its stretches split at a different
+/// visit than expected. Runs in time linear in the path lengths.
+pub fn diff_paths<T>(graph: &Graph<T>, a: &[usize], b: &[usize]) -> PathDiff {
+    let shared_prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let shared_suffix = a[shared_prefix..]
+        .iter()
+        .rev()
+        .zip(b[shared_prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_end, b_end) = (a.len() - shared_suffix, b.len() - shared_suffix);
+
+    let mut positions_in_b = HashMap::new();
+    for (i, &vertex) in b.iter().enumerate().take(b_end).skip(shared_prefix) {
+        positions_in_b.entry(vertex).or_insert(i);
+    }
+
+    let mut segments = Vec::new();
+    let (mut i, mut j) = (shared_prefix, shared_prefix);
+    while i < a_end || j < b_end {
+        if i < a_end && j < b_end && a[i] == b[j] {
+            i += 1;
+            j += 1;
+            continue;
+        }
+        // Walk `a` until it reaches a vertex `b` still has ahead of it, both
+        // rejoin there. Without one they stay apart until the shared suffix
+        let rejoin = (i..a_end).find_map(|k| {
+            positions_in_b
+                .get(&a[k])
+                .filter(|&&l| l >= j)
+                .map(|&l| (k, l))
+        });
+        let (k, l) = rejoin.unwrap_or((a_end, b_end));
+        segments.push((i..k, j..l));
+        (i, j) = (k, l);
+    }
+
+    PathDiff {
+        shared_prefix,
+        shared_suffix,
+        segments,
+        cost_delta: calculate_cost(graph, b) - calculate_cost(graph, a),
+    }
+}
+
+/// Sum of the edge weights along `solution`, following each edge in the
+/// direction of travel. Steps without an edge add nothing, use `validate_path`
+/// to rule those out.
+pub fn calculate_cost<T>(graph: &Graph<T>, solution: &[usize]) -> f32 {
+    let mut tot_cost = 0.0;
+    for i in 0..solution.len().saturating_sub(1) {
+        let current = solution[i];
+        let next = solution[i + 1];
+        if let Some((_, weight)) = graph.get_vertices()[current]
+            .get_neighbors()
+            .iter()
+            .find(|(idx, _)| *idx == next)
+        {
+            tot_cost += weight;
+        }
+    }
+    tot_cost
+}
+
+fn dfs_iterative<T>(
+    graph: &Graph<T>,
+    start: usize,
+    end: usize,
+    scratch: &mut Scratch,
+) -> Option<Vec<usize>> {
+    let len = graph.get_vertices().len();
+    let stack = &mut scratch.stack;
+    stack.clear();
+    stack.push(start);
+
+    let visited = &mut scratch.visited;
+    reset(visited, len, false);
+
+    let parent_map = &mut scratch.parent_map;
+    reset(parent_map, len, None);
+
+    let trace = &mut scratch.trace;
+    let dists = &mut scratch.dists;
+    if trace.is_on() {
+        reset(dists, len, f32::INFINITY);
+        dists[start] = 0.0;
+    }
+
+    while let Some(current) = stack.pop() {
+        if visited[current] {
+            continue;
+        }
+        visited[current] = true;
+        trace.record(|| SearchEvent::Visit { vertex: current });
+
+        if current == end {
+            return Some(reconstruct_path(parent_map, end));
+        }
+
+        for (neighbor, weight) in graph.get_vertices()[current].get_neighbors() {
+            if !visited[*neighbor] && !scratch.blocked.blocks(current, *neighbor) {
+                parent_map[*neighbor] = Some(current);
+                stack.push(*neighbor);
+                trace.relax(dists, *neighbor, current, *weight);
+            }
+        }
+    }
+    None
+}
+
+fn bfs<T>(graph: &Graph<T>, start: usize, end: usize, scratch: &mut Scratch) -> Option<Vec<usize>> {
+    let len = graph.get_vertices().len();
+    let queue = &mut scratch.queue;
+    queue.clear();
+    queue.push_back(start);
+
+    let visited = &mut scratch.visited;
+    let parent_map = &mut scratch.parent_map;
+    reset(visited, len, false);
+    reset(parent_map, len, None);
+
+    visited[start] = true;
+
+    let trace = &mut scratch.trace;
+    let dists = &mut scratch.dists;
+    if trace.is_on() {
+        reset(dists, len, f32::INFINITY);
+        dists[start] = 0.0;
+    }
+
+    while let Some(current) = queue.pop_front() {
+        trace.record(|| SearchEvent::Visit { vertex: current });
+        if current == end {
+            return Some(reconstruct_path(parent_map, end));
+        }
+
+        for (neighbor_idx, weight) in graph.get_vertices()[current].get_neighbors() {
+            if !visited[*neighbor_idx] && !scratch.blocked.blocks(current, *neighbor_idx) {
+                visited[*neighbor_idx] = true;
+                parent_map[*neighbor_idx] = Some(current);
+                queue.push_back(*neighbor_idx);
+                trace.relax(dists, *neighbor_idx, current, *weight);
+            }
+        }
+    }
+    None
+}
+
+/// Among equally cheap paths the same one is returned every run: vertices
+/// are settled by distance and then by index, and each vertex keeps the first
+/// settled predecessor that reaches it at its final distance.
+pub fn dijkstra<T>(graph: &Graph<T>) -> Option<Vec<usize>> {
+    dijkstra_to_any(graph, &[graph.end], &mut Scratch::default()).map(|(path, _)| path)
+}
+
+/// Dijkstra from `start` that stops at the first of `goals` to be popped,
+/// returning the path to it and the goal that was reached.
+fn dijkstra_to_any<T>(
+    graph: &Graph<T>,
+    goals: &[usize],
+    scratch: &mut Scratch,
+) -> Option<(Vec<usize>, usize)> {
+    let mut is_goal = std::mem::take(&mut scratch.is_goal);
+    reset(&mut is_goal, graph.get_vertices().len(), false);
+    for &goal in goals {
+        is_goal[goal] = true;
+    }
+
+    let reached = dijkstra_search(graph, graph.start, |idx, _| is_goal[idx], scratch);
+    scratch.is_goal = is_goal;
+    reached.map(|goal| (reconstruct_path(&scratch.parent_map, goal), goal))
+}
+
+/// Distances and parents of every vertex reachable from `source`. Unreachable
+/// vertices have a distance of `f32::INFINITY` and no parent, the path to any
+/// reachable target can be rebuilt by following the parents back to `source`.
+pub fn shortest_paths_from<T>(graph: &Graph<T>, source: usize) -> (Vec<f32>, Vec<Option<usize>>) {
+    let mut scratch = Scratch::default();
+    dijkstra_search(graph, source, |_, _| false, &mut scratch);
+    (scratch.dists, scratch.parent_map)
+}
+
+/// Suurballe's algorithm: two paths from `start` to `end` that share no edge,
+/// with the lowest combined cost, the cheaper one first. Useful for a backup
+/// route that survives any single corridor being blocked. `None` if there
+/// aren't two such paths, which is always the case in a perfect maze and
+/// whenever `start` or `end` sits at the end of a dead-end corridor, as
+/// entrances in the border usually do. See `suurballe_sharing_entrances` for
+/// those.
+///
+/// After a first Dijkstra the weights are reduced by its distances so none
+/// is negative, and the edges of the shortest path are turned around at no
+/// cost. A second Dijkstra on that residual graph may walk back along part of
+/// the first path, those edges cancel out and the rest of both searches'
+/// edges is split into the two paths. Expects non-negative weights.
+pub fn suurballe<T>(graph: &Graph<T>) -> Option<[PathResult; 2]> {
+    if graph.start == graph.end {
+        return None;
+    }
+    let [a, b] = disjoint_pair(graph, graph.start, graph.end)?;
+    let (a, b) = (path_result(graph, a), path_result(graph, b));
+    Some(if b.cost < a.cost { [b, a] } else { [a, b] })
+}
+
+/// Like `suurballe`, but both paths share the dead-end corridors leading
+/// from `start` and `end` to their first junction, and only the part in
+/// between is kept disjoint. Entrances in a maze are such corridors, which
+/// every route has to use, so this finds a pair where `suurballe` can't. The
+/// two paths are not edge-disjoint unless neither endpoint is in a dead end.
+pub fn suurballe_sharing_entrances<T>(graph: &Graph<T>) -> Option<[PathResult; 2]> {
+    let mut undirected: Vec<Vec<usize>> = vec![Vec::new(); graph.get_vertices().len()];
+    for (from, vertex) in graph.get_vertices().iter().enumerate() {
+        for &(to, _) in vertex.get_neighbors() {
+            undirected[from].push(to);
+            undirected[to].push(from);
+        }
+    }
+    for neighbors in undirected.iter_mut() {
+        neighbors.sort_unstable();
+        neighbors.dedup();
+    }
+    let head = entrance_corridor(&undirected, graph.start, graph.end);
+    let source = *head.last()?;
+    if source == graph.end {
+        return None;
+    }
+    let tail = entrance_corridor(&undirected, graph.end, graph.start);
+    let target = *tail.last()?;
+    if source == target {
+        return None;
+    }
+
+    let [a, b] = disjoint_pair(graph, source, target)?;
+    let splice = |middle: Vec<usize>| {
+        let path: Vec<usize> = head[..head.len() - 1]
+            .iter()
+            .copied()
+            .chain(middle)
+            .chain(tail.iter().rev().skip(1).copied())
+            .collect();
+        path_result(graph, path)
+    };
+    let (a, b) = (splice(a), splice(b));
+    Some(if b.cost < a.cost { [b, a] } else { [a, b] })
+}
+
+fn path_result<T>(graph: &Graph<T>, path: Vec<usize>) -> PathResult {
+    PathResult {
+        cost: calculate_cost(graph, &path),
+        path,
+        expansions: 0,
+    }
+}
+
+/// The corridor from `endpoint` up to the first junction, if `endpoint` is a
+/// dead end, otherwise just `endpoint`. Stops early at `other`.
+fn entrance_corridor(undirected: &[Vec<usize>], endpoint: usize, other: usize) -> Vec<usize> {
+    let mut corridor = vec![endpoint];
+    let mut previous = None;
+    let mut current = endpoint;
+    while current != other {
+        let width = if previous.is_none() { 1 } else { 2 };
+        if undirected[current].len() != width {
+            break;
+        }
+        let Some(&next) = undirected[current]
+            .iter()
+            .find(|&&next| Some(next) != previous)
+        else {
+            break;
+        };
+        previous = Some(current);
+        current = next;
+        corridor.push(current);
+    }
+    corridor
+}
+
+/// The core of `suurballe` between two vertices, without the entrance
+/// corridors.
+fn disjoint_pair<T>(graph: &Graph<T>, source: usize, target: usize) -> Option<[Vec<usize>; 2]> {
+    let (dists, parents) = shortest_paths_from(graph, source);
+    if !dists[target].is_finite() {
+        return None;
+    }
+    let first = reconstruct_path(&parents, target);
+    let on_first: HashSet<(usize, usize)> =
+        first.windows(2).map(|step| (step[0], step[1])).collect();
+
+    // Reduced weights of every edge between reachable vertices, without the
+    // first path's edges and with them reversed at no cost instead. A reversed
+    // edge replaces any edge already going that way, which would cost more.
+    let mut residual: Vec<Vec<(usize, f32)>> = vec![Vec::new(); dists.len()];
+    for (from, vertex) in graph.get_vertices().iter().enumerate() {
+        if !dists[from].is_finite() {
+            continue;
+        }
+        for &(to, weight) in vertex.get_neighbors() {
+            if on_first.contains(&(from, to)) || on_first.contains(&(to, from)) {
+                continue;
+            }
+            let reduced = (weight + dists[from] - dists[to]).max(0.0);
+            residual[from].push((to, reduced));
+        }
+    }
+    for step in first.windows(2) {
+        residual[step[1]].push((step[0], 0.0));
+    }
+
+    let second = residual_shortest_path(&residual, source, target)?;
+
+    // An edge walked one way by the first path and back by the second is
+    // used by neither
+    let mut used: HashMap<(usize, usize), usize> = HashMap::new();
+    for step in first.windows(2).chain(second.windows(2)) {
+        let reverse = (step[1], step[0]);
+        match used.get_mut(&reverse) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => *used.entry((step[0], step[1])).or_default() += 1,
+        }
+    }
+    let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); dists.len()];
+    for (&(from, to), &count) in &used {
+        for _ in 0..count {
+            outgoing[from].push(to);
+        }
+    }
+    for targets in outgoing.iter_mut() {
+        targets.sort_unstable();
+    }
+
+    let mut walk = || {
+        let mut path = vec![source];
+        let mut current = source;
+        while current != target {
+            current = outgoing[current].pop()?;
+            path.push(current);
+        }
+        Some(path)
+    };
+    Some([walk()?, walk()?])
+}
+
+/// Dijkstra over plain adjacency lists, for searches on a graph derived from
+/// a `Graph` such as `suurballe`'s residual graph.
+fn residual_shortest_path(
+    adjacency: &[Vec<(usize, f32)>],
+    source: usize,
+    target: usize,
+) -> Option<Vec<usize>> {
+    let mut dists = vec![f32::INFINITY; adjacency.len()];
+    let mut parent_map = vec![None; adjacency.len()];
+    let mut heap = BinaryHeap::new();
+    dists[source] = 0.0;
+    heap.push(State {
+        cost: 0.0,
+        position: source,
+    });
+    while let Some(State { cost, position }) = heap.pop() {
+        if position == target {
+            return Some(reconstruct_path(&parent_map, target));
+        }
+        if cost > dists[position] {
+            continue;
+        }
+        for &(next, weight) in &adjacency[position] {
+            let next_dist = cost + weight;
+            if next_dist < dists[next] {
+                dists[next] = next_dist;
+                parent_map[next] = Some(position);
+                heap.push(State {
+                    cost: next_dist,
+                    position: next,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Runs Dijkstra from `source` until `stop_at` accepts a popped vertex and its
+/// distance, or the whole reachable graph has been settled. Distances and
+/// parents are left in `scratch`, the vertex that stopped the search is
+/// returned.
+fn dijkstra_search<T, F>(
+    graph: &Graph<T>,
+    source: usize,
+    stop_at: F,
+    scratch: &mut Scratch,
+) -> Option<usize>
+where
+    F: Fn(usize, f32) -> bool,
+{
+    let len = graph.get_vertices().len();
+    let dists = &mut scratch.dists;
+    let parent_map = &mut scratch.parent_map;
+    let heap = &mut scratch.heap;
+    reset(dists, len, f32::INFINITY);
+    reset(parent_map, len, None);
+    heap.clear();
+
+    dists[source] = 0.0;
+    heap.push(State {
+        cost: 0.0,
+        position: source,
+    });
+
+    let trace = &mut scratch.trace;
+    while let Some(State { cost, position }) = heap.pop() {
+        // A goal's first entry is always its cheapest, so skipping stale
+        // entries before the goal check never skips a goal
+        if cost > dists[position] {
+            continue;
+        }
+        trace.record(|| SearchEvent::Visit { vertex: position });
+
+        if stop_at(position, cost) {
+            return Some(position);
+        }
+
+        for (neighbor_idx, weight) in graph.get_vertices()[position].get_neighbors() {
+            if scratch.blocked.blocks(position, *neighbor_idx) {
+                continue;
+            }
+            let next_dist = cost + weight;
+            if next_dist < dists[*neighbor_idx] {
+                dists[*neighbor_idx] = next_dist;
+                parent_map[*neighbor_idx] = Some(position);
+                heap.push(State {
+                    cost: next_dist,
+                    position: *neighbor_idx,
+                });
+                trace.record(|| SearchEvent::Relax {
+                    vertex: *neighbor_idx,
+                    from: position,
+                    cost: next_dist,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Delta-stepping, a Dijkstra that settles vertices in buckets of width
+/// `delta` and relaxes the edges of a whole bucket across threads. The path
+/// is optimal for non-negative weights, same as `dijkstra`. Relaxations are
+/// generated in parallel but applied in one pass, so the result doesn't
+/// depend on thread scheduling. `delta` is the mean edge weight, which keeps
+/// buckets large enough to be worth splitting without doing much redundant
+/// work. Only pays off on graphs with hundreds of thousands of vertices.
+#[cfg(feature = "parallel")]
+pub fn parallel_dijkstra<T: Sync>(graph: &Graph<T>) -> Option<PathResult> {
+    let vertices = graph.get_vertices();
+    let (weight_sum, edge_count) = vertices
+        .par_iter()
+        .map(|vertex| {
+            let neighbors = vertex.get_neighbors();
+            (
+                neighbors.iter().map(|(_, w)| w).sum::<f32>(),
+                neighbors.len(),
+            )
+        })
+        .reduce(|| (0.0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
+    let delta = if edge_count > 0 && weight_sum > 0.0 {
+        weight_sum / edge_count as f32
+    } else {
+        1.0
+    };
+    let bucket_of = |dist: f32| (dist / delta) as usize;
+
+    let mut dists = vec![f32::INFINITY; vertices.len()];
+    let mut parent_map = vec![None; vertices.len()];
+    let mut buckets: Vec<Vec<usize>> = vec![vec![graph.start]];
+    let mut expansions = 0;
+    dists[graph.start] = 0.0;
+
+    let mut relax = |requests: Vec<(usize, f32, usize)>,
+                     dists: &mut Vec<f32>,
+                     buckets: &mut Vec<Vec<usize>>| {
+        for (target, dist, from) in requests {
+            if dist < dists[target] {
+                dists[target] = dist;
+                parent_map[target] = Some(from);
+                let bucket = bucket_of(dist);
+                if bucket >= buckets.len() {
+                    buckets.resize_with(bucket + 1, Vec::new);
+                }
+                buckets[bucket].push(target);
+            }
+        }
+    };
+
+    let mut current = 0;
+    while current < buckets.len() {
+        let mut settled = Vec::new();
+        while !buckets[current].is_empty() {
+            let mut frontier = std::mem::take(&mut buckets[current]);
+            // Vertices that improved into a lower bucket or were pushed twice
+            frontier.retain(|&idx| bucket_of(dists[idx]) == current);
+            frontier.sort_unstable();
+            frontier.dedup();
+            expansions += frontier.len();
+
+            let requests = edge_requests(graph, &dists, &frontier, |weight| weight <= delta);
+            settled.extend(frontier);
+            relax(requests, &mut dists, &mut buckets);
+        }
+
+        // Heavy edges can't land back in this bucket, one pass is enough
+        settled.sort_unstable();
+        settled.dedup();
+        let requests = edge_requests(graph, &dists, &settled, |weight| weight > delta);
+        relax(requests, &mut dists, &mut buckets);
+
+        // Everything below the next bucket is final now
+        if dists[graph.end].is_finite() && bucket_of(dists[graph.end]) <= current {
+            break;
+        }
+        current += 1;
+    }
+
+    dists[graph.end].is_finite().then(|| PathResult {
+        path: reconstruct_path(&parent_map, graph.end),
+        cost: dists[graph.end],
+        expansions,
+    })
+}
+
+/// Frontiers smaller than this are expanded on the calling thread, in a maze
+/// most buckets hold a handful of vertices and aren't worth handing to rayon.
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_FRONTIER: usize = 1024;
+
+/// The (target, distance, from) relaxations along the edges of `frontier`
+/// whose weight passes `keep`.
+#[cfg(feature = "parallel")]
+fn edge_requests<T: Sync, F>(
+    graph: &Graph<T>,
+    dists: &[f32],
+    frontier: &[usize],
+    keep: F,
+) -> Vec<(usize, f32, usize)>
+where
+    F: Fn(f32) -> bool + Sync,
+{
+    let vertices = graph.get_vertices();
+    let expand = |&idx: &usize| {
+        let dist = dists[idx];
+        vertices[idx]
+            .get_neighbors()
+            .iter()
+            .filter(|(_, weight)| keep(*weight))
+            .map(move |&(neighbor, weight)| (neighbor, dist + weight, idx))
+    };
+    if frontier.len() < PARALLEL_MIN_FRONTIER {
+        frontier.iter().flat_map(expand).collect()
+    } else {
+        frontier.par_iter().flat_map_iter(expand).collect()
+    }
 }
 
-pub fn solve_graph<T>(graph: &Graph<T>, algo: &PathfindingAlgorithm) -> Option<Vec<usize>> {
-    match algo {
-        PathfindingAlgorithm::DepthFirst => dfs_iterative(graph),
-        PathfindingAlgorithm::BreadthFirst => bfs(graph),
-        PathfindingAlgorithm::Dijkstra => dijkstra(graph),
+/// Level-synchronous BFS, each frontier is expanded across threads and the
+/// first thread to reach a vertex becomes its parent. Returns a path with the
+/// fewest hops like `bfs`, though not necessarily the same one.
+#[cfg(feature = "parallel")]
+pub fn parallel_bfs<T: Sync>(graph: &Graph<T>) -> Option<Vec<usize>> {
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+    const UNVISITED: usize = usize::MAX;
+    let vertices = graph.get_vertices();
+    let parents: Vec<AtomicUsize> = (0..vertices.len())
+        .map(|_| AtomicUsize::new(UNVISITED))
+        .collect();
+    parents[graph.start].store(graph.start, AtomicOrdering::Relaxed);
+
+    let mut frontier = vec![graph.start];
+    let claim = |&idx: &usize| {
+        let parents = &parents;
+        vertices[idx]
+            .get_neighbors()
+            .iter()
+            .filter_map(move |&(neighbor, _)| {
+                parents[neighbor]
+                    .compare_exchange(
+                        UNVISITED,
+                        idx,
+                        AtomicOrdering::Relaxed,
+                        AtomicOrdering::Relaxed,
+                    )
+                    .ok()
+                    .map(|_| neighbor)
+            })
+    };
+    while !frontier.is_empty() && parents[graph.end].load(AtomicOrdering::Relaxed) == UNVISITED {
+        frontier = if frontier.len() < PARALLEL_MIN_FRONTIER {
+            frontier.iter().flat_map(claim).collect()
+        } else {
+            frontier.par_iter().flat_map_iter(claim).collect()
+        };
+    }
+
+    if parents[graph.end].load(AtomicOrdering::Relaxed) == UNVISITED {
+        return None;
     }
+    let parent_map: Vec<Option<usize>> = parents
+        .into_iter()
+        .enumerate()
+        .map(|(idx, parent)| {
+            let parent = parent.into_inner();
+            (parent != UNVISITED && idx != graph.start).then_some(parent)
+        })
+        .collect();
+    Some(reconstruct_path(&parent_map, graph.end))
 }
 
-fn reconstruct_path(parent_map: &[Option<usize>], target: usize) -> Vec<usize> {
-    let mut path = vec![target];
-    let mut current = target;
+/// Handles negative edge weights as long as no negative cycle is reachable
+/// from `start`. Note that two-way edges are stored in both directions, so a
+/// single negative two-way edge already forms a negative cycle.
+pub fn bellman_ford<T>(graph: &Graph<T>) -> Result<Option<Vec<usize>>, SolveError> {
+    bellman_ford_search(graph, graph.start, graph.end, &mut Scratch::default())
+}
 
-    while let Some(parent) = parent_map[current] {
-        path.push(parent);
-        current = parent;
+fn bellman_ford_search<T>(
+    graph: &Graph<T>,
+    start: usize,
+    end: usize,
+    scratch: &mut Scratch,
+) -> Result<Option<Vec<usize>>, SolveError> {
+    let vertices = graph.get_vertices();
+    let dists = &mut scratch.dists;
+    let parent_map = &mut scratch.parent_map;
+    reset(dists, vertices.len(), f32::INFINITY);
+    reset(parent_map, vertices.len(), None);
+    dists[start] = 0.0;
+    let trace = &mut scratch.trace;
+
+    // A shortest path has at most V - 1 edges, one more round that still
+    // improves something means there is a negative cycle. There is no
+    // frontier, every round visits all vertices reached so far
+    for round in 0..vertices.len() {
+        let mut relaxed = false;
+        for (current, vertex) in vertices.iter().enumerate() {
+            if dists[current] == f32::INFINITY {
+                continue;
+            }
+            trace.record(|| SearchEvent::Visit { vertex: current });
+            for (neighbor_idx, weight) in vertex.get_neighbors() {
+                if scratch.blocked.blocks(current, *neighbor_idx) {
+                    continue;
+                }
+                let next_dist = dists[current] + weight;
+                if next_dist < dists[*neighbor_idx] {
+                    dists[*neighbor_idx] = next_dist;
+                    parent_map[*neighbor_idx] = Some(current);
+                    relaxed = true;
+                    trace.record(|| SearchEvent::Relax {
+                        vertex: *neighbor_idx,
+                        from: current,
+                        cost: next_dist,
+                    });
+                }
+            }
+        }
+
+        if !relaxed {
+            break;
+        }
+        if round == vertices.len() - 1 {
+            return Err(SolveError::NegativeCycle);
+        }
     }
 
-    path.reverse();
-    path
+    if dists[end] == f32::INFINITY {
+        return Ok(None);
+    }
+    Ok(Some(reconstruct_path(parent_map, end)))
 }
 
-pub fn calculate_cost<T>(graph: &Graph<T>, solution: &[usize]) -> f32 {
-    let mut tot_cost = 0.0;
-    for i in 0..solution.len().saturating_sub(1) {
-        let current = solution[i];
-        let next = solution[i + 1];
-        if let Some((_, weight)) = graph.get_vertices()[current]
-            .get_neighbors()
-            .iter()
-            .find(|(idx, _)| *idx == next)
-        {
-            tot_cost += weight;
+/// Trémaux's maze exploration. Instead of a global visited set it counts how
+/// often each passage has been walked and never walks one a third time. On
+/// reaching a junction seen before through a fresh passage it turns around,
+/// otherwise it takes the least walked passage. The returned path is the walk
+/// as it happened, dead ends and backtracking included.
+fn tremaux<T>(
+    graph: &Graph<T>,
+    start: usize,
+    end: usize,
+    scratch: &mut Scratch,
+) -> Option<Vec<usize>> {
+    let vertices = graph.get_vertices();
+    let passage = |a: usize, b: usize| (a.min(b), a.max(b));
+    let mut marks: HashMap<(usize, usize), u8> = HashMap::new();
+    let visited = &mut scratch.visited;
+    reset(visited, vertices.len(), false);
+
+    let mut walk = vec![start];
+    let mut current = start;
+    let mut previous = None;
+    let mut seen_before = false;
+    visited[start] = true;
+    let trace = &mut scratch.trace;
+    trace.record(|| SearchEvent::Visit { vertex: start });
+
+    while current != end {
+        let marks_to = |next: usize| marks.get(&passage(current, next)).copied().unwrap_or(0);
+        let turn_back =
+            previous.filter(|&p| seen_before && marks_to(p) == 1 && graph.has_edge(current, p));
+
+        let next = match turn_back {
+            Some(p) => p,
+            None => {
+                let next = vertices[current]
+                    .get_neighbors()
+                    .iter()
+                    .map(|(idx, _)| *idx)
+                    .filter(|&idx| idx != current && !scratch.blocked.blocks(current, idx))
+                    .min_by_key(|&idx| marks_to(idx))?;
+                if marks_to(next) >= 2 {
+                    return None;
+                }
+                next
+            }
+        };
+
+        *marks.entry(passage(current, next)).or_insert(0) += 1;
+        if Some(next) == previous {
+            trace.record(|| SearchEvent::Backtrack { vertex: current });
         }
+        trace.record(|| SearchEvent::Visit { vertex: next });
+        walk.push(next);
+        previous = Some(current);
+        current = next;
+        seen_before = visited[next];
+        visited[next] = true;
     }
-    tot_cost
+    Some(walk)
 }
 
-fn dfs_iterative<T>(graph: &Graph<T>) -> Option<Vec<usize>> {
-    let mut stack = vec![graph.start];
-    
-    let mut visited = vec![false; graph.get_vertices().len()];
-    
-    let mut parent_map = vec![None; graph.get_vertices().len()];
+/// Depth-limited DFS with a growing limit. Finds the path with the fewest hops.
+/// Without remembering how deep each vertex was reached, every loop in the
+/// maze doubles the number of paths a round walks.
+fn iddfs<T>(
+    graph: &Graph<T>,
+    start: usize,
+    end: usize,
+    scratch: &mut Scratch,
+) -> Option<Vec<usize>> {
+    // Fewest hops each vertex was reached in this round, a vertex reached
+    // again no shallower has nothing new below it
+    let mut best_depth = vec![usize::MAX; graph.get_vertices().len()];
+    let visited = &mut scratch.visited;
+    reset(visited, best_depth.len(), false);
+    visited[start] = true;
 
-    while let Some(current) = stack.pop() {
-        if current == graph.end {
-            return Some(reconstruct_path(&parent_map, graph.end));
+    let trace = &mut scratch.trace;
+    let dists = &mut scratch.dists;
+    if trace.is_on() {
+        reset(dists, best_depth.len(), f32::INFINITY);
+        dists[start] = 0.0;
+    }
+
+    for limit in 0..graph.get_vertices().len() {
+        let mut path = vec![start];
+        let mut next_edge = vec![0];
+        let mut cutoff = false;
+        best_depth.fill(usize::MAX);
+        best_depth[start] = 0;
+        if limit > 0 {
+            trace.record(|| SearchEvent::Restart {
+                limit: limit as f32,
+            });
         }
+        trace.record(|| SearchEvent::Visit { vertex: start });
 
-        if !visited[current] {
-            visited[current] = true;
+        while let Some(&current) = path.last() {
+            if current == end {
+                return Some(path);
+            }
+
+            let neighbors = graph.get_vertices()[current].get_neighbors();
+            let depth = path.len() - 1;
+            let edge = next_edge.last_mut().unwrap();
 
-            for (neighbor, _) in graph.get_vertices()[current].get_neighbors() {
-                if !visited[*neighbor] {
-                    parent_map[*neighbor] = Some(current);
-                    stack.push(*neighbor);
+            if depth < limit && *edge < neighbors.len() {
+                let (neighbor, weight) = neighbors[*edge];
+                *edge += 1;
+                if depth + 1 < best_depth[neighbor] && !scratch.blocked.blocks(current, neighbor) {
+                    best_depth[neighbor] = depth + 1;
+                    visited[neighbor] = true;
+                    path.push(neighbor);
+                    next_edge.push(0);
+                    trace.relax(dists, neighbor, current, weight);
+                    trace.record(|| SearchEvent::Visit { vertex: neighbor });
                 }
+            } else {
+                let open =
+                    |idx: usize| depth < best_depth[idx] && !scratch.blocked.blocks(current, idx);
+                if depth == limit && neighbors.iter().any(|(idx, _)| open(*idx)) {
+                    cutoff = true;
+                }
+                path.pop();
+                next_edge.pop();
+                trace.record(|| SearchEvent::Backtrack { vertex: current });
             }
         }
+
+        // Nothing was pruned by the limit, so a deeper search won't find anything new
+        if !cutoff {
+            return None;
+        }
     }
     None
 }
 
-fn bfs<T>(graph: &Graph<T>) -> Option<Vec<usize>> {
-    let mut queue = VecDeque::new();
-    queue.push_back(graph.start);
+/// Iterative deepening A*. Like `iddfs` but the limit is on `cost + heuristic`
+/// rather than the depth, which gives the cheapest path on weighted graphs.
+fn ida_star<T: Distance>(
+    graph: &Graph<T>,
+    start: usize,
+    end: usize,
+    scratch: &mut Scratch,
+) -> Option<Vec<usize>> {
+    let vertices = graph.get_vertices();
+    let goal = vertices[end].get_pos();
+    let heuristic = |idx: usize| vertices[idx].get_pos().distance(goal, graph.heuristic);
 
-    let mut visited = vec![false; graph.get_vertices().len()];
-    let mut parent_map = vec![None; graph.get_vertices().len()];
+    let mut on_path = vec![false; vertices.len()];
+    let visited = &mut scratch.visited;
+    reset(visited, vertices.len(), false);
+    visited[start] = true;
+    let mut threshold = heuristic(start);
+    let trace = &mut scratch.trace;
+    let mut first = true;
 
-    visited[graph.start] = true;
+    loop {
+        let mut path = vec![start];
+        let mut costs = vec![0.0];
+        let mut next_edge = vec![0];
+        let mut next_threshold = f32::INFINITY;
+        on_path[start] = true;
+        if !first {
+            trace.record(|| SearchEvent::Restart { limit: threshold });
+        }
+        first = false;
+        trace.record(|| SearchEvent::Visit { vertex: start });
 
-    while let Some(current) = queue.pop_front() {
+        while let Some(&current) = path.last() {
+            if current == end {
+                return Some(path);
+            }
+
+            let neighbors = vertices[current].get_neighbors();
+            let cost = *costs.last().unwrap();
+            let edge = next_edge.last_mut().unwrap();
+
+            if *edge < neighbors.len() {
+                let (neighbor, weight) = neighbors[*edge];
+                *edge += 1;
+                if on_path[neighbor] || scratch.blocked.blocks(current, neighbor) {
+                    continue;
+                }
+
+                let next_cost = cost + weight;
+                let estimate = next_cost + heuristic(neighbor);
+                if estimate > threshold {
+                    next_threshold = next_threshold.min(estimate);
+                } else {
+                    on_path[neighbor] = true;
+                    visited[neighbor] = true;
+                    path.push(neighbor);
+                    costs.push(next_cost);
+                    next_edge.push(0);
+                    trace.record(|| SearchEvent::Relax {
+                        vertex: neighbor,
+                        from: current,
+                        cost: next_cost,
+                    });
+                    trace.record(|| SearchEvent::Visit { vertex: neighbor });
+                }
+            } else {
+                on_path[current] = false;
+                path.pop();
+                costs.pop();
+                next_edge.pop();
+                trace.record(|| SearchEvent::Backtrack { vertex: current });
+            }
+        }
+
+        if next_threshold == f32::INFINITY {
+            return None;
+        }
+        threshold = next_threshold;
+    }
+}
+
+/// The graph's own heuristic towards `goal`.
+fn goal_distance<T: Distance>(graph: &Graph<T>, goal: usize) -> impl Fn(usize) -> f32 + '_ {
+    let vertices = graph.get_vertices();
+    let goal = vertices[goal].get_pos();
+    move |idx| vertices[idx].get_pos().distance(goal, graph.heuristic)
+}
+
+/// A* with the heuristic scaled by `epsilon`. At `epsilon = 1.0` this is plain
+/// A* and the path is optimal, larger values make the search greedier so it
+/// expands fewer vertices at the price of a path at most `epsilon` times the
+/// optimal cost. Vertices are never reopened once expanded.
+pub fn astar_weighted<T: Distance>(graph: &Graph<T>, epsilon: f32) -> Option<PathResult> {
+    astar_path(
+        graph,
+        graph.start,
+        graph.end,
+        goal_distance(graph, graph.end),
+        epsilon,
+    )
+}
+
+/// How much `solve_auto` trades path cost for search speed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    /// Weighted A* leaning hard on the heuristic, close to a greedy
+    /// best-first search. Few expansions, the path can be well off optimal.
+    Fastest,
+    /// A* slightly weighted towards the goal, a path at most 1.2 times the
+    /// optimal cost for somewhat fewer expansions than `Optimal`.
+    Balanced,
+    /// A cheapest path, by A* when the heuristic allows and Dijkstra otherwise.
+    Optimal,
+}
+
+/// The search `solve_auto` picked for a preset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AutoAlgorithm {
+    Dijkstra,
+    AStar,
+    /// A* with the heuristic scaled by the given epsilon, see `astar_weighted`
+    WeightedAStar(f32),
+}
+
+impl fmt::Display for AutoAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AutoAlgorithm::Dijkstra => write!(f, "Dijkstra"),
+            AutoAlgorithm::AStar => write!(f, "A*"),
+            AutoAlgorithm::WeightedAStar(epsilon) => write!(f, "weighted A* (epsilon {epsilon})"),
+        }
+    }
+}
+
+/// Solves `graph` with an algorithm suited to `preset`, for when picking one
+/// by hand isn't worth it. Returns the algorithm that ran along with the
+/// path, `Ok(None)` when `end` can't be reached. Bad endpoints and weights
+/// fail the same way they do for `Solver::solve`.
+///
+/// `Optimal` only trusts A* if no edge is cheaper than the heuristic's
+/// estimate between its ends, which custom step weights can break, and falls
+/// back to Dijkstra if one is.
+pub fn solve_auto<T: Distance>(
+    graph: &Graph<T>,
+    preset: Preset,
+) -> Result<Option<(AutoAlgorithm, PathResult)>, SolveError> {
+    validate_endpoints(graph, graph.start, graph.end)?;
+    validate_weights(graph)?;
+    let algorithm = match preset {
+        Preset::Fastest => AutoAlgorithm::WeightedAStar(5.0),
+        Preset::Balanced => AutoAlgorithm::WeightedAStar(1.2),
+        Preset::Optimal if heuristic_is_consistent(graph) => AutoAlgorithm::AStar,
+        Preset::Optimal => AutoAlgorithm::Dijkstra,
+    };
+    let result = match algorithm {
+        AutoAlgorithm::Dijkstra => astar_weighted(graph, 0.0),
+        AutoAlgorithm::AStar => astar_weighted(graph, 1.0),
+        AutoAlgorithm::WeightedAStar(epsilon) => astar_weighted(graph, epsilon),
+    };
+    Ok(result.map(|result| (algorithm, result)))
+}
+
+/// Whether every edge costs at least the heuristic's estimate between its
+/// ends, which keeps A* optimal without reopening vertices.
+fn heuristic_is_consistent<T: Distance>(graph: &Graph<T>) -> bool {
+    let vertices = graph.get_vertices();
+    vertices.iter().all(|vertex| {
+        vertex.get_neighbors().iter().all(|&(next, weight)| {
+            let estimate = vertex
+                .get_pos()
+                .distance(vertices[next].get_pos(), graph.heuristic);
+            weight >= estimate * (1.0 - 1e-6)
+        })
+    })
+}
+
+/// A* from `source` to `target` using the ALT heuristic built from
+/// `landmarks`. The landmarks must have been computed on this graph, the path
+/// is optimal and usually found after far fewer expansions than Dijkstra.
+pub fn alt_astar<T>(
+    graph: &Graph<T>,
+    landmarks: &Landmarks,
+    source: usize,
+    target: usize,
+) -> Option<PathResult> {
+    astar_path(
+        graph,
+        source,
+        target,
+        |idx| landmarks.lower_bound(idx, target),
+        1.0,
+    )
+}
+
+/// The heuristic towards one goal, evaluated once for every vertex, so
+/// repeated A* queries to that goal look it up instead of recomputing it
+/// each time a vertex is pushed. Worth it for the ALT heuristic, which goes
+/// over every landmark per call. The positional heuristics are about as cheap
+/// to compute as to look up. Costs one `f32` per vertex and is only valid for
+/// the graph it was built on.
+#[derive(Debug, Clone)]
+pub struct HeuristicTable {
+    goal: usize,
+    values: Vec<f32>,
+}
+
+impl HeuristicTable {
+    /// The graph's own `heuristic` towards `goal`.
+    pub fn new<T: Distance>(graph: &Graph<T>, goal: usize) -> Self {
+        let vertices = graph.get_vertices();
+        let target = vertices[goal].get_pos();
+        Self {
+            goal,
+            values: vertices
+                .iter()
+                .map(|vertex| vertex.get_pos().distance(target, graph.heuristic))
+                .collect(),
+        }
+    }
+
+    /// The ALT bound from `landmarks` towards `goal`, see `alt_astar`.
+    pub fn from_landmarks<T>(graph: &Graph<T>, landmarks: &Landmarks, goal: usize) -> Self {
+        Self {
+            goal,
+            values: (0..graph.get_vertices().len())
+                .map(|idx| landmarks.lower_bound(idx, goal))
+                .collect(),
+        }
+    }
+
+    pub fn get_goal(&self) -> usize {
+        self.goal
+    }
+}
+
+/// A* from `source` to the table's goal, taking the heuristic from `table`.
+/// Returns the same path cost as searching with the heuristic the table was
+/// built from.
+pub fn astar_cached<T>(
+    graph: &Graph<T>,
+    table: &HeuristicTable,
+    source: usize,
+) -> Option<PathResult> {
+    astar_path(graph, source, table.goal, |idx| table.values[idx], 1.0)
+}
+
+/// A* from `source` to `target` in a scratch space of its own.
+fn astar_path<T, H>(
+    graph: &Graph<T>,
+    source: usize,
+    target: usize,
+    heuristic: H,
+    epsilon: f32,
+) -> Option<PathResult>
+where
+    H: Fn(usize) -> f32,
+{
+    let mut scratch = Scratch::default();
+    let (reached, expansions) = astar_search(
+        graph,
+        source,
+        heuristic,
+        epsilon,
+        |idx, _| idx == target,
+        &mut scratch,
+    );
+    reached.map(|target| PathResult {
+        path: reconstruct_path(&scratch.parent_map, target),
+        cost: scratch.dists[target],
+        expansions,
+    })
+}
+
+/// The A* loop behind `Solver`, `astar_weighted`, `alt_astar`, `astar_cached`
+/// and `solve_auto`. Pops vertices by cost so far plus `epsilon` times
+/// `heuristic`, skipping whatever `scratch.blocked` rules out, until
+/// `stop_at` accepts a popped vertex and its priority. Returns that vertex
+/// along with how many vertices were expanded before it. Distances and
+/// parents are left in `scratch`, the closed set in `scratch.visited`.
+///
+/// Vertices are never reopened once expanded, so the path is the cheapest
+/// only when `epsilon` is 0, or 1 with a consistent heuristic.
+fn astar_search<T, H, F>(
+    graph: &Graph<T>,
+    source: usize,
+    heuristic: H,
+    epsilon: f32,
+    stop_at: F,
+    scratch: &mut Scratch,
+) -> (Option<usize>, usize)
+where
+    H: Fn(usize) -> f32,
+    F: Fn(usize, f32) -> bool,
+{
+    let vertices = graph.get_vertices();
+    let len = vertices.len();
+    let dists = &mut scratch.dists;
+    let parent_map = &mut scratch.parent_map;
+    let closed = &mut scratch.visited;
+    let heap = &mut scratch.heap;
+    reset(dists, len, f32::INFINITY);
+    reset(parent_map, len, None);
+    reset(closed, len, false);
+    heap.clear();
+    // Skips calling the heuristic at all for plain Dijkstra order
+    let priority = |idx: usize, dist: f32| {
+        if epsilon == 0.0 {
+            dist
+        } else {
+            dist + epsilon * heuristic(idx)
+        }
+    };
+
+    dists[source] = 0.0;
+    heap.push(State {
+        cost: priority(source, 0.0),
+        position: source,
+    });
+
+    let trace = &mut scratch.trace;
+    let mut expansions = 0;
+    while let Some(State { cost, position }) = heap.pop() {
+        if closed[position] {
+            continue;
+        }
+        closed[position] = true;
+        trace.record(|| SearchEvent::Visit { vertex: position });
+        if stop_at(position, cost) {
+            return (Some(position), expansions);
+        }
+        expansions += 1;
+
+        for &(neighbor_idx, weight) in vertices[position].get_neighbors() {
+            if closed[neighbor_idx] || scratch.blocked.blocks(position, neighbor_idx) {
+                continue;
+            }
+            let next_dist = dists[position] + weight;
+            if next_dist < dists[neighbor_idx] {
+                dists[neighbor_idx] = next_dist;
+                parent_map[neighbor_idx] = Some(position);
+                heap.push(State {
+                    cost: priority(neighbor_idx, next_dist),
+                    position: neighbor_idx,
+                });
+                trace.record(|| SearchEvent::Relax {
+                    vertex: neighbor_idx,
+                    from: position,
+                    cost: next_dist,
+                });
+            }
+        }
+    }
+    (None, expansions)
+}
+
+/// Dijkstra that also charges `penalty` every time the direction of travel
+/// changes, including the bends inside corridors that reduction collapsed.
+/// Among paths of equal length this prefers the one with the fewest turns,
+/// a large penalty even trades extra length for straighter paths. The search
+/// runs over (vertex, incoming direction) pairs, so it does up to nine times
+/// the work of plain Dijkstra. The returned cost leaves the penalties out.
+pub fn dijkstra_with_turn_penalty(graph: &Graph<Coord>, penalty: f32) -> Option<PathResult> {
+    let vertices = graph.get_vertices();
+    let edge_turns = edge_turns(graph);
+
+    let state = |vertex: usize, direction: usize| vertex * DIRECTIONS + direction;
+    let mut dists = vec![f32::INFINITY; vertices.len() * DIRECTIONS];
+    let mut parent_map = vec![None; vertices.len() * DIRECTIONS];
+    let mut heap = BinaryHeap::new();
+    let mut expansions = 0;
+
+    let source = state(graph.start, NO_DIRECTION);
+    dists[source] = 0.0;
+    heap.push(State {
+        cost: 0.0,
+        position: source,
+    });
+
+    while let Some(State { cost, position }) = heap.pop() {
+        if cost > dists[position] {
+            continue;
+        }
+        let (current, direction) = (position / DIRECTIONS, position % DIRECTIONS);
         if current == graph.end {
-            return Some(reconstruct_path(&parent_map, graph.end));
+            let path = reconstruct_path(&parent_map, position)
+                .into_iter()
+                .map(|state| state / DIRECTIONS)
+                .collect::<Vec<_>>();
+            return Some(PathResult {
+                cost: calculate_cost(graph, &path),
+                path,
+                expansions,
+            });
         }
+        expansions += 1;
 
-        for (neighbor_idx, _) in graph.get_vertices()[current].get_neighbors() {
-            if !visited[*neighbor_idx] {
-                visited[*neighbor_idx] = true;
-                parent_map[*neighbor_idx] = Some(current);
-                queue.push_back(*neighbor_idx);
+        let neighbors = vertices[current].get_neighbors();
+        for (&(neighbor, weight), &(leave, enter, bends)) in
+            neighbors.iter().zip(&edge_turns[current])
+        {
+            let turn = direction != NO_DIRECTION && direction != leave;
+            let turns = bends + usize::from(turn);
+            let next = state(neighbor, enter);
+            let next_cost = cost + weight + penalty * turns as f32;
+            if next_cost < dists[next] {
+                dists[next] = next_cost;
+                parent_map[next] = Some(position);
+                heap.push(State {
+                    cost: next_cost,
+                    position: next,
+                });
             }
         }
     }
     None
 }
 
-pub fn dijkstra<T>(graph: &Graph<T>) -> Option<Vec<usize>> {
-    let mut dists = vec![f32::MAX; graph.get_vertices().len()];
-    let mut parent_map = vec![None; graph.get_vertices().len()];
+/// The path from start to end with the fewest changes of direction, no
+/// matter how long it gets. Like `dijkstra_with_turn_penalty` it searches
+/// (vertex, incoming direction) pairs, but the cost of an edge is only the
+/// turns it takes. That would be a 0-1 BFS on an unreduced graph, corridors
+/// collapsed by reduction can bend several times though, so the frontier is
+/// kept in one bucket per turn count instead of a deque. Among paths with
+/// equally few turns any one may be returned. The returned cost is the
+/// length of the path, not its number of turns.
+pub fn fewest_turns(graph: &Graph<Coord>) -> Option<PathResult> {
+    let vertices = graph.get_vertices();
+    let edge_turns = edge_turns(graph);
+
+    let state = |vertex: usize, direction: usize| vertex * DIRECTIONS + direction;
+    let mut turns = vec![usize::MAX; vertices.len() * DIRECTIONS];
+    let mut parent_map = vec![None; vertices.len() * DIRECTIONS];
+    let mut buckets: Vec<Vec<usize>> = vec![Vec::new()];
+    let mut expansions = 0;
+
+    let source = state(graph.start, NO_DIRECTION);
+    turns[source] = 0;
+    buckets[0].push(source);
+
+    let mut current_turns = 0;
+    while current_turns < buckets.len() {
+        let Some(position) = buckets[current_turns].pop() else {
+            current_turns += 1;
+            continue;
+        };
+        if turns[position] < current_turns {
+            continue;
+        }
+        let (current, direction) = (position / DIRECTIONS, position % DIRECTIONS);
+        if current == graph.end {
+            let path = reconstruct_path(&parent_map, position)
+                .into_iter()
+                .map(|state| state / DIRECTIONS)
+                .collect::<Vec<_>>();
+            return Some(PathResult {
+                cost: calculate_cost(graph, &path),
+                path,
+                expansions,
+            });
+        }
+        expansions += 1;
+
+        let neighbors = vertices[current].get_neighbors();
+        for (&(neighbor, _), &(leave, enter, bends)) in neighbors.iter().zip(&edge_turns[current]) {
+            let turn = direction != NO_DIRECTION && direction != leave;
+            let next_turns = current_turns + bends + usize::from(turn);
+            let next = state(neighbor, enter);
+            if next_turns < turns[next] {
+                turns[next] = next_turns;
+                parent_map[next] = Some(position);
+                if buckets.len() <= next_turns {
+                    buckets.resize_with(next_turns + 1, Vec::new);
+                }
+                buckets[next_turns].push(next);
+            }
+        }
+    }
+    None
+}
+
+/// How each edge leaves its source, which direction it enters its target from
+/// and how often it bends in between, indexed like the neighbor lists.
+fn edge_turns(graph: &Graph<Coord>) -> Vec<Vec<(usize, usize, usize)>> {
+    let vertices = graph.get_vertices();
+    vertices
+        .iter()
+        .enumerate()
+        .map(|(from, vertex)| {
+            vertex
+                .get_neighbors()
+                .iter()
+                .map(|&(to, _)| match graph.edge_pixels(from, to) {
+                    Some(pixels) if pixels.len() >= 2 => {
+                        let steps: Vec<usize> = pixels
+                            .windows(2)
+                            .map(|pair| step_direction(&pair[0], &pair[1]))
+                            .collect();
+                        let bends = steps.windows(2).filter(|pair| pair[0] != pair[1]).count();
+                        (steps[0], steps[steps.len() - 1], bends)
+                    }
+                    _ => {
+                        let step = step_direction(vertices[from].get_pos(), vertices[to].get_pos());
+                        (step, step, 0)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Number of direction codes `step_direction` produces.
+const DIRECTIONS: usize = 9;
+
+/// The direction code of the start, which hasn't moved yet.
+const NO_DIRECTION: usize = 4;
+
+/// Encodes the sign of the step from `from` to `to` on both axes as 0..9,
+/// with `NO_DIRECTION` in the middle for not moving at all.
+fn step_direction(from: &Coord, to: &Coord) -> usize {
+    let dx = (to.x() as i64 - from.x() as i64).signum();
+    let dy = (to.y() as i64 - from.y() as i64).signum();
+    ((dy + 1) * 3 + dx + 1) as usize
+}
+
+/// Theta*, an any-angle variant of A*. When relaxing an edge it checks whether
+/// the current vertex's parent can see the neighbor directly, and if so links
+/// them with a straight segment. Consecutive vertices in the returned path are
+/// therefore not necessarily adjacent in the graph, and the cost is the
+/// Euclidean length of the polyline. Returns `None` for graphs without a grid.
+pub fn theta_star(graph: &Graph<Coord>) -> Option<PathResult> {
+    let grid = graph.get_grid()?;
+    let vertices = graph.get_vertices();
+    let goal = vertices[graph.end].get_pos();
+    let heuristic = |idx: usize| vertices[idx].get_pos().euclidean_distance(goal);
+
+    let mut dists = vec![f32::MAX; vertices.len()];
+    let mut parent_map: Vec<Option<usize>> = vec![None; vertices.len()];
+    let mut closed = vec![false; vertices.len()];
     let mut heap = BinaryHeap::new();
+    let mut expansions = 0;
 
     dists[graph.start] = 0.0;
     heap.push(State {
-        cost: 0.0,
+        cost: heuristic(graph.start),
         position: graph.start,
     });
 
-    while let Some(State { cost, position }) = heap.pop() {
+    while let Some(State { position, .. }) = heap.pop() {
         if position == graph.end {
-            return Some(reconstruct_path(&parent_map, graph.end));
+            return Some(PathResult {
+                path: reconstruct_path(&parent_map, graph.end),
+                cost: dists[graph.end],
+                expansions,
+            });
         }
 
-        if cost > dists[position] {
+        if closed[position] {
             continue;
         }
+        closed[position] = true;
+        expansions += 1;
+
+        for (neighbor_idx, weight) in vertices[position].get_neighbors() {
+            if closed[*neighbor_idx] {
+                continue;
+            }
+
+            let neighbor_pos = vertices[*neighbor_idx].get_pos();
+            let (parent, next_dist) = match parent_map[position] {
+                Some(parent) if grid.line_of_sight(vertices[parent].get_pos(), neighbor_pos) => (
+                    parent,
+                    dists[parent] + vertices[parent].get_pos().euclidean_distance(neighbor_pos),
+                ),
+                _ => (position, dists[position] + weight),
+            };
 
-        for (neighbor_idx, weight) in graph.get_vertices()[position].get_neighbors() {
-            let next_dist = cost + weight;
             if next_dist < dists[*neighbor_idx] {
                 dists[*neighbor_idx] = next_dist;
-                parent_map[*neighbor_idx] = Some(position);
+                parent_map[*neighbor_idx] = Some(parent);
                 heap.push(State {
-                    cost: next_dist,
+                    cost: next_dist + heuristic(*neighbor_idx),
                     position: *neighbor_idx,
                 });
             }
@@ -132,6 +2688,298 @@ pub fn dijkstra<T>(graph: &Graph<T>) -> Option<Vec<usize>> {
     None
 }
 
+/// Shortens a solved path by dropping waypoints that can be skipped with a
+/// straight line. From each kept waypoint it looks as far ahead along the path
+/// as it has clear line of sight. Consecutive waypoints without line of sight
+/// are kept as they are, so the result never crosses a wall. Graphs without a
+/// grid get the path back unchanged.
+pub fn smooth_path(graph: &Graph<Coord>, path: &[usize]) -> Vec<usize> {
+    let Some(grid) = graph.get_grid() else {
+        return path.to_vec();
+    };
+    let pos = |i: usize| graph.get_vertices()[path[i]].get_pos();
+
+    let mut smoothed = Vec::new();
+    let mut anchor = 0;
+    while anchor + 1 < path.len() {
+        smoothed.push(path[anchor]);
+        let mut next = anchor + 1;
+        while next + 1 < path.len() && grid.line_of_sight(pos(anchor), pos(next + 1)) {
+            next += 1;
+        }
+        anchor = next;
+    }
+    if let Some(&last) = path.last() {
+        smoothed.push(last);
+    }
+    smoothed
+}
+
+/// Drops the waypoints of `path` that lie on the straight line between the
+/// waypoints around them, such as a junction the path goes straight through.
+/// Unlike `smooth_path` this never changes the route: a waypoint is only
+/// dropped if the corridors on both sides of it run along that line too.
+/// Edges without known pixels count as straight. The waypoints left are
+/// meant for drawing lines between, consecutive ones may no longer share an
+/// edge.
+pub fn simplify_collinear(graph: &Graph<Coord>, path: &[usize]) -> Vec<usize> {
+    let vertices = graph.get_vertices();
+    let pos = |idx: usize| {
+        let pos = vertices[idx].get_pos();
+        (i64::from(pos.x()), i64::from(pos.y()))
+    };
+    let pixels = |a: usize, b: usize| -> Vec<(i64, i64)> {
+        match graph.edge_pixels(a, b) {
+            Some(pixels) => pixels
+                .iter()
+                .map(|p| (i64::from(p.x()), i64::from(p.y())))
+                .collect(),
+            None => vec![pos(a), pos(b)],
+        }
+    };
+
+    let mut simplified: Vec<usize> = path.iter().take(1).copied().collect();
+    for step in path.windows(3) {
+        let (before, middle, after) = (step[0], step[1], step[2]);
+        let (a, b, c) = (pos(*simplified.last().unwrap()), pos(middle), pos(after));
+        let on_line = |(x, y): (i64, i64)| (x - a.0) * (c.1 - a.1) == (y - a.1) * (c.0 - a.0);
+        // Straight on rather than doubling back along the same line
+        let ahead = (b.0 - a.0) * (c.0 - b.0) + (b.1 - a.1) * (c.1 - b.1) > 0;
+        let straight = ahead
+            && on_line(b)
+            && pixels(before, middle).into_iter().all(on_line)
+            && pixels(middle, after).into_iter().all(on_line);
+        if !straight {
+            simplified.push(middle);
+        }
+    }
+    if path.len() > 1 {
+        simplified.push(path[path.len() - 1]);
+    }
+    simplified
+}
+
+/// D* Lite, an incremental version of A* for mazes that change between
+/// queries. It searches backwards from `end` and keeps its distance estimates
+/// around, so after `update_edge` the next `replan` only repairs the part of
+/// the search the change actually affects. Edge weights are copied out of the
+/// graph on creation, changes are only seen by this planner. Weights must not
+/// be negative.
+pub struct DStarLite<T> {
+    positions: Vec<T>,
+    heuristic: Heuristic,
+    neighbors: Vec<Vec<(usize, f32)>>,
+    /// Vertices with an edge into each vertex, which differ from `neighbors`
+    /// once the graph has one-way edges
+    predecessors: Vec<Vec<usize>>,
+    start: usize,
+    end: usize,
+    /// Sum of the heuristic distances `start` has moved, keeps the keys
+    /// already in the queue valid without recomputing them
+    km: f32,
+    g: Vec<f32>,
+    rhs: Vec<f32>,
+    open: BinaryHeap<QueueEntry>,
+    /// Key each vertex is currently queued with, older heap entries are stale
+    queued: Vec<Option<Key>>,
+}
+
+type Key = (f32, f32);
+
+impl<T: Distance + Clone> DStarLite<T> {
+    pub fn new(graph: &Graph<T>) -> Result<Self, SolveError> {
+        validate_endpoints(graph, graph.start, graph.end)?;
+        validate_weights(graph)?;
+        let vertices = graph.get_vertices();
+        let len = vertices.len();
+        let mut predecessors = vec![Vec::new(); len];
+        for (from, vertex) in vertices.iter().enumerate() {
+            for &(to, _) in vertex.get_neighbors() {
+                predecessors[to].push(from);
+            }
+        }
+
+        let mut planner = Self {
+            positions: vertices.iter().map(|v| v.get_pos().clone()).collect(),
+            heuristic: graph.heuristic,
+            neighbors: vertices.iter().map(|v| v.get_neighbors().clone()).collect(),
+            predecessors,
+            start: graph.start,
+            end: graph.end,
+            km: 0.0,
+            g: vec![f32::INFINITY; len],
+            rhs: vec![f32::INFINITY; len],
+            open: BinaryHeap::new(),
+            queued: vec![None; len],
+        };
+        planner.rhs[planner.end] = 0.0;
+        planner.enqueue(planner.end);
+        Ok(planner)
+    }
+
+    /// Sets the weight of the edge between `a` and `b` in both directions,
+    /// adding the edge if it doesn't exist yet. `f32::INFINITY` blocks it,
+    /// NaN and vertices out of range are rejected.
+    pub fn update_edge(&mut self, a: usize, b: usize, weight: f32) -> Result<(), SolveError> {
+        if weight.is_nan() {
+            return Err(SolveError::InvalidWeight {
+                from: a,
+                to: b,
+                weight,
+            });
+        }
+        let vertex_count = self.neighbors.len();
+        if let Some(index) = [a, b].into_iter().find(|&idx| idx >= vertex_count) {
+            return Err(SolveError::VertexOutOfRange {
+                index,
+                vertex_count,
+            });
+        }
+        for (from, to) in [(a, b), (b, a)] {
+            let edges = &mut self.neighbors[from];
+            let mut found = false;
+            for (_, w) in edges.iter_mut().filter(|(idx, _)| *idx == to) {
+                *w = weight;
+                found = true;
+            }
+            if !found {
+                edges.push((to, weight));
+                self.predecessors[to].push(from);
+            }
+        }
+        self.update_vertex(a);
+        self.update_vertex(b);
+        Ok(())
+    }
+
+    /// Moves the start, e.g. as the walker follows the path. The search tree
+    /// is rooted at `end`, so this doesn't throw any work away.
+    pub fn set_start(&mut self, start: usize) {
+        self.km += self.positions[self.start].distance(&self.positions[start], self.heuristic);
+        self.start = start;
+    }
+
+    /// Brings the search up to date with all changes since the last call and
+    /// returns the cheapest path from start to end. `expansions` only counts
+    /// the vertices this call had to expand.
+    pub fn replan(&mut self) -> Option<PathResult> {
+        let expansions = self.compute_shortest_path();
+        if self.g[self.start] == f32::INFINITY {
+            return None;
+        }
+
+        // Walk downhill on g, every vertex's best neighbor is one step closer
+        let mut path = vec![self.start];
+        let mut current = self.start;
+        while current != self.end {
+            let (next, _) = self.neighbors[current]
+                .iter()
+                .map(|&(idx, weight)| (idx, weight + self.g[idx]))
+                .min_by(|a, b| a.1.total_cmp(&b.1))?;
+            if path.len() > self.positions.len() {
+                return None;
+            }
+            path.push(next);
+            current = next;
+        }
+
+        Some(PathResult {
+            path,
+            cost: self.g[self.start],
+            expansions,
+        })
+    }
+
+    fn key(&self, idx: usize) -> Key {
+        let best = self.g[idx].min(self.rhs[idx]);
+        let heuristic = self.positions[self.start].distance(&self.positions[idx], self.heuristic);
+        (best + heuristic + self.km, best)
+    }
+
+    fn enqueue(&mut self, idx: usize) {
+        let key = self.key(idx);
+        self.queued[idx] = Some(key);
+        self.open.push(QueueEntry { key, position: idx });
+    }
+
+    fn update_vertex(&mut self, idx: usize) {
+        if idx != self.end {
+            self.rhs[idx] = self.neighbors[idx]
+                .iter()
+                .map(|&(next, weight)| weight + self.g[next])
+                .fold(f32::INFINITY, f32::min);
+        }
+        if self.g[idx] != self.rhs[idx] {
+            self.enqueue(idx);
+        } else {
+            self.queued[idx] = None;
+        }
+    }
+
+    fn compute_shortest_path(&mut self) -> usize {
+        let mut expansions = 0;
+        while let Some(&QueueEntry { key, position }) = self.open.peek() {
+            let start_key = self.key(self.start);
+            let start_consistent = self.g[self.start] == self.rhs[self.start];
+            if !key_less(key, start_key) && start_consistent {
+                break;
+            }
+            self.open.pop();
+            if self.queued[position] != Some(key) {
+                continue;
+            }
+
+            let new_key = self.key(position);
+            if key_less(key, new_key) {
+                self.enqueue(position);
+                continue;
+            }
+
+            expansions += 1;
+            self.queued[position] = None;
+            if self.g[position] > self.rhs[position] {
+                self.g[position] = self.rhs[position];
+            } else {
+                self.g[position] = f32::INFINITY;
+                self.update_vertex(position);
+            }
+            for i in 0..self.predecessors[position].len() {
+                let predecessor = self.predecessors[position][i];
+                self.update_vertex(predecessor);
+            }
+        }
+        expansions
+    }
+}
+
+fn key_cmp(a: Key, b: Key) -> Ordering {
+    a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1))
+}
+
+fn key_less(a: Key, b: Key) -> bool {
+    key_cmp(a, b) == Ordering::Less
+}
+
+#[derive(Copy, Clone, PartialEq)]
+struct QueueEntry {
+    key: Key,
+    position: usize,
+}
+
+impl Eq for QueueEntry {}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        key_cmp(other.key, self.key)
+    }
+}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 #[derive(Copy, Clone, PartialEq)]
 struct State {
     cost: f32,
@@ -142,10 +2990,15 @@ impl Eq for State {}
 
 impl Ord for State {
     fn cmp(&self, other: &Self) -> Ordering {
+        // total_cmp rather than partial_cmp so a NaN that slips through
+        // still gives the heap a consistent order instead of corrupting it.
+        // Equal costs pop the lower vertex index first, so ties between
+        // equally cheap paths depend on the graph alone and not on the order
+        // entries happened to be pushed in
         other
             .cost
-            .partial_cmp(&self.cost)
-            .unwrap_or(Ordering::Equal)
+            .total_cmp(&self.cost)
+            .then_with(|| other.position.cmp(&self.position))
     }
 }
 
@@ -154,3 +3007,51 @@ impl PartialOrd for State {
         Some(self.cmp(other))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Coord, Grid, LoadOptions};
+
+    /// A short route along the top and a long one around the loop below it
+    fn two_routes() -> Graph<Coord> {
+        let rows: Vec<Vec<bool>> = ["#.#####", "#......", "#.###.#", "#.....#", "#######"]
+            .iter()
+            .map(|row| row.chars().map(|c| c != '#').collect())
+            .collect();
+        Graph::from_grid(Grid::from_rows(&rows).unwrap(), &LoadOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn verify_rejects_paths_from_a_corrupt_parent_map() {
+        let graph = two_routes();
+        let algo = PathfindingAlgorithm::Dijkstra;
+        let mut solver = Solver::new(algo);
+        let path = solver.solve(&graph).unwrap().unwrap();
+        assert!(verify(&graph, &algo, Some(path.clone())).is_ok());
+
+        // `end` claims to be reached straight from `start`, which it isn't
+        // next to
+        let parents = &mut solver.scratch.parent_map;
+        let before_end = parents[graph.end];
+        parents[graph.end] = Some(graph.start);
+        let skipping = reconstruct_path(parents, graph.end);
+        assert_eq!(
+            verify(&graph, &algo, Some(skipping)),
+            Err(VerifyError::MissingEdge {
+                from: graph.start,
+                to: graph.end,
+            })
+        );
+
+        // A vertex on the way lost its parent, so the path stops short of
+        // `start`
+        parents[graph.end] = before_end;
+        parents[path[path.len() - 2]] = None;
+        let cut_short = reconstruct_path(parents, graph.end);
+        assert!(matches!(
+            verify(&graph, &algo, Some(cut_short)),
+            Err(VerifyError::WrongEndpoints { .. })
+        ));
+    }
+}
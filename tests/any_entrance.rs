@@ -0,0 +1,53 @@
+mod common;
+
+use maze_solving::graph::{Coord, Graph};
+use maze_solving::pathfinding::{PathfindingAlgorithm, calculate_cost};
+
+/// Entrances at the top in columns 1 and 9, exits at the bottom in columns
+/// 1 and 7. Straight down column 1 is the cheapest of the four pairings
+const TWO_BY_TWO: &[&str] = &[
+    "#.#######.#", //
+    "#.#######.#", //
+    "#.#######.#", //
+    "#.........#", //
+    "#.#####.###", //
+    "#.#####.###", //
+];
+
+fn vertex_at(graph: &Graph<Coord>, x: u32, y: u32) -> usize {
+    graph
+        .get_vertices()
+        .iter()
+        .position(|vertex| *vertex.get_pos() == Coord::new(x, y))
+        .expect("every open pixel has a vertex")
+}
+
+#[test]
+fn cheapest_of_all_pairings_is_returned() {
+    let graph = common::graph(&common::ascii_rows(TWO_BY_TWO));
+    // Listed so the cheapest pair comes last on both sides
+    let entrances = [vertex_at(&graph, 9, 0), vertex_at(&graph, 1, 0)];
+    let exits = [vertex_at(&graph, 7, 5), vertex_at(&graph, 1, 5)];
+
+    for algo in [
+        PathfindingAlgorithm::Dijkstra,
+        PathfindingAlgorithm::AStar,
+        PathfindingAlgorithm::BellmanFord,
+    ] {
+        let path = graph
+            .solve_any_entrance_to_any_exit(&entrances, &exits, &algo)
+            .unwrap()
+            .unwrap();
+        assert_eq!(path.first(), Some(&entrances[1]), "{algo:?}");
+        assert_eq!(path.last(), Some(&exits[1]), "{algo:?}");
+        assert_eq!(calculate_cost(&graph, &path), 5.0, "{algo:?}");
+    }
+
+    // From the other entrance alone the nearer exit wins
+    let path = graph
+        .solve_any_entrance_to_any_exit(&entrances[..1], &exits, &PathfindingAlgorithm::Dijkstra)
+        .unwrap()
+        .unwrap();
+    assert_eq!(path.last(), Some(&exits[0]));
+    assert_eq!(calculate_cost(&graph, &path), 7.0);
+}
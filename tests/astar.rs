@@ -0,0 +1,118 @@
+mod common;
+
+use maze_solving::graph::{
+    Coord, CornerPolicy, Distance, Graph, GraphBuilder, Heuristic, WeightFn,
+};
+use maze_solving::pathfinding::{
+    PathfindingAlgorithm, Preset, astar_weighted, calculate_cost, solve_auto, solve_graph,
+};
+
+fn cost(graph: &Graph<Coord>, algo: PathfindingAlgorithm) -> Option<f32> {
+    let path = solve_graph(graph, &algo).unwrap()?;
+    Some(calculate_cost(graph, &path))
+}
+
+fn assert_astar_matches_dijkstra(builder: &GraphBuilder) {
+    for seed in 0..8 {
+        for rows in [
+            common::maze_rows(12, seed, 30),
+            common::room_rows(30, seed, 0.25),
+        ] {
+            let graph = common::graph_with(&rows, builder);
+            let expected = cost(&graph, PathfindingAlgorithm::Dijkstra);
+            assert_eq!(cost(&graph, PathfindingAlgorithm::AStar), expected);
+            let auto = solve_auto(&graph, Preset::Optimal)
+                .unwrap()
+                .map(|(_, result)| result.cost);
+            assert_eq!(auto, expected);
+        }
+    }
+}
+
+#[test]
+fn astar_cost_matches_dijkstra() {
+    assert_astar_matches_dijkstra(&GraphBuilder::new());
+}
+
+#[test]
+fn astar_cost_matches_dijkstra_with_diagonals() {
+    assert_astar_matches_dijkstra(&GraphBuilder::new().diagonals(CornerPolicy::Always));
+    assert_astar_matches_dijkstra(&GraphBuilder::new().diagonals(CornerPolicy::NoCornerCutting));
+}
+
+#[test]
+fn octile_astar_matches_dijkstra_where_manhattan_overestimates() {
+    let builder = GraphBuilder::new().diagonals(CornerPolicy::Always);
+    for seed in 0..4 {
+        let graph = common::graph_with(&common::room_rows(20, seed, 0.0), &builder);
+        assert_eq!(graph.heuristic, Heuristic::Octile);
+        let optimal = cost(&graph, PathfindingAlgorithm::Dijkstra).unwrap();
+        assert_eq!(cost(&graph, PathfindingAlgorithm::AStar), Some(optimal));
+
+        let start = graph.get_vertices()[graph.start].get_pos();
+        let end = graph.get_vertices()[graph.end].get_pos();
+        // Octile is exact in an open room, Manhattan counts every diagonal
+        // step twice
+        let octile = start.distance(end, Heuristic::Octile);
+        assert!((octile - optimal).abs() < 1e-3, "{octile} {optimal}");
+        assert!(start.distance(end, Heuristic::Manhattan) > optimal);
+    }
+}
+
+#[test]
+fn chebyshev_astar_matches_dijkstra_with_king_moves() {
+    let builder = GraphBuilder::new()
+        .diagonals(CornerPolicy::Always)
+        .king_moves(true);
+    for seed in 0..4 {
+        for rows in [
+            common::room_rows(20, seed, 0.0),
+            common::room_rows(30, seed, 0.25),
+        ] {
+            let graph = common::graph_with(&rows, &builder);
+            assert_eq!(graph.heuristic, Heuristic::Chebyshev);
+            let optimal = cost(&graph, PathfindingAlgorithm::Dijkstra).unwrap();
+            assert_eq!(cost(&graph, PathfindingAlgorithm::AStar), Some(optimal));
+        }
+
+        // Every step costs 1, so an open room is crossed in as many steps as
+        // the larger of the two distances
+        let graph = common::graph_with(&common::room_rows(20, seed, 0.0), &builder);
+        let start = graph.get_vertices()[graph.start].get_pos();
+        let end = graph.get_vertices()[graph.end].get_pos();
+        assert_eq!(
+            cost(&graph, PathfindingAlgorithm::Dijkstra),
+            Some(start.distance(end, Heuristic::Chebyshev))
+        );
+    }
+}
+
+#[test]
+fn astar_stays_optimal_with_steps_cheaper_than_the_heuristic() {
+    // Steps in the right half cost less than the Manhattan estimate, which
+    // would make A* settle for a costlier path if it trusted the heuristic
+    let cheap_right =
+        WeightFn::new(|from: &Coord, _: &Coord| if from.x() > 15 { 0.1 } else { 1.0 });
+    assert_astar_matches_dijkstra(&GraphBuilder::new().weight(cheap_right));
+}
+
+#[test]
+fn weighted_astar_trades_cost_for_expansions() {
+    for seed in 0..4 {
+        let graph = common::graph(&common::room_rows(40, seed, 0.1));
+        let optimal = cost(&graph, PathfindingAlgorithm::Dijkstra).unwrap();
+        let exact = astar_weighted(&graph, 1.0).unwrap();
+        assert_eq!(exact.cost, optimal);
+
+        // Greedier isn't fewer expansions at every step, but never more than
+        // plain A* and far fewer once the heuristic dominates
+        for epsilon in [1.5, 2.0, 5.0] {
+            let result = astar_weighted(&graph, epsilon).unwrap();
+            assert!(result.cost <= optimal * epsilon, "{epsilon}");
+            assert_eq!(result.cost, calculate_cost(&graph, &result.path));
+            assert!(result.expansions <= exact.expansions, "{epsilon}");
+        }
+        let greedy = astar_weighted(&graph, 5.0).unwrap();
+        assert!(greedy.expansions * 2 < exact.expansions);
+    }
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use maze_solving::pathfinding::{
+    ALGORITHM_NAMES, PathfindingAlgorithm, Solver, dijkstra, solve_graph_avoiding,
+};
+use std::collections::HashSet;
+
+#[test]
+fn blocking_the_only_path_leaves_none_until_unblocked() {
+    let graph = common::graph(&common::maze_rows(8, 2, 0));
+    assert!(graph.is_perfect());
+    let path = dijkstra(&graph).unwrap();
+    let blocked: HashSet<usize> = path[1..path.len() - 1].iter().copied().collect();
+    assert!(!blocked.is_empty());
+
+    for &(name, algo) in ALGORITHM_NAMES {
+        assert_eq!(
+            solve_graph_avoiding(&graph, &algo, &blocked).unwrap(),
+            None,
+            "{name}"
+        );
+
+        // The same solver finds the path again once nothing is blocked
+        let mut solver = Solver::new(algo);
+        assert_eq!(solver.solve_avoiding(&graph, &blocked).unwrap(), None);
+        let found = solver.solve_avoiding(&graph, &HashSet::new()).unwrap();
+        assert!(found.is_some(), "{name}");
+        assert_eq!(solver.solve(&graph).unwrap(), found, "{name}");
+    }
+}
+
+#[test]
+fn blocked_vertices_are_routed_around() {
+    let mut detours = 0;
+    for seed in 0..4 {
+        let graph = common::graph(&common::maze_rows(8, seed, 30));
+        let path = dijkstra(&graph).unwrap();
+        let blocked = HashSet::from([path[path.len() / 2]]);
+        // Some mazes have no other way past the blocked vertex
+        let Some(detour) =
+            solve_graph_avoiding(&graph, &PathfindingAlgorithm::Dijkstra, &blocked).unwrap()
+        else {
+            continue;
+        };
+        assert!(detour.iter().all(|vertex| !blocked.contains(vertex)));
+        assert_eq!(detour.first(), Some(&graph.start));
+        assert_eq!(detour.last(), Some(&graph.end));
+        detours += 1;
+    }
+    assert!(detours > 0);
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use maze_solving::graph::{Coord, Graph, GraphBuilder};
+use maze_solving::pathfinding::{SolveError, bellman_ford, calculate_cost, dijkstra};
+
+/// A short route along the top and a long one around the loop below it
+const TWO_ROUTES: &[&str] = &[
+    "#.#####", //
+    "#......", //
+    "#.###.#", //
+    "#.###.#", //
+    "#.....#", //
+    "#######", //
+];
+
+fn vertex_at(graph: &Graph<Coord>, x: u32, y: u32) -> usize {
+    graph
+        .get_vertices()
+        .iter()
+        .position(|vertex| *vertex.get_pos() == Coord::new(x, y))
+        .expect("every open pixel has a vertex")
+}
+
+/// One vertex per open pixel, so every edge is a single step
+fn two_routes() -> Graph<Coord> {
+    common::graph_with(
+        &common::ascii_rows(TWO_ROUTES),
+        &GraphBuilder::new().skip_reduction(true),
+    )
+}
+
+#[test]
+fn negative_one_way_edge_makes_the_long_route_cheaper() {
+    let mut graph = two_routes();
+    assert_eq!(bellman_ford(&graph).unwrap(), dijkstra(&graph));
+
+    let (a, b) = (vertex_at(&graph, 3, 4), vertex_at(&graph, 4, 4));
+    assert!(graph.set_one_way(a, b));
+    graph.set_edge_weight(a, b, -10.0).unwrap();
+
+    let path = bellman_ford(&graph).unwrap().unwrap();
+    assert!(path.windows(2).any(|pair| pair == [a, b]));
+    // 12 steps around the loop, one of them now costs -10
+    assert_eq!(calculate_cost(&graph, &path), 1.0);
+}
+
+#[test]
+fn negative_two_way_edge_is_a_negative_cycle() {
+    let mut graph = two_routes();
+    let (a, b) = (vertex_at(&graph, 3, 4), vertex_at(&graph, 4, 4));
+    graph.set_edge_weight(a, b, -1.0).unwrap();
+    assert_eq!(bellman_ford(&graph), Err(SolveError::NegativeCycle));
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use maze_solving::graph::{BorderMode, Coord, EndpointStrategy, Graph, GraphBuilder};
+
+/// A snake from (0, 0) to (4, 4) drawn without a frame, so its bends touch
+/// the edge of the image everywhere
+const FRAMELESS: &[&str] = &[
+    ".#...", //
+    ".#.#.", //
+    ".#.#.", //
+    ".#.#.", //
+    "...#.", //
+];
+
+fn endpoints(graph: &Graph<Coord>) -> [Coord; 2] {
+    let vertices = graph.get_vertices();
+    let mut ends = [
+        *vertices[graph.start].get_pos(),
+        *vertices[graph.end].get_pos(),
+    ];
+    ends.sort_by_key(|pos| (pos.x(), pos.y()));
+    ends
+}
+
+#[test]
+fn border_mode_decides_the_openings_of_a_frameless_maze() {
+    let rows = common::ascii_rows(FRAMELESS);
+    let builder = GraphBuilder::new().seed(3);
+
+    // Read from the border pixels, the snake's two halves along the edge
+    // each make one long opening, entered at its corner
+    let detected = common::graph_with(&rows, &builder);
+    assert_eq!(detected.boundary_openings().len(), 2);
+    assert_eq!(endpoints(&detected), [Coord::new(0, 4), Coord::new(4, 0)]);
+
+    // A wall all around leaves no openings, the endpoints are picked at random
+    let walled = common::graph_with(&rows, &builder.clone().border(BorderMode::Wall));
+    assert!(walled.boundary_openings().is_empty());
+    assert_ne!(walled.start, walled.end);
+
+    // Open space all around makes every open border pixel an opening, the
+    // farthest two of them are the snake's ends
+    let open = builder
+        .clone()
+        .border(BorderMode::Open)
+        .endpoints(EndpointStrategy::MaxDiameter);
+    let open = common::graph_with(&rows, &open);
+    assert_eq!(open.boundary_openings().len(), 14);
+    assert_eq!(endpoints(&open), [Coord::new(0, 0), Coord::new(4, 4)]);
+}
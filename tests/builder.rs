@@ -0,0 +1,63 @@
+mod common;
+
+use maze_solving::graph::{
+    BorderMode, Coord, CornerPolicy, Graph, GraphBuilder, Heuristic, WeightFn,
+};
+
+fn live(graph: &Graph<Coord>) -> usize {
+    graph
+        .get_vertices()
+        .iter()
+        .filter(|vertex| !vertex.get_neighbors().is_empty())
+        .count()
+}
+
+#[test]
+fn every_builder_option_takes_effect() {
+    // A 10x10 open room plus the two openings in its walls
+    let rows = common::room_rows(12, 0, 0.0);
+    let builder = GraphBuilder::new()
+        .diagonals(CornerPolicy::Always)
+        .king_moves(true)
+        .skip_reduction(true)
+        .border(BorderMode::Wall)
+        .seed(5)
+        .weight(WeightFn::new(|_, _| 2.0));
+    let options = builder.options();
+    assert_eq!(options.diagonals, Some(CornerPolicy::Always));
+    assert_eq!(options.border, Some(BorderMode::Wall));
+    assert_eq!(options.seed, Some(5));
+    assert!(options.king_moves && options.skip_reduction);
+
+    let defaults = common::graph(&rows);
+    let graph = common::graph_with(&rows, &builder);
+
+    // King moves price diagonals like orthogonal steps
+    assert_eq!(defaults.heuristic, Heuristic::Manhattan);
+    assert_eq!(graph.heuristic, Heuristic::Chebyshev);
+    // Inside the room a pixel reaches all 8 around it
+    let most = |graph: &Graph<Coord>| {
+        graph
+            .get_vertices()
+            .iter()
+            .map(|vertex| vertex.get_neighbors().len())
+            .max()
+    };
+    assert_eq!(most(&defaults), Some(4));
+    assert_eq!(most(&graph), Some(8));
+    // Nothing is reduced away, even in a maze of corridors
+    assert_eq!(live(&graph), 102);
+    let maze = common::maze_rows(6, 1, 3);
+    let open = maze.iter().flatten().filter(|open| **open).count();
+    assert!(live(&common::graph(&maze)) < open);
+    assert_eq!(live(&common::graph_with(&maze, &builder)), open);
+    // The walled border hides both openings
+    assert_eq!(defaults.boundary_openings().len(), 2);
+    assert!(graph.boundary_openings().is_empty());
+    // Every step costs what the weight says
+    assert!(graph.edges().all(|(_, _, weight)| weight == 2.0));
+
+    // The random endpoints a walled border falls back to follow the seed
+    let again = common::graph_with(&rows, &builder);
+    assert_eq!((again.start, again.end), (graph.start, graph.end));
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use maze_solving::graph::GraphBuilder;
+use maze_solving::pathfinding::{PathfindingAlgorithm, solve_graph};
+
+/// A five pixel wide corridor from left to right, cut by a wall one pixel
+/// thick and with a speck of wall in it
+const BROKEN_CORRIDOR: &[&str] = &[
+    "###############", //
+    ".......#.......", //
+    ".......#.......", //
+    "...#...#.......", //
+    ".......#.......", //
+    ".......#.......", //
+    "###############", //
+];
+
+#[test]
+fn cleanup_drops_specks_and_bridges_thin_walls() {
+    let rows = common::ascii_rows(BROKEN_CORRIDOR);
+    let raw = common::graph_with(&rows, &GraphBuilder::new().seed(1));
+    assert_eq!(
+        solve_graph(&raw, &PathfindingAlgorithm::BreadthFirst).unwrap(),
+        None
+    );
+
+    let cleaned = common::graph_with(&rows, &GraphBuilder::new().seed(1).cleanup_radius(1));
+    let grid = cleaned.get_grid().unwrap();
+    assert!(grid.is_open(3, 3));
+    // Next to the outer walls the cut has too many walls around it to go
+    assert!((2..5).all(|y| grid.is_open(7, y)));
+    // The walls along the top and bottom stay
+    assert!((0..15).all(|x| !grid.is_open(x, 0) && !grid.is_open(x, 6)));
+
+    let path = solve_graph(&cleaned, &PathfindingAlgorithm::BreadthFirst)
+        .unwrap()
+        .unwrap();
+    let pos = |idx: usize| *cleaned.get_vertices()[idx].get_pos();
+    assert_eq!(pos(path[0]).x().min(pos(path[path.len() - 1]).x()), 0);
+    assert_eq!(pos(path[0]).x().max(pos(path[path.len() - 1]).x()), 14);
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "image")]
+
+mod common;
+
+use image::ImageFormat;
+use maze_solving::graph::{Coord, Graph};
+use maze_solving::pathfinding::{PathfindingAlgorithm, calculate_cost, solve_graph};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Runs the binary in the temp dir with `args`, feeding it `stdin`, and
+/// returns whether it succeeded and what it printed.
+fn output(args: &[&str], stdin: &[u8]) -> (bool, String) {
+    let dir = common::temp_file("cli", &[]).with_file_name("cli-run");
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_maze-solving"))
+        .args(args)
+        .current_dir(&dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    let output = child.wait_with_output().unwrap();
+    let printed = String::from_utf8_lossy(&output.stdout).into_owned()
+        + &String::from_utf8_lossy(&output.stderr);
+    (output.status.success(), printed)
+}
+
+/// Like `output`, but panics if the binary fails.
+fn run(args: &[&str], stdin: &[u8]) -> String {
+    let (success, printed) = output(args, stdin);
+    assert!(success, "{printed}");
+    printed
+}
+
+#[test]
+fn png_from_stdin_solves_like_from_bytes() {
+    let png = common::encode(&common::maze_rows(6, 3, 4), ImageFormat::Png, 1);
+    let graph = Graph::from_bytes(&png).unwrap();
+    let path = solve_graph(&graph, &PathfindingAlgorithm::Dijkstra)
+        .unwrap()
+        .unwrap();
+    let cost = calculate_cost(&graph, &path);
+
+    let stdout = run(&["--algo", "dijkstra", "-"], &png);
+    assert!(stdout.contains(&format!("with cost {cost} ")), "{stdout}");
+}
+
+#[test]
+fn start_and_end_snap_to_the_nearest_vertices() {
+    let png = common::encode(&common::maze_rows(6, 3, 4), ImageFormat::Png, 1);
+    let mut graph = Graph::from_bytes(&png).unwrap();
+    graph.compact();
+    graph.start = graph.snap_to_vertex(&Coord::new(3, 3), 64).unwrap();
+    graph.end = graph.snap_to_vertex(&Coord::new(9, 11), 64).unwrap();
+    let path = solve_graph(&graph, &PathfindingAlgorithm::Dijkstra)
+        .unwrap()
+        .unwrap();
+    let cost = calculate_cost(&graph, &path);
+
+    let args = ["--start", "3,3", "--end", "9,11", "--algo", "dijkstra", "-"];
+    let stdout = run(&args, &png);
+    let snapped = graph.get_vertices()[graph.start].get_pos();
+    assert!(
+        stdout.contains(&format!("--start 3,3 snapped to {snapped}")),
+        "{stdout}"
+    );
+    assert!(stdout.contains(&format!("with cost {cost} ")), "{stdout}");
+}
+
+#[test]
+fn points_outside_the_image_or_malformed_are_errors() {
+    let png = common::encode(&common::maze_rows(6, 3, 4), ImageFormat::Png, 1);
+    let (success, printed) = output(&["--start", "13,2", "-"], &png);
+    assert!(!success);
+    assert!(
+        printed.contains("--start 13,2 is outside the 13x13 image"),
+        "{printed}"
+    );
+
+    let (success, printed) = output(&["--end", "1,x", "-"], &png);
+    assert!(!success);
+    assert!(printed.contains("--end"), "{printed}");
+}
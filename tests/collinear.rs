@@ -0,0 +1,52 @@
+mod common;
+
+use maze_solving::graph::{Coord, Graph};
+use maze_solving::pathfinding::{dijkstra, simplify_collinear};
+
+/// A corridor straight down past a junction at (1, 2) with a dead end off
+/// to its right
+const STRAIGHT_THROUGH: &[&str] = &[
+    "#.###", //
+    "#.###", //
+    "#..##", //
+    "#.###", //
+    "#.###", //
+];
+
+/// The same junction, but the way out turns right at it
+const TURN_AT_JUNCTION: &[&str] = &[
+    "#.###", //
+    "#.###", //
+    "#....", //
+    "#.###", //
+    "#####", //
+];
+
+fn positions(graph: &Graph<Coord>, path: &[usize]) -> Vec<Coord> {
+    path.iter()
+        .map(|&idx| *graph.get_vertices()[idx].get_pos())
+        .collect()
+}
+
+#[test]
+fn straight_through_junction_is_dropped() {
+    let graph = common::graph(&common::ascii_rows(STRAIGHT_THROUGH));
+    let path = dijkstra(&graph).unwrap();
+    assert_eq!(
+        positions(&graph, &path),
+        [Coord::new(1, 0), Coord::new(1, 2), Coord::new(1, 4)]
+    );
+    let simplified = simplify_collinear(&graph, &path);
+    assert_eq!(
+        positions(&graph, &simplified),
+        [Coord::new(1, 0), Coord::new(1, 4)]
+    );
+}
+
+#[test]
+fn turning_junction_is_kept() {
+    let graph = common::graph(&common::ascii_rows(TURN_AT_JUNCTION));
+    let path = dijkstra(&graph).unwrap();
+    assert_eq!(path.len(), 3);
+    assert_eq!(simplify_collinear(&graph, &path), path);
+}
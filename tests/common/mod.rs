@@ -0,0 +1,162 @@
+#![allow(dead_code)]
+
+use maze_solving::graph::{Coord, Distance, Graph, GraphBuilder, Grid};
+use maze_solving::pathfinding::{
+    PathfindingAlgorithm, astar_weighted, calculate_cost, solve_graph, validate_path,
+};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+/// Maze of `cells`x`cells` cells carved by a seeded depth-first backtracker,
+/// `2 * cells + 1` pixels on a side with openings in the top left and bottom
+/// right corner. `loops` random walls between cells are knocked down
+/// afterwards, so anything above 0 gives several routes between the
+/// openings.
+pub fn maze_rows(cells: u32, seed: u64, loops: usize) -> Vec<Vec<bool>> {
+    let side = (2 * cells + 1) as usize;
+    let mut rows = vec![vec![false; side]; side];
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let cells = cells as usize;
+    let mut visited = vec![false; cells * cells];
+    let mut stack: Vec<(usize, usize)> = vec![(0, 0)];
+    visited[0] = true;
+    rows[1][1] = true;
+    while let Some(&(x, y)) = stack.last() {
+        let mut next: Vec<(usize, usize)> = [(0, -1), (0, 1), (-1, 0), (1, 0)]
+            .iter()
+            .filter_map(|&(dx, dy)| Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?)))
+            .filter(|&(nx, ny)| nx < cells && ny < cells && !visited[ny * cells + nx])
+            .collect();
+        next.shuffle(&mut rng);
+        let Some(&(nx, ny)) = next.first() else {
+            stack.pop();
+            continue;
+        };
+
+        visited[ny * cells + nx] = true;
+        rows[y + ny + 1][x + nx + 1] = true;
+        rows[2 * ny + 1][2 * nx + 1] = true;
+        stack.push((nx, ny));
+    }
+
+    // Walls between two cells sit at one odd and one even coordinate
+    let mut walls: Vec<(usize, usize)> = (1..side - 1)
+        .flat_map(|y| (1..side - 1).map(move |x| (x, y)))
+        .filter(|&(x, y)| (x + y) % 2 == 1 && !rows[y][x])
+        .collect();
+    walls.shuffle(&mut rng);
+    for &(x, y) in walls.iter().take(loops) {
+        rows[y][x] = true;
+    }
+
+    rows[0][1] = true;
+    rows[side - 1][side - 2] = true;
+    rows
+}
+
+/// A walled `size`x`size` room with about `wall_chance` of its inside
+/// pixels blocked at random and the same openings as `maze_rows`. The open
+/// areas give diagonal moves plenty to choose from.
+pub fn room_rows(size: usize, seed: u64, wall_chance: f64) -> Vec<Vec<bool>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut rows = vec![vec![false; size]; size];
+    for (y, row) in rows.iter_mut().enumerate().take(size - 1).skip(1) {
+        for (x, open) in row.iter_mut().enumerate().take(size - 1).skip(1) {
+            *open =
+                !rng.random_bool(wall_chance) || (x, y) == (1, 1) || (x, y) == (size - 2, size - 2);
+        }
+    }
+    rows[0][1] = true;
+    rows[size - 1][size - 2] = true;
+    rows
+}
+
+/// Rows from a picture of the maze, `#` for a wall and anything else open.
+pub fn ascii_rows(picture: &[&str]) -> Vec<Vec<bool>> {
+    picture
+        .iter()
+        .map(|row| row.chars().map(|c| c != '#').collect())
+        .collect()
+}
+
+/// The graph of `rows` with the default load options.
+pub fn graph(rows: &[Vec<bool>]) -> Graph<Coord> {
+    graph_with(rows, &GraphBuilder::new())
+}
+
+pub fn graph_with(rows: &[Vec<bool>], builder: &GraphBuilder) -> Graph<Coord> {
+    let grid = Grid::from_rows(rows).expect("rows have the same length");
+    builder.build_from_grid(grid).expect("maze has open pixels")
+}
+
+/// `rows` as an image in `format`, walls black and passages white, every
+/// pixel blown up to a `scale`x`scale` block.
+#[cfg(feature = "image")]
+pub fn encode(rows: &[Vec<bool>], format: image::ImageFormat, scale: u32) -> Vec<u8> {
+    let (width, height) = (rows[0].len() as u32, rows.len() as u32);
+    let img = image::RgbImage::from_fn(width * scale, height * scale, |x, y| {
+        let open = rows[(y / scale) as usize][(x / scale) as usize];
+        image::Rgb(if open { [255; 3] } else { [0; 3] })
+    });
+    let mut bytes = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut bytes), format)
+        .expect("encoding to memory can't fail");
+    bytes
+}
+
+/// Writes `bytes` to a file of the given name in a directory of its own for
+/// this test binary, for the loaders that take a path.
+pub fn temp_file(name: &str, bytes: &[u8]) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("maze-solving-tests-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("temp dir is writable");
+    let path = dir.join(name);
+    std::fs::write(&path, bytes).expect("temp dir is writable");
+    path
+}
+
+/// Runs the solvers that promise the cheapest path, Dijkstra, A* and
+/// Bellman-Ford, and asserts that they agree: each path has to pass
+/// `validate_path` and cost the same, the paths themselves may differ. On
+/// graphs where every edge costs 1 BFS has to match them too. Returns the
+/// agreed cost, `None` if none of them finds a path.
+pub fn assert_optimal_agree<T: Distance>(graph: &Graph<T>) -> Option<f32> {
+    let unweighted = graph.edges().all(|(_, _, weight)| weight == 1.0);
+    let mut algos = vec![
+        PathfindingAlgorithm::Dijkstra,
+        PathfindingAlgorithm::AStar,
+        PathfindingAlgorithm::BellmanFord,
+    ];
+    if unweighted {
+        algos.push(PathfindingAlgorithm::BreadthFirst);
+    }
+
+    let costs: Vec<Option<f32>> = algos
+        .iter()
+        .map(|algo| {
+            let path = solve_graph(graph, algo).unwrap_or_else(|err| panic!("{algo:?}: {err}"))?;
+            validate_path(graph, &path).unwrap_or_else(|err| panic!("{algo:?}: {err}"));
+            Some(calculate_cost(graph, &path))
+        })
+        .collect();
+    let weighted = astar_weighted(graph, 1.0).map(|result| result.cost);
+
+    let expected = costs[0];
+    let tolerance = 1e-4 * expected.unwrap_or(0.0).abs().max(1.0);
+    let agrees = |cost: Option<f32>| match (cost, expected) {
+        (Some(cost), Some(expected)) => (cost - expected).abs() <= tolerance,
+        (cost, expected) => cost.is_none() && expected.is_none(),
+    };
+    for (algo, &cost) in algos.iter().zip(&costs) {
+        assert!(
+            agrees(cost),
+            "{algo:?} found {cost:?}, Dijkstra {expected:?}"
+        );
+    }
+    assert!(
+        agrees(weighted),
+        "astar_weighted found {weighted:?}, Dijkstra {expected:?}"
+    );
+    expected
+}
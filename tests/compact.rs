@@ -0,0 +1,93 @@
+mod common;
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use maze_solving::graph::{CornerPolicy, Distance, Graph, GraphBuilder};
+use maze_solving::pathfinding::{PathfindingAlgorithm, calculate_cost, solve_graph};
+
+/// Every edge by the positions it connects, with its weight's bits so the
+/// set can hash it.
+fn edges_by_position<T: Copy + Eq + Hash>(graph: &Graph<T>) -> HashSet<(T, T, u32)> {
+    let vertices = graph.get_vertices();
+    graph
+        .edges()
+        .map(|(from, to, weight)| {
+            (
+                *vertices[from].get_pos(),
+                *vertices[to].get_pos(),
+                weight.to_bits(),
+            )
+        })
+        .collect()
+}
+
+fn assert_compaction_keeps_edges<T>(mut graph: Graph<T>)
+where
+    T: Copy + Eq + Hash + Debug + Distance,
+{
+    let edges = edges_by_position(&graph);
+    let live = graph
+        .get_vertices()
+        .iter()
+        .filter(|vertex| !vertex.get_neighbors().is_empty())
+        .count();
+    let vertices = graph.get_vertices();
+    let endpoints = (
+        *vertices[graph.start].get_pos(),
+        *vertices[graph.end].get_pos(),
+    );
+    let cost = |graph: &Graph<T>| {
+        let path = solve_graph(graph, &PathfindingAlgorithm::Dijkstra).unwrap()?;
+        Some(calculate_cost(graph, &path))
+    };
+    let before = cost(&graph);
+
+    graph.compact();
+
+    assert_eq!(graph.get_vertices().len(), live);
+    assert_eq!(edges_by_position(&graph), edges);
+    let vertices = graph.get_vertices();
+    assert_eq!(
+        (
+            *vertices[graph.start].get_pos(),
+            *vertices[graph.end].get_pos()
+        ),
+        endpoints
+    );
+    assert_eq!(cost(&graph), before);
+}
+
+#[test]
+fn compaction_keeps_edges_between_the_same_positions() {
+    for seed in 0..4 {
+        let rows = common::maze_rows(12, seed, 15);
+        assert_compaction_keeps_edges(common::graph(&rows));
+        let diagonal = GraphBuilder::new().diagonals(CornerPolicy::Always);
+        assert_compaction_keeps_edges(common::graph_with(&rows, &diagonal));
+        let room = common::room_rows(25, seed, 0.3);
+        assert_compaction_keeps_edges(common::graph_with(&room, &diagonal));
+    }
+}
+
+#[test]
+fn compaction_keeps_edges_in_3d() {
+    let layer = |seed| common::maze_rows(6, seed, 4);
+    let graph = Graph::from_layers(&[layer(1), layer(2)]).unwrap();
+    assert_compaction_keeps_edges(graph);
+}
+
+#[test]
+fn compaction_keeps_corridor_pixels() {
+    let mut graph = common::graph(&common::maze_rows(10, 9, 10));
+    let pixels = |graph: &Graph<_>| -> HashSet<_> {
+        graph
+            .edges()
+            .map(|(from, to, _)| graph.edge_pixels(from, to).unwrap().to_vec())
+            .collect()
+    };
+    let before = pixels(&graph);
+    graph.compact();
+    assert_eq!(pixels(&graph), before);
+}
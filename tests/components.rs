@@ -0,0 +1,69 @@
+mod common;
+
+use maze_solving::pathfinding::{PathfindingAlgorithm, solve_verified};
+
+/// Two mazes side by side, each with its own way in at the top and out at
+/// the bottom
+const TWO_MAZES: &[&str] = &[
+    "#.#####.###", //
+    "#...###...#", //
+    "###.#####.#", //
+    "#...###...#", //
+    "#.#####.###", //
+    "#.#####.###", //
+];
+
+#[test]
+fn separate_mazes_split_into_solvable_graphs() {
+    let graph = common::graph(&common::ascii_rows(TWO_MAZES));
+    let parts = graph.split_components();
+    assert_eq!(parts.len(), 2);
+
+    let mut columns = Vec::new();
+    for part in &parts {
+        let vertices = part.get_vertices();
+        let (start, end) = (vertices[part.start].get_pos(), vertices[part.end].get_pos());
+        assert_eq!([start.y(), end.y()].iter().min(), Some(&0));
+        assert_eq!([start.y(), end.y()].iter().max(), Some(&5));
+        assert_eq!(start.x(), end.x());
+        columns.push(start.x());
+
+        let result = solve_verified(part, &PathfindingAlgorithm::Dijkstra).unwrap();
+        assert_eq!(result.cost, 9.0);
+    }
+    columns.sort_unstable();
+    assert_eq!(columns, [1, 7]);
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn each_region_gets_its_own_color() {
+    let rows = common::ascii_rows(TWO_MAZES);
+    let graph = common::graph(&rows);
+    let output = common::temp_file("components.png", &[]);
+    graph.draw_components(&output).unwrap();
+    let img = image::open(&output).unwrap().into_rgb8();
+
+    // Every open pixel takes its region's color, walls stay black
+    let mut colors = [None, None];
+    for (x, y, pixel) in img.enumerate_pixels() {
+        if !rows[y as usize][x as usize] {
+            assert_eq!(pixel.0, [0; 3], "({x}, {y})");
+            continue;
+        }
+        let region = &mut colors[usize::from(x > 5)];
+        assert_eq!(*region.get_or_insert(*pixel), *pixel, "({x}, {y})");
+    }
+    let [Some(left), Some(right)] = colors else {
+        panic!("both regions are drawn");
+    };
+    assert_ne!(left, right);
+    for color in [left, right] {
+        assert_ne!(color.0, [0; 3]);
+        assert_ne!(color.0, [255; 3]);
+    }
+
+    // The colors follow discovery order, so they come out the same every time
+    common::graph(&rows).draw_components(&output).unwrap();
+    assert_eq!(image::open(&output).unwrap().into_rgb8(), img);
+}
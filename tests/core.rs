@@ -0,0 +1,18 @@
+//! Only the grid and pathfinding core, so this runs with
+//! `--no-default-features` as well.
+
+mod common;
+
+use maze_solving::graph::{Graph, Grid, LoadOptions};
+use maze_solving::pathfinding::{ALGORITHM_NAMES, solve_verified};
+
+#[test]
+fn grid_builds_and_solves_without_images() {
+    let rows = common::maze_rows(8, 3, 6);
+    let graph = Graph::from_grid(Grid::from_rows(&rows).unwrap(), &LoadOptions::default()).unwrap();
+    let cost = common::assert_optimal_agree(&graph).unwrap();
+    for &(name, algo) in ALGORITHM_NAMES {
+        let result = solve_verified(&graph, &algo).unwrap_or_else(|err| panic!("{name}: {err}"));
+        assert!(result.cost >= cost, "{name}");
+    }
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use maze_solving::graph::{Coord, CornerPolicy, Graph, GraphBuilder};
+
+/// The diagonal from (1, 1) to (2, 2) squeezes between two walls
+const SQUEEZE: &[&str] = &[
+    "#.###", //
+    "#.###", //
+    "##.##", //
+    "##.##", //
+];
+
+/// The diagonal from (1, 1) to (2, 2) cuts the corner of the wall at (1, 2)
+const ONE_WALL: &[&str] = &[
+    "#.###", //
+    "#..##", //
+    "##.##", //
+    "##.##", //
+];
+
+/// The diagonal from (1, 1) to (2, 2) crosses open space
+const NO_WALLS: &[&str] = &[
+    "#.###", //
+    "#..##", //
+    "#..##", //
+    "##.##", //
+];
+
+fn vertex_at(graph: &Graph<Coord>, x: u32, y: u32) -> usize {
+    graph
+        .get_vertices()
+        .iter()
+        .position(|vertex| *vertex.get_pos() == Coord::new(x, y))
+        .expect("every open pixel has a vertex")
+}
+
+fn has_diagonal(picture: &[&str], policy: CornerPolicy) -> bool {
+    let builder = GraphBuilder::new().diagonals(policy).skip_reduction(true);
+    let graph = common::graph_with(&common::ascii_rows(picture), &builder);
+    let (a, b) = (vertex_at(&graph, 1, 1), vertex_at(&graph, 2, 2));
+    let links = |from: usize, to: usize| {
+        graph.get_vertices()[from]
+            .get_neighbors()
+            .iter()
+            .any(|(idx, _)| *idx == to)
+    };
+    assert_eq!(links(a, b), links(b, a));
+    links(a, b)
+}
+
+#[test]
+fn diagonal_gap_under_each_corner_policy() {
+    for (policy, expected) in [
+        (CornerPolicy::Always, [true, true, true]),
+        (CornerPolicy::NoSqueezing, [false, true, true]),
+        (CornerPolicy::NoCornerCutting, [false, false, true]),
+    ] {
+        for (picture, expected) in [SQUEEZE, ONE_WALL, NO_WALLS].into_iter().zip(expected) {
+            assert_eq!(
+                has_diagonal(picture, policy),
+                expected,
+                "{policy:?} {picture:?}"
+            );
+        }
+    }
+}
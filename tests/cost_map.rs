@@ -0,0 +1,76 @@
+#![cfg(feature = "image")]
+
+mod common;
+
+use image::{GrayImage, ImageFormat, Luma};
+use maze_solving::graph::{Coord, CostMap, Graph, LoadOptions};
+use maze_solving::pathfinding::{calculate_cost, dijkstra};
+use std::path::{Path, PathBuf};
+
+/// A loop around a pillar, both ways around it as long
+const RING: &[&str] = &[
+    "#.###", //
+    "#...#", //
+    "#.#.#", //
+    "#...#", //
+    "###.#", //
+];
+
+fn save_gray(img: &GrayImage, name: &str) -> PathBuf {
+    let mut bytes = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)
+        .unwrap();
+    common::temp_file(name, &bytes)
+}
+
+fn load(maze: &Path, cost_map: Option<CostMap>) -> Graph<Coord> {
+    let options = LoadOptions {
+        cost_map,
+        ..LoadOptions::default()
+    };
+    Graph::from_png_with_options(maze, &options).unwrap()
+}
+
+#[test]
+fn uniform_cost_map_changes_nothing() {
+    let rows = common::maze_rows(8, 2, 10);
+    let maze = common::temp_file("cost_maze.png", &common::encode(&rows, ImageFormat::Png, 1));
+    let (width, height) = (rows[0].len() as u32, rows.len() as u32);
+    let black = save_gray(&GrayImage::new(width, height), "uniform_costs.png");
+
+    let plain = load(&maze, None);
+    let costed = load(&maze, Some(CostMap::new(&black, 10.0)));
+    assert_eq!(
+        costed.edges().collect::<Vec<_>>(),
+        plain.edges().collect::<Vec<_>>()
+    );
+    assert_eq!(dijkstra(&costed), dijkstra(&plain));
+}
+
+#[test]
+fn dijkstra_avoids_the_expensive_side() {
+    let rows = common::ascii_rows(RING);
+    let maze = common::temp_file("cost_ring.png", &common::encode(&rows, ImageFormat::Png, 1));
+    // Brighter, so more expensive, further right
+    let gradient = GrayImage::from_fn(5, 5, |x, _| Luma([(x * 60) as u8]));
+    let gradient = save_gray(&gradient, "gradient_costs.png");
+
+    let plain = load(&maze, None);
+    let costed = load(&maze, Some(CostMap::new(&gradient, 5.0)));
+    let left_of_pillar = |graph: &Graph<Coord>, path: &[usize]| {
+        let pixels = graph.path_to_coords(path);
+        pixels.contains(&(1, 2)) && !pixels.contains(&(3, 2))
+    };
+    // Without costs the tie goes to the right
+    assert!(!left_of_pillar(&plain, &dijkstra(&plain).unwrap()));
+    let path = dijkstra(&costed).unwrap();
+    assert!(left_of_pillar(&costed, &path));
+    assert!(calculate_cost(&costed, &path) > calculate_cost(&plain, &dijkstra(&plain).unwrap()));
+
+    let wrong_size = save_gray(&GrayImage::new(4, 5), "wrong_size_costs.png");
+    let options = LoadOptions {
+        cost_map: Some(CostMap::new(&wrong_size, 5.0)),
+        ..LoadOptions::default()
+    };
+    assert!(Graph::from_png_with_options(&maze, &options).is_err());
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use maze_solving::graph::{Coord, GraphBuilder};
+
+/// A corridor from top to bottom with a branch off to the right that ends
+/// at (5, 3)
+const BRANCH: &[&str] = &[
+    "#.#####", //
+    "#.....#", //
+    "#.###.#", //
+    "#.###.#", //
+    "#.#####", //
+];
+
+fn positions(found: &[(usize, Coord)]) -> Vec<Coord> {
+    found.iter().map(|(_, pos)| *pos).collect()
+}
+
+#[test]
+fn dead_ends_and_junctions_of_a_branch() {
+    let rows = common::ascii_rows(BRANCH);
+    for builder in [
+        GraphBuilder::new(),
+        GraphBuilder::new().skip_reduction(true),
+    ] {
+        let graph = common::graph_with(&rows, &builder);
+        let dead_ends = graph.dead_ends();
+        let junctions = graph.junctions();
+
+        // The openings lead one way only, so they count as dead ends too
+        assert_eq!(
+            positions(&dead_ends),
+            [Coord::new(1, 0), Coord::new(5, 3), Coord::new(1, 4)]
+        );
+        assert_eq!(positions(&junctions), [Coord::new(1, 1)]);
+        let dead_end_indices: Vec<usize> = dead_ends.iter().map(|(idx, _)| *idx).collect();
+        assert!(dead_end_indices.contains(&graph.start));
+        assert!(dead_end_indices.contains(&graph.end));
+        for (idx, pos) in dead_ends.iter().chain(&junctions) {
+            assert_eq!(graph.get_vertices()[*idx].get_pos(), pos);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use maze_solving::graph::GraphBuilder;
+
+/// A corridor with a bend, entered at the top and left on the right
+const BEND: &[&str] = &[
+    "#.##", //
+    "#...", //
+    "####", //
+];
+
+#[test]
+fn debug_output_shows_positions_and_neighbors() {
+    let graph = common::graph_with(
+        &common::ascii_rows(BEND),
+        &GraphBuilder::new().skip_reduction(true),
+    );
+    let debug = format!("{graph:?}");
+    for expected in [
+        "Graph",
+        "start: 0",
+        "end: 3",
+        "Vertex { pos: Coord { x: 1, y: 0 }, neighbors: [(1, 1.0)] }",
+        "Vertex { pos: Coord { x: 3, y: 1 }, neighbors: [(2, 1.0)] }",
+    ] {
+        assert!(debug.contains(expected), "{expected} missing from {debug}");
+    }
+    // The bend at (1, 1) connects up to the entrance and right along the row
+    let bend = &graph.get_vertices()[1];
+    assert_eq!(bend.to_string(), "(1, 1), [(0, 1.0), (2, 1.0)]");
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "image")]
+
+mod common;
+
+use image::ImageFormat;
+use maze_solving::graph::GraphBuilder;
+
+#[test]
+fn debug_stages_save_three_pictures_with_fewer_live_vertices() {
+    let rows = common::maze_rows(8, 3, 6);
+    let maze = common::temp_file("stages.png", &common::encode(&rows, ImageFormat::Png, 1));
+    let dir = maze.with_file_name("stages");
+    let _ = std::fs::remove_dir_all(&dir);
+    let graph = GraphBuilder::new()
+        .debug_stages(&dir)
+        .build_from_png(&maze)
+        .unwrap();
+
+    let live = |name: &str| {
+        let img = image::open(dir.join(name)).unwrap().into_rgb8();
+        assert_eq!(img.dimensions(), (rows[0].len() as u32, rows.len() as u32));
+        img.pixels().filter(|pixel| pixel.0 == [255, 0, 0]).count()
+    };
+    let counts = [
+        live("1_vertices.png"),
+        live("2_neighbors.png"),
+        live("3_reduced.png"),
+    ];
+    let open = rows.iter().flatten().filter(|open| **open).count();
+    assert_eq!(counts[0], open);
+    assert!(
+        counts[0] >= counts[1] && counts[1] >= counts[2],
+        "{counts:?}"
+    );
+    assert!(counts[2] < counts[1]);
+
+    let kept = graph
+        .get_vertices()
+        .iter()
+        .filter(|vertex| !vertex.get_neighbors().is_empty())
+        .count();
+    assert_eq!(counts[2], kept);
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use maze_solving::graph::Coord;
+use maze_solving::pathfinding::{calculate_cost, shortest_paths_from};
+
+/// A corridor from top to bottom with a branch off to the right that ends
+/// 9 steps from the bottom opening, farther than anything else
+const BRANCH: &[&str] = &[
+    "#.#####", //
+    "#.#####", //
+    "#.....#", //
+    "#.###.#", //
+    "#.###.#", //
+    "#.#####", //
+];
+
+/// A loop around a pillar, entered at the top and left at the bottom
+const RING: &[&str] = &[
+    "#.###", //
+    "#...#", //
+    "#.#.#", //
+    "#...#", //
+    "###.#", //
+];
+
+#[test]
+fn tree_diameter_runs_between_the_farthest_ends() {
+    let graph = common::graph(&common::ascii_rows(BRANCH));
+    assert!(graph.is_perfect());
+    let path = graph.diameter_path().unwrap();
+    assert_eq!(calculate_cost(&graph, &path), 9.0);
+
+    let pos = |idx: usize| *graph.get_vertices()[idx].get_pos();
+    let mut ends = [pos(path[0]), pos(path[path.len() - 1])];
+    ends.sort_by_key(|end| (end.x(), end.y()));
+    assert_eq!(ends, [Coord::new(1, 5), Coord::new(5, 4)]);
+}
+
+#[test]
+fn diameter_is_the_longest_shortest_path() {
+    for seed in 0..4 {
+        let graph = common::graph(&common::maze_rows(8, seed, 0));
+        let longest = (0..graph.get_vertices().len())
+            .flat_map(|from| shortest_paths_from(&graph, from).0)
+            .filter(|dist| dist.is_finite())
+            .fold(0.0, f32::max);
+        let path = graph.diameter_path().unwrap();
+        assert_eq!(calculate_cost(&graph, &path), longest);
+    }
+}
+
+#[test]
+fn mazes_with_loops_have_no_diameter() {
+    let graph = common::graph(&common::ascii_rows(RING));
+    assert!(!graph.is_perfect());
+    assert_eq!(graph.diameter_path(), None);
+    assert_eq!(
+        common::graph(&common::maze_rows(8, 1, 5)).diameter_path(),
+        None
+    );
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use maze_solving::graph::{Coord, Graph, GraphBuilder};
+use maze_solving::pathfinding::diff_paths;
+
+/// A short route along the top and a long one around the loop below it
+const TWO_ROUTES: &[&str] = &[
+    "#.#####", //
+    "#......", //
+    "#.###.#", //
+    "#.###.#", //
+    "#.....#", //
+    "#######", //
+];
+
+fn vertex_at(graph: &Graph<Coord>, x: u32, y: u32) -> usize {
+    graph
+        .get_vertices()
+        .iter()
+        .position(|vertex| *vertex.get_pos() == Coord::new(x, y))
+        .expect("every open pixel has a vertex")
+}
+
+fn path(graph: &Graph<Coord>, pixels: &[(u32, u32)]) -> Vec<usize> {
+    pixels
+        .iter()
+        .map(|&(x, y)| vertex_at(graph, x, y))
+        .collect()
+}
+
+#[test]
+fn diff_finds_where_the_routes_part_and_meet() {
+    let builder = GraphBuilder::new().skip_reduction(true);
+    let graph = common::graph_with(&common::ascii_rows(TWO_ROUTES), &builder);
+    let top = path(
+        &graph,
+        &[(1, 0), (1, 1), (2, 1), (3, 1), (4, 1), (5, 1), (6, 1)],
+    );
+    let around = path(
+        &graph,
+        &[
+            (1, 0),
+            (1, 1),
+            (1, 2),
+            (1, 3),
+            (1, 4),
+            (2, 4),
+            (3, 4),
+            (4, 4),
+            (5, 4),
+            (5, 3),
+            (5, 2),
+            (5, 1),
+            (6, 1),
+        ],
+    );
+
+    let diff = diff_paths(&graph, &top, &around);
+    assert_eq!(diff.shared_prefix, 2);
+    assert_eq!(diff.shared_suffix, 2);
+    // Both leave (1, 1) and meet again at (5, 1)
+    assert_eq!(diff.segments, [(2..5, 2..11)]);
+    assert_eq!(diff.cost_delta, 6.0);
+    assert!(!diff.is_same());
+
+    let reversed = diff_paths(&graph, &around, &top);
+    assert_eq!(reversed.segments, [(2..11, 2..5)]);
+    assert_eq!(reversed.cost_delta, -6.0);
+
+    let same = diff_paths(&graph, &top, &top);
+    assert!(same.is_same());
+    assert_eq!(same.cost_delta, 0.0);
+}
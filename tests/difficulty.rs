@@ -0,0 +1,41 @@
+mod common;
+
+use maze_solving::graph::GraphBuilder;
+
+/// A corridor straight from top to bottom
+const CORRIDOR: &[&str] = &[
+    "#.#", //
+    "#.#", //
+    "#.#", //
+    "#.#", //
+    "#.#", //
+    "#.#", //
+    "#.#", //
+];
+
+#[test]
+fn twisty_mazes_score_higher_than_a_straight_corridor() {
+    let corridor = common::graph(&common::ascii_rows(CORRIDOR));
+    // No decisions and no dead ends, only the length counts
+    assert_eq!(corridor.difficulty(), 7f32.log2());
+
+    for seed in 0..4 {
+        let rows = common::maze_rows(10, seed, 0);
+        let maze = common::graph(&rows);
+        assert!(maze.difficulty() > corridor.difficulty(), "seed {seed}");
+
+        // Corridor vertices don't count, so reduction leaves the score alone
+        let full = common::graph_with(&rows, &GraphBuilder::new().skip_reduction(true));
+        assert_eq!(full.difficulty(), maze.difficulty(), "seed {seed}");
+    }
+}
+
+#[test]
+fn unsolvable_mazes_score_zero() {
+    let mut graph = common::graph(&common::maze_rows(6, 1, 0));
+    // The start is an opening with a single way in
+    let start = graph.start;
+    let next = graph.get_vertices()[start].get_neighbors()[0].0;
+    graph.remove_edge(start, next).unwrap();
+    assert_eq!(graph.difficulty(), 0.0);
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use maze_solving::graph::{Coord, Graph};
+use maze_solving::pathfinding::{SolveError, calculate_cost};
+
+/// Enters at the top, with exits 4 steps away on the left, 8 on the right
+/// and 11 at the bottom.
+const THREE_EXITS: &[&str] = &[
+    "#.#####", //
+    "#.....#", //
+    "#.###.#", //
+    "..#....", //
+    "#.#.###", //
+    "###.###", //
+];
+
+fn vertex_at(graph: &Graph<Coord>, x: u32, y: u32) -> usize {
+    graph
+        .get_vertices()
+        .iter()
+        .position(|vertex| *vertex.get_pos() == Coord::new(x, y))
+        .expect("every open pixel has a vertex")
+}
+
+fn three_exits() -> (Graph<Coord>, [usize; 3]) {
+    let mut graph = common::graph(&common::ascii_rows(THREE_EXITS));
+    graph.start = vertex_at(&graph, 1, 0);
+    let exits = [
+        vertex_at(&graph, 0, 3),
+        vertex_at(&graph, 6, 3),
+        vertex_at(&graph, 3, 5),
+    ];
+    (graph, exits)
+}
+
+#[test]
+fn reaches_the_closest_of_three_exits() {
+    let (graph, [left, right, bottom]) = three_exits();
+
+    let (path, goal) = graph
+        .dijkstra_multi(&[bottom, right, left])
+        .unwrap()
+        .unwrap();
+    assert_eq!(goal, left);
+    assert_eq!(path.first(), Some(&graph.start));
+    assert_eq!(path.last(), Some(&left));
+    assert_eq!(calculate_cost(&graph, &path), 4.0);
+
+    let (path, goal) = graph.dijkstra_multi(&[bottom, right]).unwrap().unwrap();
+    assert_eq!(goal, right);
+    assert_eq!(calculate_cost(&graph, &path), 8.0);
+
+    assert_eq!(graph.dijkstra_multi(&[]).unwrap(), None);
+}
+
+#[test]
+fn out_of_range_indices_are_errors() {
+    let (mut graph, [left, ..]) = three_exits();
+    let vertex_count = graph.get_vertices().len();
+
+    assert!(matches!(
+        graph.dijkstra_multi(&[left, vertex_count]),
+        Err(SolveError::VertexOutOfRange { index, .. }) if index == vertex_count
+    ));
+
+    graph.start = vertex_count + 1;
+    assert!(matches!(
+        graph.dijkstra_multi(&[left]),
+        Err(SolveError::EndpointOutOfRange {
+            endpoint: "start",
+            ..
+        })
+    ));
+}
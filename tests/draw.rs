@@ -0,0 +1,252 @@
+#![cfg(feature = "image")]
+
+mod common;
+
+use image::{Rgb, RgbImage};
+use maze_solving::graph::{Coord, DrawOptions, Graph, GraphError};
+use maze_solving::pathfinding::{PathfindingAlgorithm, SearchEvent, dijkstra, solve_graph_traced};
+
+fn maze() -> Graph<Coord> {
+    common::graph(&common::maze_rows(6, 2, 6))
+}
+
+/// The solution drawn over the maze with `options`, read back from `name`.
+fn drawn(graph: &Graph<Coord>, name: &str, options: &DrawOptions) -> RgbImage {
+    let path = dijkstra(graph).unwrap();
+    let output = common::temp_file(name, &[]);
+    graph
+        .draw_path_on(&path, graph.to_image(), &output, options)
+        .unwrap();
+    image::open(&output).unwrap().into_rgb8()
+}
+
+#[test]
+fn scale_multiplies_the_dimensions() {
+    let graph = maze();
+    let (width, height) = graph.to_image().dimensions();
+    for scale in [1, 2, 5] {
+        let options = DrawOptions {
+            scale,
+            ..DrawOptions::default()
+        };
+        let img = drawn(&graph, &format!("scale_{scale}.png"), &options);
+        assert_eq!(img.dimensions(), (width * scale, height * scale));
+    }
+}
+
+#[test]
+fn overflowing_scale_is_an_error() {
+    let graph = maze();
+    let (width, height) = graph.to_image().dimensions();
+    let scale = u32::MAX / 2;
+    let options = DrawOptions {
+        scale,
+        ..DrawOptions::default()
+    };
+    let path = dijkstra(&graph).unwrap();
+    let output = common::temp_file("overflow.png", &[]);
+    let err = graph
+        .draw_path_on(&path, graph.to_image(), &output, &options)
+        .unwrap_err();
+    let expected = GraphError::ScaleTooLarge {
+        width,
+        height,
+        scale,
+    };
+    assert!(err.to_string().ends_with(&expected.to_string()), "{err}");
+}
+
+#[test]
+fn start_and_end_get_their_own_markers() {
+    let graph = maze();
+    let options = DrawOptions::default();
+    let img = drawn(&graph, "markers.png", &options);
+    let start = graph.get_vertices()[graph.start].get_pos();
+    let end = graph.get_vertices()[graph.end].get_pos();
+    assert_eq!(*img.get_pixel(start.x(), start.y()), options.start_color);
+    assert_eq!(*img.get_pixel(end.x(), end.y()), options.end_color);
+
+    // The route in between keeps the path color, away from the markers
+    let path = dijkstra(&graph).unwrap();
+    let middle = graph.get_vertices()[path[path.len() / 2]].get_pos();
+    assert_eq!(*img.get_pixel(middle.x(), middle.y()), options.path_color);
+}
+
+#[test]
+fn marker_radius_sets_the_marker_size() {
+    let graph = maze();
+    let count = |marker_radius: u32| {
+        let options = DrawOptions {
+            marker_radius,
+            ..DrawOptions::default()
+        };
+        let img = drawn(&graph, &format!("radius_{marker_radius}.png"), &options);
+        img.pixels()
+            .filter(|&&pixel| pixel == options.start_color)
+            .count()
+    };
+    assert!(count(1) > 0);
+    assert!(count(3) > count(1));
+}
+
+#[test]
+fn label_only_changes_the_top_left_corner() {
+    let graph = maze();
+    let plain = DrawOptions {
+        scale: 4,
+        ..DrawOptions::default()
+    };
+    let labeled = DrawOptions {
+        label: Some("A* 42".to_string()),
+        ..plain.clone()
+    };
+    let without = drawn(&graph, "unlabeled.png", &plain);
+    let with = drawn(&graph, "labeled.png", &labeled);
+
+    // 5 glyphs 5 pixels wide with a pixel between them, 7 pixels tall, and
+    // a pixel of padding all around
+    let (box_width, box_height) = (5 * 6 - 1 + 2, 7 + 2);
+    let mut changed = 0;
+    for (x, y, pixel) in with.enumerate_pixels() {
+        if x < box_width && y < box_height {
+            changed += usize::from(pixel != without.get_pixel(x, y));
+        } else {
+            assert_eq!(pixel, without.get_pixel(x, y), "({x}, {y})");
+        }
+    }
+    assert!(changed > 0);
+}
+
+#[test]
+fn overlaid_paths_keep_their_colors_where_they_differ() {
+    let graph = common::graph(&common::maze_rows(6, 3, 10));
+    let paths = graph
+        .k_shortest_paths(2, &PathfindingAlgorithm::Dijkstra)
+        .unwrap();
+    assert_eq!(paths.len(), 2);
+    let (first, second) = (&paths[0].path, &paths[1].path);
+    let (red, blue) = (Rgb([255, 0, 0]), Rgb([0, 0, 255]));
+
+    let output = common::temp_file("overlaid.png", &[]);
+    let options = DrawOptions {
+        marker_radius: 0,
+        ..DrawOptions::default()
+    };
+    graph
+        .draw_paths_on(
+            &[(first, red), (second, blue)],
+            graph.to_image(),
+            &output,
+            &options,
+        )
+        .unwrap();
+    let img = image::open(&output).unwrap().into_rgb8();
+
+    let first_pixels = graph.path_to_coords(first);
+    let second_pixels = graph.path_to_coords(second);
+    let ends = [graph.start, graph.end].map(|idx| {
+        let pos = graph.get_vertices()[idx].get_pos();
+        (pos.x(), pos.y())
+    });
+    let mut only_first = 0;
+    for &(x, y) in first_pixels.iter().filter(|pixel| !ends.contains(pixel)) {
+        // The later path is drawn on top where the two overlap
+        let expected = if second_pixels.contains(&(x, y)) {
+            blue
+        } else {
+            only_first += 1;
+            red
+        };
+        assert_eq!(*img.get_pixel(x, y), expected, "({x}, {y})");
+    }
+    for &(x, y) in second_pixels.iter().filter(|pixel| !ends.contains(pixel)) {
+        assert_eq!(*img.get_pixel(x, y), blue, "({x}, {y})");
+    }
+    assert!(only_first > 0);
+}
+
+#[test]
+fn outline_borders_the_path_without_covering_it() {
+    let graph = maze();
+    let outline = Rgb([255, 255, 0]);
+    let options = DrawOptions {
+        marker_radius: 0,
+        outline_color: Some(outline),
+        ..DrawOptions::default()
+    };
+    let img = drawn(&graph, "outline.png", &options);
+    let path = graph.path_to_coords(&dijkstra(&graph).unwrap());
+
+    let mut outlined = 0;
+    for (x, y, pixel) in img.enumerate_pixels() {
+        if *pixel != outline {
+            continue;
+        }
+        outlined += 1;
+        assert!(!path.contains(&(x, y)), "({x}, {y})");
+        let next_to_path = path
+            .iter()
+            .any(|&(px, py)| px.abs_diff(x) <= 1 && py.abs_diff(y) <= 1);
+        assert!(next_to_path, "({x}, {y})");
+    }
+    assert!(outlined > 0);
+    for &(x, y) in &path[1..path.len() - 1] {
+        assert_eq!(*img.get_pixel(x, y), options.path_color, "({x}, {y})");
+    }
+}
+
+#[test]
+fn transparent_background_only_keeps_the_path() {
+    let graph = maze();
+    let options = DrawOptions {
+        marker_radius: 0,
+        transparent_background: true,
+        ..DrawOptions::default()
+    };
+    let path = dijkstra(&graph).unwrap();
+    let output = common::temp_file("transparent_path.png", &[]);
+    graph
+        .draw_path_on(&path, graph.to_image(), &output, &options)
+        .unwrap();
+    let img = image::open(&output).unwrap().into_rgba8();
+    assert_eq!(img.dimensions(), graph.to_image().dimensions());
+
+    // The markers sit on the first and last pixel of the path
+    let pixels = graph.path_to_coords(&path);
+    for (x, y, pixel) in img.enumerate_pixels() {
+        let alpha = if pixels.contains(&(x, y)) { 255 } else { 0 };
+        assert_eq!(pixel.0[3], alpha, "({x}, {y})");
+    }
+    let (x, y) = pixels[pixels.len() / 2];
+    assert_eq!(img.get_pixel(x, y).0[..3], options.path_color.0);
+}
+
+#[test]
+fn trace_frames_end_with_the_path() {
+    let graph = maze();
+    let (path, events) = solve_graph_traced(&graph, &PathfindingAlgorithm::Dijkstra).unwrap();
+    let path = path.unwrap();
+    let visits = events
+        .iter()
+        .filter(|event| matches!(event, SearchEvent::Visit { .. }))
+        .count();
+    let options = DrawOptions {
+        marker_radius: 0,
+        ..DrawOptions::default()
+    };
+
+    for every in [1, 3, 7, visits + 1] {
+        let frames = graph.trace_frames(&events, every, &options).unwrap();
+        assert_eq!(frames.len(), visits / every + 1, "every {every}");
+
+        let pixels = graph.path_to_coords(&path);
+        let last = frames.last().unwrap();
+        for &(x, y) in &pixels[1..pixels.len() - 1] {
+            assert_eq!(*last.get_pixel(x, y), options.path_color, "({x}, {y})");
+        }
+        // Only the last frame shows the path
+        for frame in &frames[..frames.len() - 1] {
+            assert!(frame.pixels().all(|pixel| *pixel != options.path_color));
+        }
+    }
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use maze_solving::pathfinding::{DStarLite, SolveError, calculate_cost, dijkstra};
+
+#[test]
+fn replanning_routes_around_a_blocked_edge() {
+    let graph = common::graph(&common::maze_rows(10, 4, 30));
+    let mut planner = DStarLite::new(&graph).unwrap();
+    let first = planner.replan().unwrap();
+    assert_eq!(first.path, dijkstra(&graph).unwrap());
+
+    // The first edge on the path the maze's loops can get around
+    let (a, b, without) = first
+        .path
+        .windows(2)
+        .find_map(|pair| {
+            let mut without = graph.clone();
+            without.remove_edge(pair[0], pair[1]).unwrap();
+            dijkstra(&without).map(|_| (pair[0], pair[1], without))
+        })
+        .expect("some edge on the path lies on a loop");
+
+    planner.update_edge(a, b, f32::INFINITY).unwrap();
+    let replanned = planner.replan().unwrap();
+    assert!(
+        replanned
+            .path
+            .windows(2)
+            .all(|pair| pair != [a, b] && pair != [b, a])
+    );
+    let expected = calculate_cost(&without, &dijkstra(&without).unwrap());
+    assert_eq!(replanned.cost, expected);
+    assert_eq!(calculate_cost(&without, &replanned.path), expected);
+}
+
+#[test]
+fn updating_a_missing_vertex_is_an_error() {
+    let graph = common::graph(&common::maze_rows(4, 1, 2));
+    let vertex_count = graph.get_vertices().len();
+    let mut planner = DStarLite::new(&graph).unwrap();
+    assert_eq!(
+        planner.update_edge(0, vertex_count, 1.0),
+        Err(SolveError::VertexOutOfRange {
+            index: vertex_count,
+            vertex_count
+        })
+    );
+    assert!(planner.replan().is_some());
+}
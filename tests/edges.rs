@@ -0,0 +1,63 @@
+mod common;
+
+use maze_solving::graph::GraphBuilder;
+
+/// A short route along the top and a long one around the loop below it
+const TWO_ROUTES: &[&str] = &[
+    "#.#####", //
+    "#......", //
+    "#.###.#", //
+    "#.###.#", //
+    "#.....#", //
+    "#######", //
+];
+
+#[test]
+fn counts_each_edge_once() {
+    let rows = common::ascii_rows(TWO_ROUTES);
+    // One edge between every two neighboring open pixels
+    let full = common::graph_with(&rows, &GraphBuilder::new().skip_reduction(true));
+    assert_eq!(full.edges().count(), 16);
+
+    // Reduced to the entrance, the two junctions, the exit and the one
+    // vertex left on the loop
+    let reduced = common::graph(&rows);
+    let edges: Vec<_> = reduced.edges().collect();
+    assert_eq!(edges.len(), 5);
+    assert_eq!(edges.iter().map(|(_, _, weight)| weight).sum::<f32>(), 16.0);
+
+    for graph in [&full, &reduced] {
+        let edges: Vec<_> = graph.edges().collect();
+        assert!(edges.iter().all(|&(a, b, _)| a < b));
+        assert!(edges.is_sorted_by_key(|&(a, _, _)| a));
+        let stored: usize = graph
+            .get_vertices()
+            .iter()
+            .map(|vertex| vertex.get_neighbors().len())
+            .sum();
+        assert_eq!(stored, 2 * edges.len());
+    }
+}
+
+#[test]
+fn edge_pixels_run_between_the_junctions() {
+    let graph = common::graph(&common::ascii_rows(TWO_ROUTES));
+    let vertices = graph.get_vertices();
+    for (a, b, weight) in graph.edges() {
+        let forward = graph.edge_pixels(a, b).unwrap();
+        assert_eq!(forward.first(), Some(vertices[a].get_pos()));
+        assert_eq!(forward.last(), Some(vertices[b].get_pos()));
+        // One pixel per step of the corridor, each next to the one before
+        assert_eq!(forward.len() as f32 - 1.0, weight);
+        assert!(forward.windows(2).all(|step| {
+            step[0].x().abs_diff(step[1].x()) + step[0].y().abs_diff(step[1].y()) == 1
+        }));
+
+        let mut backward = graph.edge_pixels(b, a).unwrap().to_vec();
+        backward.reverse();
+        assert_eq!(backward, forward);
+    }
+
+    // Only vertices joined by an edge have pixels between them
+    assert_eq!(graph.edge_pixels(graph.start, graph.end), None);
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use maze_solving::graph::{Coord, Graph};
+use maze_solving::pathfinding::{SolveError, calculate_cost, dijkstra};
+
+/// A short route along the top and a long one around the loop below it,
+/// joining at the junctions at (1, 1) and (5, 1)
+const TWO_ROUTES: &[&str] = &[
+    "#.#####", //
+    "#......", //
+    "#.###.#", //
+    "#.###.#", //
+    "#.....#", //
+    "#######", //
+];
+
+fn vertex_at(graph: &Graph<Coord>, x: u32, y: u32) -> usize {
+    graph
+        .get_vertices()
+        .iter()
+        .position(|vertex| *vertex.get_pos() == Coord::new(x, y))
+        .expect("every open pixel has a vertex")
+}
+
+fn maze() -> Graph<Coord> {
+    common::graph(&common::maze_rows(6, 3, 0))
+}
+
+/// Two vertices with edges that aren't connected to each other
+fn unconnected_pair(graph: &Graph<Coord>) -> (usize, usize) {
+    let alive: Vec<usize> = (0..graph.get_vertices().len())
+        .filter(|&idx| !graph.get_vertices()[idx].get_neighbors().is_empty())
+        .collect();
+    alive
+        .iter()
+        .flat_map(|&a| alive.iter().map(move |&b| (a, b)))
+        .find(|&(a, b)| a != b && !graph.has_edge(a, b) && !graph.has_edge(b, a))
+        .unwrap()
+}
+
+#[test]
+fn adding_and_removing_an_edge_restores_the_graph() {
+    let mut graph = maze();
+    let before: Vec<_> = graph.edges().collect();
+    let (a, b) = unconnected_pair(&graph);
+
+    graph.add_edge(a, b, 2.5).unwrap();
+    assert!(graph.has_edge(a, b) && graph.has_edge(b, a));
+    assert_eq!(graph.edges().count(), before.len() + 1);
+
+    graph.remove_edge(b, a).unwrap();
+    assert_eq!(graph.edges().collect::<Vec<_>>(), before);
+    assert_eq!(
+        graph.remove_edge(a, b),
+        Err(SolveError::MissingEdge { from: a, to: b })
+    );
+}
+
+#[test]
+fn shortcut_edge_is_used() {
+    let mut graph = maze();
+    let direct = calculate_cost(&graph, &dijkstra(&graph).unwrap());
+    graph.add_edge(graph.start, graph.end, 1.0).unwrap();
+    assert_eq!(dijkstra(&graph).unwrap(), [graph.start, graph.end]);
+    assert!(direct > 1.0);
+}
+
+#[test]
+fn duplicate_edges_and_self_loops_are_rejected() {
+    let mut graph = maze();
+    let before: Vec<_> = graph.edges().collect();
+    let (a, b, _) = before[0];
+    assert_eq!(
+        graph.add_edge(a, b, 1.0),
+        Err(SolveError::DuplicateEdge { from: a, to: b })
+    );
+    assert_eq!(
+        graph.add_edge(b, a, 1.0),
+        Err(SolveError::DuplicateEdge { from: b, to: a })
+    );
+    assert_eq!(
+        graph.add_edge(a, a, 1.0),
+        Err(SolveError::SelfLoop { vertex: a })
+    );
+    assert_eq!(graph.edges().collect::<Vec<_>>(), before);
+}
+
+#[test]
+fn raising_a_weight_reroutes_dijkstra() {
+    let mut graph = common::graph(&common::ascii_rows(TWO_ROUTES));
+    let (left, right) = (vertex_at(&graph, 1, 1), vertex_at(&graph, 5, 1));
+    let uses_top = |path: &[usize]| path.windows(2).any(|pair| pair == [left, right]);
+
+    let path = dijkstra(&graph).unwrap();
+    assert!(uses_top(&path));
+    assert_eq!(calculate_cost(&graph, &path), 6.0);
+
+    graph.set_edge_weight(left, right, 100.0).unwrap();
+    assert!(graph.edges().any(|edge| edge == (left, right, 100.0)));
+    let path = dijkstra(&graph).unwrap();
+    assert!(!uses_top(&path));
+    assert_eq!(calculate_cost(&graph, &path), 12.0);
+}
+
+#[test]
+fn weight_of_a_missing_edge_is_an_error() {
+    let mut graph = maze();
+    let before: Vec<_> = graph.edges().collect();
+    let (a, b) = unconnected_pair(&graph);
+    assert_eq!(
+        graph.set_edge_weight(a, b, 3.0),
+        Err(SolveError::MissingEdge { from: a, to: b })
+    );
+    assert_eq!(graph.edges().collect::<Vec<_>>(), before);
+}
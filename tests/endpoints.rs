@@ -0,0 +1,92 @@
+mod common;
+
+use maze_solving::graph::{Coord, Graph};
+use maze_solving::pathfinding::{ALGORITHM_NAMES, SolveError, solve_graph};
+
+fn maze() -> Graph<Coord> {
+    common::graph(&common::maze_rows(6, 1, 6))
+}
+
+/// A vertex reduction merged into a corridor, so it has no edges left.
+fn dead_vertex(graph: &Graph<Coord>) -> usize {
+    graph
+        .get_vertices()
+        .iter()
+        .position(|vertex| vertex.get_neighbors().is_empty())
+        .expect("reduction leaves dead vertices behind")
+}
+
+#[test]
+fn out_of_range_endpoints_are_errors() {
+    let graph = maze();
+    let vertex_count = graph.get_vertices().len();
+    for &(name, algo) in ALGORITHM_NAMES {
+        let mut bad_start = graph.clone();
+        bad_start.start = vertex_count;
+        assert!(
+            matches!(
+                solve_graph(&bad_start, &algo),
+                Err(SolveError::EndpointOutOfRange {
+                    endpoint: "start",
+                    index,
+                    ..
+                }) if index == vertex_count
+            ),
+            "{name}"
+        );
+
+        let mut bad_end = graph.clone();
+        bad_end.end = usize::MAX;
+        assert!(
+            matches!(
+                solve_graph(&bad_end, &algo),
+                Err(SolveError::EndpointOutOfRange {
+                    endpoint: "end",
+                    ..
+                })
+            ),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn dead_endpoints_are_errors() {
+    let graph = maze();
+    let dead = dead_vertex(&graph);
+    for &(name, algo) in ALGORITHM_NAMES {
+        let mut dead_start = graph.clone();
+        dead_start.start = dead;
+        assert!(
+            matches!(
+                solve_graph(&dead_start, &algo),
+                Err(SolveError::DeadEndpoint {
+                    endpoint: "start",
+                    index,
+                }) if index == dead
+            ),
+            "{name}"
+        );
+
+        let mut dead_end = graph.clone();
+        dead_end.end = dead;
+        assert!(
+            matches!(
+                solve_graph(&dead_end, &algo),
+                Err(SolveError::DeadEndpoint {
+                    endpoint: "end",
+                    ..
+                })
+            ),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn valid_endpoints_solve() {
+    let graph = maze();
+    for &(name, algo) in ALGORITHM_NAMES {
+        assert!(solve_graph(&graph, &algo).unwrap().is_some(), "{name}");
+    }
+}
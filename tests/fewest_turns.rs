@@ -0,0 +1,53 @@
+mod common;
+
+use maze_solving::graph::GraphBuilder;
+use maze_solving::pathfinding::{calculate_cost, dijkstra, fewest_turns};
+
+/// A short staircase from (1, 1) to (9, 5) and a longer way round the
+/// outside with only three turns
+const STAIRS_OR_AROUND: &[&str] = &[
+    "#.#########", //
+    "#...#######", //
+    "#.#...#####", //
+    "#.###...###", //
+    "#.#####..##", //
+    "#.######...", //
+    "#.#######.#", //
+    "#.#######.#", //
+    "#.#######.#", //
+    "#.........#", //
+    "###########", //
+];
+
+/// Direction changes along a list of pixels.
+fn turns(coords: &[(u32, u32)]) -> usize {
+    let steps: Vec<(i64, i64)> = coords
+        .windows(2)
+        .map(|pair| {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            (i64::from(x1) - i64::from(x0), i64::from(y1) - i64::from(y0))
+        })
+        .collect();
+    steps.windows(2).filter(|pair| pair[0] != pair[1]).count()
+}
+
+#[test]
+fn fewest_turns_takes_the_longer_straighter_route() {
+    let rows = common::ascii_rows(STAIRS_OR_AROUND);
+    for builder in [
+        GraphBuilder::new(),
+        GraphBuilder::new().skip_reduction(true),
+    ] {
+        let graph = common::graph_with(&rows, &builder);
+        let shortest = dijkstra(&graph).unwrap();
+        assert_eq!(calculate_cost(&graph, &shortest), 14.0);
+
+        let result = fewest_turns(&graph).unwrap();
+        assert_eq!(result.cost, 22.0);
+        assert_eq!(result.cost, calculate_cost(&graph, &result.path));
+        let coords = graph.path_to_coords(&result.path);
+        assert!(coords.contains(&(1, 9)), "{coords:?}");
+        assert_eq!(turns(&coords), 3);
+        assert!(turns(&graph.path_to_coords(&shortest)) > 3);
+    }
+}
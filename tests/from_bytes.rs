@@ -0,0 +1,130 @@
+#![cfg(feature = "image")]
+
+mod common;
+
+use image::ImageFormat;
+use maze_solving::graph::{Graph, GraphError, MAX_PIXELS};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn png(rows: &[Vec<bool>]) -> Vec<u8> {
+    common::encode(rows, ImageFormat::Png, 1)
+}
+
+/// The CRC-32 PNG chunks end with.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// `bytes` with the width and height in its IHDR chunk replaced, and the
+/// chunk's CRC fixed up so the decoder gets as far as the dimensions.
+fn with_dimensions(mut bytes: Vec<u8>, width: u32, height: u32) -> Vec<u8> {
+    // 8 byte signature, then the IHDR chunk's length and type
+    let ihdr = 8 + 8;
+    bytes[ihdr..ihdr + 4].copy_from_slice(&width.to_be_bytes());
+    bytes[ihdr + 4..ihdr + 8].copy_from_slice(&height.to_be_bytes());
+    let crc = crc32(&bytes[ihdr - 4..ihdr + 13]);
+    bytes[ihdr + 13..ihdr + 17].copy_from_slice(&crc.to_be_bytes());
+    bytes
+}
+
+#[test]
+fn truncated_png_is_an_error() {
+    let bytes = png(&common::maze_rows(5, 1, 3));
+    // Cut in the signature, the header and the pixel data. The trailer after
+    // the pixel data isn't needed to decode
+    for len in [0, 1, 8, 16, 33, bytes.len() / 2] {
+        assert!(Graph::from_bytes(&bytes[..len]).is_err(), "{len} bytes");
+    }
+    assert!(Graph::from_bytes(&bytes).is_ok());
+}
+
+#[test]
+fn zero_dimensions_are_an_error() {
+    let bytes = png(&[vec![true, true]]);
+    // Rewriting the real dimensions keeps the image valid
+    assert!(Graph::from_bytes(&with_dimensions(bytes.clone(), 2, 1)).is_ok());
+    for (width, height) in [(0, 0), (0, 1), (1, 0)] {
+        let patched = with_dimensions(bytes.clone(), width, height);
+        assert!(Graph::from_bytes(&patched).is_err(), "{width}x{height}");
+    }
+}
+
+#[test]
+fn images_over_max_pixels_are_too_large() {
+    let bytes = png(&common::maze_rows(2, 1, 0));
+    let too_large = GraphError::TooLarge {
+        width: 1 << 16,
+        height: 1 << 16,
+    };
+    assert_eq!(1u64 << 32, MAX_PIXELS + 1);
+    // Rejected from the header, before the decoder allocates anything
+    let err = Graph::from_bytes(&with_dimensions(bytes.clone(), 1 << 16, 1 << 16))
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains(&too_large.to_string()), "{err}");
+
+    // Exactly `MAX_PIXELS` passes the check and only fails to decode
+    let err = Graph::from_bytes(&with_dimensions(bytes, 65535, 65537))
+        .err()
+        .unwrap();
+    assert!(!err.to_string().contains("at most"), "{err}");
+}
+
+#[test]
+fn no_walkable_pixels_is_an_error() {
+    let err = Graph::from_bytes(&png(&vec![vec![false; 4]; 4]))
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("no walkable pixels"), "{err}");
+}
+
+#[test]
+fn single_pixel_images_are_an_error() {
+    for open in [false, true] {
+        assert!(Graph::from_bytes(&png(&[vec![open]])).is_err());
+    }
+    let mut rows = vec![vec![false; 5]; 5];
+    rows[2][2] = true;
+    let err = Graph::from_bytes(&png(&rows)).err().unwrap();
+    assert!(err.to_string().contains("single walkable pixel"), "{err}");
+}
+
+#[test]
+fn one_pixel_wide_and_tall_images_load() {
+    // A single row or column is nothing but border on both sides
+    for rows in [vec![vec![true; 6]], vec![vec![true]; 6]] {
+        let graph = Graph::from_bytes(&png(&rows)).unwrap();
+        assert_ne!(graph.start, graph.end);
+    }
+}
+
+#[test]
+fn random_bytes_never_panic() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let valid = png(&common::maze_rows(4, 2, 2));
+    for _ in 0..500 {
+        // Flip a few bytes of a valid image, which gets past the signature
+        // far more often than pure noise
+        let mut bytes = valid.clone();
+        for _ in 0..rng.random_range(1..8) {
+            let at = rng.random_range(0..bytes.len());
+            bytes[at] = rng.random();
+        }
+        let _ = Graph::from_bytes(&bytes);
+
+        let noise: Vec<u8> = (0..rng.random_range(0..64)).map(|_| rng.random()).collect();
+        let _ = Graph::from_bytes(&noise);
+    }
+}
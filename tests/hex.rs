@@ -0,0 +1,60 @@
+#![cfg(feature = "image")]
+
+mod common;
+
+use image::{ImageFormat, Rgb, RgbImage};
+use maze_solving::graph::{Graph, HexCoord};
+
+const RADIUS: f32 = 10.0;
+
+/// A 4x4 hex grid of radius 10 with every cell open, before any walls are
+/// drawn in.
+fn open_hexes() -> RgbImage {
+    RgbImage::from_pixel(70, 60, Rgb([255, 255, 255]))
+}
+
+fn load(img: &RgbImage, name: &str) -> Graph<HexCoord> {
+    let mut bytes = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)
+        .unwrap();
+    Graph::from_hex_png(common::temp_file(name, &bytes), RADIUS).unwrap()
+}
+
+fn neighbor_count(graph: &Graph<HexCoord>, col: u32, row: u32) -> usize {
+    graph
+        .get_vertices()
+        .iter()
+        .find(|vertex| *vertex.get_pos() == HexCoord::new(col, row))
+        .expect("the cell is open")
+        .get_neighbors()
+        .len()
+}
+
+#[test]
+fn open_hex_cells_have_up_to_six_neighbors() {
+    let graph = load(&open_hexes(), "open_hexes.png");
+    assert_eq!(graph.get_vertices().len(), 16);
+    for (col, row) in [(1, 1), (2, 1), (1, 2), (2, 2)] {
+        assert_eq!(neighbor_count(&graph, col, row), 6, "({col}, {row})");
+    }
+    // Odd rows are shifted right, so the top left and bottom right corners
+    // touch two cells and the other two corners three
+    for (col, row, count) in [(0, 0, 2), (3, 3, 2), (3, 0, 3), (0, 3, 3), (0, 1, 5)] {
+        assert_eq!(neighbor_count(&graph, col, row), count, "({col}, {row})");
+    }
+}
+
+#[test]
+fn walls_between_hex_cells_cut_the_link() {
+    let mut img = open_hexes();
+    // The middle of the side between (1, 1) and (2, 1). Their row is shifted
+    // a whole spacing, so their centers are 2 and 3 spacings in
+    let spacing = 3f32.sqrt() * RADIUS;
+    let (x, y) = ((2.5 * spacing).round() as u32, (2.5 * RADIUS) as u32);
+    img.put_pixel(x, y, Rgb([0, 0, 0]));
+
+    let graph = load(&img, "walled_hexes.png");
+    assert_eq!(neighbor_count(&graph, 1, 1), 5);
+    assert_eq!(neighbor_count(&graph, 2, 1), 5);
+    assert_eq!(neighbor_count(&graph, 1, 2), 6);
+}
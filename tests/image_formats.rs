@@ -0,0 +1,148 @@
+#![cfg(feature = "image")]
+
+mod common;
+
+use image::{ImageFormat, Rgba, RgbaImage};
+use maze_solving::graph::{Graph, LoadOptions, WallDetect};
+use maze_solving::pathfinding::{PathfindingAlgorithm, calculate_cost, solve_graph};
+
+fn cost(graph: &Graph<maze_solving::graph::Coord>) -> f32 {
+    let path = solve_graph(graph, &PathfindingAlgorithm::Dijkstra)
+        .unwrap()
+        .expect("the maze is solvable");
+    calculate_cost(graph, &path)
+}
+
+#[test]
+fn bmp_loads_like_png_with_the_default_walls() {
+    let rows = common::maze_rows(8, 4, 10);
+    let png = common::temp_file("formats.png", &common::encode(&rows, ImageFormat::Png, 2));
+    let bmp = common::temp_file("formats.bmp", &common::encode(&rows, ImageFormat::Bmp, 2));
+
+    let (from_png, format) = Graph::from_image_with_format(&png, &LoadOptions::default()).unwrap();
+    assert_eq!(format, ImageFormat::Png);
+    let (from_bmp, format) = Graph::from_image_with_format(&bmp, &LoadOptions::default()).unwrap();
+    assert_eq!(format, ImageFormat::Bmp);
+    assert_eq!(cost(&from_bmp), cost(&from_png));
+}
+
+#[test]
+fn jpeg_loads_with_a_red_threshold() {
+    let rows = common::maze_rows(8, 4, 10);
+    let png = common::temp_file("threshold.png", &common::encode(&rows, ImageFormat::Png, 4));
+    let jpeg = common::temp_file(
+        "threshold.jpg",
+        &common::encode(&rows, ImageFormat::Jpeg, 4),
+    );
+
+    let options = LoadOptions {
+        walls: WallDetect::RedChannel(128),
+        ..LoadOptions::default()
+    };
+    let (from_png, _) = Graph::from_image_with_format(&png, &LoadOptions::default()).unwrap();
+    let (from_jpeg, format) = Graph::from_image_with_format(&jpeg, &options).unwrap();
+    assert_eq!(format, ImageFormat::Jpeg);
+    assert_eq!(cost(&from_jpeg), cost(&from_png));
+}
+
+#[test]
+fn transparent_passages_are_open() {
+    let rows = common::maze_rows(8, 5, 10);
+    // Passages are transparent black, so only their alpha tells them apart
+    let img = RgbaImage::from_fn(rows[0].len() as u32, rows.len() as u32, |x, y| {
+        let open = rows[y as usize][x as usize];
+        Rgba(if open { [0, 0, 0, 0] } else { [0, 0, 0, 255] })
+    });
+    let mut bytes = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)
+        .unwrap();
+
+    let from_alpha = Graph::from_bytes(&bytes).unwrap();
+    let expected = common::graph(&rows);
+    assert_eq!(
+        from_alpha.edges().collect::<Vec<_>>(),
+        expected.edges().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        (from_alpha.start, from_alpha.end),
+        (expected.start, expected.end)
+    );
+    let path = common::temp_file("transparent.png", &bytes);
+    for walls in [
+        WallDetect::Luminance(128),
+        WallDetect::RelativeLuminance(128),
+    ] {
+        let options = LoadOptions {
+            walls,
+            ..LoadOptions::default()
+        };
+        let (graph, _) = Graph::from_image_with_format(&path, &options).unwrap();
+        assert_eq!(cost(&graph), cost(&expected));
+    }
+}
+
+#[test]
+fn blue_walls_on_a_dark_background() {
+    let rows = common::maze_rows(8, 6, 10);
+    // Walls a little off pure blue, passages dark green, so none of the
+    // brightness based rules would get either right
+    let img = image::RgbImage::from_fn(rows[0].len() as u32, rows.len() as u32, |x, y| {
+        let open = rows[y as usize][x as usize];
+        image::Rgb(if open {
+            [0, 100, 0]
+        } else {
+            [(x % 3) as u8 * 5, 0, 250]
+        })
+    });
+    let mut bytes = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)
+        .unwrap();
+    let path = common::temp_file("blue_walls.png", &bytes);
+
+    let graph =
+        Graph::from_png_with_walls(&path, WallDetect::Color(image::Rgb([0, 0, 255]), 16)).unwrap();
+    let expected = common::graph(&rows);
+    assert_eq!(
+        graph.edges().collect::<Vec<_>>(),
+        expected.edges().collect::<Vec<_>>()
+    );
+    assert_eq!((graph.start, graph.end), (expected.start, expected.end));
+
+    // A tolerance too tight for the off-blue walls opens them up
+    let tight =
+        Graph::from_png_with_walls(&path, WallDetect::Color(image::Rgb([0, 0, 255]), 4)).unwrap();
+    assert_ne!(
+        tight.edges().collect::<Vec<_>>(),
+        expected.edges().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn green_passages_and_dark_red_walls_by_relative_luminance() {
+    let rows = common::maze_rows(8, 7, 10);
+    let (green, dark_red) = ([0, 200, 0], [150, 0, 0]);
+    // Averaged, the green passages (67) would come out darker than the
+    // threshold just like the walls (50)
+    let average = |[r, g, b]: [u8; 3]| (u32::from(r) + u32::from(g) + u32::from(b)) / 3;
+    assert!(average(green) < 100 && average(dark_red) < 100);
+
+    let img = image::RgbImage::from_fn(rows[0].len() as u32, rows.len() as u32, |x, y| {
+        image::Rgb(if rows[y as usize][x as usize] {
+            green
+        } else {
+            dark_red
+        })
+    });
+    let mut bytes = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)
+        .unwrap();
+    let path = common::temp_file("green_on_red.png", &bytes);
+
+    let graph = Graph::from_png_with_walls(&path, WallDetect::RelativeLuminance(100)).unwrap();
+    let expected = common::graph(&rows);
+    assert_eq!(
+        graph.edges().collect::<Vec<_>>(),
+        expected.edges().collect::<Vec<_>>()
+    );
+    assert_eq!(cost(&graph), cost(&expected));
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use maze_solving::graph::GraphBuilder;
+use maze_solving::pathfinding::{PathfindingAlgorithm, calculate_cost, solve_graph};
+
+#[test]
+fn iddfs_path_is_as_short_as_bfs() {
+    // Without reduction every edge costs 1, so BFS finds the fewest steps
+    let builder = GraphBuilder::new().skip_reduction(true);
+    for seed in 0..4 {
+        let graph = common::graph_with(&common::maze_rows(5, seed, 8), &builder);
+        let bfs = solve_graph(&graph, &PathfindingAlgorithm::BreadthFirst)
+            .unwrap()
+            .unwrap();
+        let iddfs = solve_graph(&graph, &PathfindingAlgorithm::IterativeDeepening)
+            .unwrap()
+            .unwrap();
+        assert_eq!(iddfs.len(), bfs.len());
+        assert_eq!(iddfs.first(), Some(&graph.start));
+        assert_eq!(iddfs.last(), Some(&graph.end));
+    }
+}
+
+#[test]
+fn iddfs_handles_heavily_looped_mazes() {
+    // Every loop doubles the paths a search that only avoids its own path
+    // walks, this many would never finish
+    let builder = GraphBuilder::new().skip_reduction(true);
+    let graph = common::graph_with(&common::maze_rows(20, 1, 300), &builder);
+    let bfs = solve_graph(&graph, &PathfindingAlgorithm::BreadthFirst)
+        .unwrap()
+        .unwrap();
+    let iddfs = solve_graph(&graph, &PathfindingAlgorithm::IterativeDeepening)
+        .unwrap()
+        .unwrap();
+    assert_eq!(iddfs.len(), bfs.len());
+}
+
+#[test]
+fn ida_star_cost_matches_dijkstra() {
+    for seed in 0..4 {
+        let graph = common::graph(&common::maze_rows(6, seed, 10));
+        let cost = |algo| {
+            let path = solve_graph(&graph, &algo).unwrap().unwrap();
+            calculate_cost(&graph, &path)
+        };
+        assert_eq!(
+            cost(PathfindingAlgorithm::IdaStar),
+            cost(PathfindingAlgorithm::Dijkstra)
+        );
+    }
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use maze_solving::pathfinding::{PathfindingAlgorithm, calculate_cost, solve_graph};
+
+/// A short route along the top and a long one around the loop below it
+const TWO_ROUTES: &[&str] = &[
+    "#.#####", //
+    "#......", //
+    "#.###.#", //
+    "#.###.#", //
+    "#.....#", //
+    "#######", //
+];
+
+#[test]
+fn returns_both_routes_cheaper_first() {
+    let graph = common::graph(&common::ascii_rows(TWO_ROUTES));
+    let dijkstra = solve_graph(&graph, &PathfindingAlgorithm::Dijkstra)
+        .unwrap()
+        .unwrap();
+
+    let paths = graph
+        .k_shortest_paths(3, &PathfindingAlgorithm::Dijkstra)
+        .unwrap();
+    assert_eq!(paths.len(), 2);
+    assert_eq!(paths[0].path, dijkstra);
+    assert_eq!([paths[0].cost, paths[1].cost], [6.0, 12.0]);
+    for result in &paths {
+        assert_eq!(result.cost, calculate_cost(&graph, &result.path));
+        assert_eq!(result.path.first(), Some(&graph.start));
+        assert_eq!(result.path.last(), Some(&graph.end));
+    }
+}
+
+#[test]
+fn costs_never_decrease() {
+    let graph = common::graph(&common::maze_rows(8, 6, 12));
+    for algo in [PathfindingAlgorithm::Dijkstra, PathfindingAlgorithm::AStar] {
+        let paths = graph.k_shortest_paths(6, &algo).unwrap();
+        assert_eq!(paths.len(), 6);
+        assert!(paths.windows(2).all(|pair| pair[0].cost <= pair[1].cost));
+        for (i, result) in paths.iter().enumerate() {
+            assert!(paths[..i].iter().all(|other| other.path != result.path));
+        }
+    }
+}
+
+#[test]
+fn zero_paths_asked_for_is_empty() {
+    let graph = common::graph(&common::ascii_rows(TWO_ROUTES));
+    let paths = graph
+        .k_shortest_paths(0, &PathfindingAlgorithm::Dijkstra)
+        .unwrap();
+    assert!(paths.is_empty());
+}
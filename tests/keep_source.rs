@@ -0,0 +1,39 @@
+#![cfg(feature = "image")]
+
+mod common;
+
+use image::ImageFormat;
+use maze_solving::graph::{DrawOptions, GraphBuilder};
+use maze_solving::pathfinding::dijkstra;
+
+#[test]
+fn drawing_leaves_the_source_file_alone() {
+    let rows = common::maze_rows(6, 4, 6);
+    let png = common::encode(&rows, ImageFormat::Png, 1);
+    let source = common::temp_file("kept_source.png", &png);
+    let output = common::temp_file("kept_source_solved.png", &[]);
+    let graph = GraphBuilder::new()
+        .keep_source(true)
+        .build_from_png(&source)
+        .unwrap();
+    let path = dijkstra(&graph).unwrap();
+
+    graph
+        .draw_path_on_source(&path, &output, &DrawOptions::default())
+        .unwrap();
+    assert_eq!(std::fs::read(&source).unwrap(), png);
+    let drawn = image::open(&output).unwrap().into_rgb8();
+    let original = image::open(&source).unwrap().into_rgb8();
+    assert_ne!(drawn, original);
+
+    // The kept image is drawn on, not whatever the file holds by now
+    std::fs::write(
+        &source,
+        common::encode(&vec![vec![false; 13]; 13], ImageFormat::Png, 1),
+    )
+    .unwrap();
+    graph
+        .draw_path_on_source(&path, &output, &DrawOptions::default())
+        .unwrap();
+    assert_eq!(image::open(&output).unwrap().into_rgb8(), drawn);
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use maze_solving::pathfinding::{
+    HeuristicTable, alt_astar, astar_cached, astar_weighted, calculate_cost, dijkstra,
+    shortest_paths_from,
+};
+
+#[test]
+fn alt_astar_cost_matches_dijkstra() {
+    for seed in 0..4 {
+        let graph = common::graph(&common::maze_rows(12, seed, 30));
+        let landmarks = graph.precompute_landmarks(4);
+        assert_eq!(landmarks.get_landmarks().len(), 4);
+
+        let expected = calculate_cost(&graph, &dijkstra(&graph).unwrap());
+        let result = alt_astar(&graph, &landmarks, graph.start, graph.end).unwrap();
+        assert_eq!(result.cost, expected, "seed {seed}");
+        assert_eq!(result.cost, calculate_cost(&graph, &result.path));
+
+        // The same tables serve queries to any other target
+        let (dists, _) = shortest_paths_from(&graph, graph.start);
+        for target in (0..dists.len()).filter(|idx| dists[*idx].is_finite()) {
+            let result = alt_astar(&graph, &landmarks, graph.start, target).unwrap();
+            assert_eq!(result.cost, dists[target], "seed {seed} target {target}");
+        }
+    }
+}
+
+#[test]
+fn cached_heuristics_agree_with_uncached_astar() {
+    for seed in 0..4 {
+        let graph = common::graph(&common::maze_rows(12, seed, 30));
+        let uncached = astar_weighted(&graph, 1.0).unwrap();
+        let table = HeuristicTable::new(&graph, graph.end);
+        assert_eq!(astar_cached(&graph, &table, graph.start), Some(uncached));
+
+        let landmarks = graph.precompute_landmarks(4);
+        let alt_table = HeuristicTable::from_landmarks(&graph, &landmarks, graph.end);
+        // One table serves queries from anywhere to its goal
+        let (dists, _) = shortest_paths_from(&graph, graph.end);
+        for source in (0..dists.len()).filter(|idx| dists[*idx].is_finite()) {
+            let alt = alt_astar(&graph, &landmarks, source, graph.end);
+            assert_eq!(astar_cached(&graph, &alt_table, source), alt);
+            let cached = astar_cached(&graph, &table, source).unwrap();
+            assert_eq!(cached.cost, dists[source], "seed {seed} source {source}");
+        }
+    }
+}
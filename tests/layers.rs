@@ -0,0 +1,72 @@
+use maze_solving::graph::{Coord3D, Graph};
+use maze_solving::pathfinding::{PathfindingAlgorithm, calculate_cost, solve_graph};
+
+fn layer(picture: &[&str]) -> Vec<Vec<bool>> {
+    picture
+        .iter()
+        .map(|row| row.chars().map(|c| c != '#').collect())
+        .collect()
+}
+
+#[test]
+fn two_layer_maze_needs_a_level_change() {
+    // The lower layer is cut in two by a wall, the only way across is up
+    // through (2, 1), along the upper layer and back down through (2, 3)
+    let layers = [
+        layer(&[
+            ".....", //
+            ".....", //
+            "#####", //
+            ".....", //
+            ".....", //
+        ]),
+        layer(&[
+            "#####", //
+            "##.##", //
+            "##.##", //
+            "##.##", //
+            "#####", //
+        ]),
+    ];
+    let mut graph = Graph::from_layers(&layers).unwrap();
+    let find = |graph: &Graph<Coord3D>, name: &str| {
+        graph
+            .get_vertices()
+            .iter()
+            .position(|vertex| vertex.get_pos().to_string() == name)
+            .unwrap()
+    };
+    graph.start = find(&graph, "(0, 0, 0)");
+    graph.end = find(&graph, "(3, 4, 0)");
+
+    for algo in [
+        PathfindingAlgorithm::BreadthFirst,
+        PathfindingAlgorithm::DepthFirst,
+        PathfindingAlgorithm::Dijkstra,
+    ] {
+        let path = solve_graph(&graph, &algo).unwrap().unwrap();
+        let layers: Vec<bool> = path
+            .iter()
+            .map(|&idx| {
+                graph.get_vertices()[idx]
+                    .get_pos()
+                    .to_string()
+                    .ends_with(", 1)")
+            })
+            .collect();
+        assert!(layers.contains(&true), "{algo:?}");
+        if algo.finds_cheapest() {
+            // 3 to the stairs, 1 up, 2 along the upper layer, 1 down and 2
+            // more to the end
+            assert_eq!(calculate_cost(&graph, &path), 9.0, "{algo:?}");
+        }
+    }
+
+    // Without the upper layer there is no way across from the first open
+    // cell to the last
+    let flat = Graph::from_layers(&layers[..1]).unwrap();
+    assert_eq!(
+        solve_graph(&flat, &PathfindingAlgorithm::BreadthFirst).unwrap(),
+        None
+    );
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use maze_solving::graph::GraphBuilder;
+use maze_solving::pathfinding::{PathfindingAlgorithm, solve_graph};
+use std::sync::Mutex;
+
+/// Keeps every record logged, for looking through afterwards.
+struct Capture(Mutex<Vec<(Level, String)>>);
+
+impl Log for Capture {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        let message = record.args().to_string();
+        self.0.lock().unwrap().push((record.level(), message));
+    }
+
+    fn flush(&self) {}
+}
+
+static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+
+/// A loop of corridors that never reaches the border
+const INTERIOR_ONLY: &[&str] = &[
+    "#######", //
+    "#.....#", //
+    "#.###.#", //
+    "#.#.#.#", //
+    "#.....#", //
+    "#######", //
+];
+
+#[test]
+fn building_and_solving_log_their_phases() {
+    log::set_logger(&CAPTURE).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    let rows = common::ascii_rows(INTERIOR_ONLY);
+    let graph = common::graph_with(&rows, &GraphBuilder::new().seed(1));
+    solve_graph(&graph, &PathfindingAlgorithm::Dijkstra).unwrap();
+
+    let records = CAPTURE.0.lock().unwrap();
+    let logged = |level: Level, text: &str| {
+        records
+            .iter()
+            .any(|(at, message)| *at == level && message.contains(text))
+    };
+    assert!(
+        logged(Level::Debug, "walkable pixels in the 7x6 grid"),
+        "{records:?}"
+    );
+    assert!(logged(Level::Debug, "Reduction kept"), "{records:?}");
+    assert!(logged(Level::Warn, "using two at random"), "{records:?}");
+    assert!(logged(Level::Info, "Built a graph of"), "{records:?}");
+    assert!(logged(Level::Debug, "Dijkstra visited"), "{records:?}");
+}
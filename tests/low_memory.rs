@@ -0,0 +1,32 @@
+mod common;
+
+use maze_solving::graph::{Coord, CornerPolicy, Graph, GraphBuilder};
+
+fn assert_same_graph(a: &Graph<Coord>, b: &Graph<Coord>) {
+    assert_eq!(a.get_vertices().len(), b.get_vertices().len());
+    for (idx, (va, vb)) in a.get_vertices().iter().zip(b.get_vertices()).enumerate() {
+        assert_eq!(va.get_pos(), vb.get_pos(), "{idx}");
+        assert_eq!(va.get_neighbors(), vb.get_neighbors(), "{idx}");
+    }
+    assert_eq!((a.start, a.end), (b.start, b.end));
+}
+
+#[test]
+fn low_memory_builds_the_same_graph() {
+    for builder in [
+        GraphBuilder::new(),
+        GraphBuilder::new().skip_reduction(true),
+        GraphBuilder::new().diagonals(CornerPolicy::NoSqueezing),
+    ] {
+        for seed in 0..4 {
+            for rows in [
+                common::maze_rows(10, seed, 12),
+                common::room_rows(20, seed, 0.3),
+            ] {
+                let default = common::graph_with(&rows, &builder);
+                let streamed = common::graph_with(&rows, &builder.clone().low_memory(true));
+                assert_same_graph(&default, &streamed);
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "image")]
+
+mod common;
+
+use image::{ImageFormat, Rgba, RgbaImage};
+use maze_solving::graph::{Coord, Graph};
+
+#[test]
+fn orange_border_cells_become_the_endpoints() {
+    let mut rows = common::maze_rows(6, 3, 4);
+    let side = rows.len() as u32;
+    // A third, plain opening next to the first cell, so the border alone
+    // doesn't settle which two are meant
+    rows[1][0] = true;
+    let orange = [Coord::new(1, 0), Coord::new(side - 2, side - 1)];
+
+    let img = RgbaImage::from_fn(side, side, |x, y| {
+        if orange.contains(&Coord::new(x, y)) {
+            Rgba([255, 140, 0, 255])
+        } else if rows[y as usize][x as usize] {
+            Rgba([255, 255, 255, 255])
+        } else {
+            Rgba([0, 0, 0, 255])
+        }
+    });
+    let mut bytes = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)
+        .unwrap();
+
+    for _ in 0..3 {
+        let graph = Graph::from_bytes(&bytes).unwrap();
+        assert_eq!(graph.boundary_openings().len(), 3);
+        let pos = |idx: usize| *graph.get_vertices()[idx].get_pos();
+        let mut endpoints = [pos(graph.start), pos(graph.end)];
+        endpoints.sort_by_key(|pos| (pos.y(), pos.x()));
+        assert_eq!(endpoints, orange);
+    }
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use maze_solving::pathfinding::{
+    PathfindingAlgorithm, SolveError, Solver, calculate_cost, solve_graph, solve_graph_within,
+};
+
+#[test]
+fn paths_over_the_limit_are_too_long() {
+    let graph = common::graph(&common::maze_rows(15, 3, 20));
+    let path = solve_graph(&graph, &PathfindingAlgorithm::Dijkstra)
+        .unwrap()
+        .unwrap();
+    let cost = calculate_cost(&graph, &path);
+
+    for algo in [
+        PathfindingAlgorithm::Dijkstra,
+        PathfindingAlgorithm::AStar,
+        PathfindingAlgorithm::BreadthFirst,
+        PathfindingAlgorithm::BellmanFord,
+    ] {
+        assert!(
+            matches!(
+                solve_graph_within(&graph, &algo, cost - 1.0),
+                Err(SolveError::PathTooLong { .. })
+            ),
+            "{algo:?}"
+        );
+    }
+    for algo in [PathfindingAlgorithm::Dijkstra, PathfindingAlgorithm::AStar] {
+        let within = solve_graph_within(&graph, &algo, cost).unwrap().unwrap();
+        assert_eq!(calculate_cost(&graph, &within), cost, "{algo:?}");
+    }
+}
+
+#[test]
+fn astar_stops_at_the_limit() {
+    let graph = common::graph(&common::maze_rows(15, 3, 20));
+    let visited = |max_cost: Option<f32>| {
+        let mut solver = Solver::new(PathfindingAlgorithm::AStar);
+        solver.set_max_cost(max_cost);
+        let _ = solver.solve(&graph);
+        solver.visited().iter().filter(|&&visited| visited).count()
+    };
+    assert!(visited(Some(10.0)) < visited(None));
+}
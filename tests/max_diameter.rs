@@ -0,0 +1,47 @@
+mod common;
+
+use maze_solving::graph::{Coord, EndpointStrategy, Graph, GraphBuilder};
+
+/// Three openings: (1, 0) is close to both others, while (6, 1) and (1, 6)
+/// are a long way apart
+const THREE_OPENINGS: &[&str] = &[
+    "#.#####", //
+    "#......", //
+    "#.#####", //
+    "#.#####", //
+    "#.#####", //
+    "#.#####", //
+    "#.#####", //
+];
+
+fn endpoints(graph: &Graph<Coord>) -> [Coord; 2] {
+    let vertices = graph.get_vertices();
+    let mut ends = [
+        *vertices[graph.start].get_pos(),
+        *vertices[graph.end].get_pos(),
+    ];
+    ends.sort_by_key(|pos| (pos.x(), pos.y()));
+    ends
+}
+
+#[test]
+fn max_diameter_picks_the_farthest_openings() {
+    let rows = common::ascii_rows(THREE_OPENINGS);
+    let builder = GraphBuilder::new().endpoints(EndpointStrategy::MaxDiameter);
+    for seed in 0..4 {
+        let graph = common::graph_with(&rows, &builder.clone().seed(seed));
+        assert_eq!(graph.boundary_openings().len(), 3);
+        assert_eq!(endpoints(&graph), [Coord::new(1, 6), Coord::new(6, 1)]);
+    }
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn png_loads_pick_the_farthest_openings() {
+    let rows = common::ascii_rows(THREE_OPENINGS);
+    let png = common::encode(&rows, image::ImageFormat::Png, 1);
+    let path = common::temp_file("three_openings.png", &png);
+    let graph =
+        Graph::from_png_with_endpoint_strategy(&path, EndpointStrategy::MaxDiameter).unwrap();
+    assert_eq!(endpoints(&graph), [Coord::new(1, 6), Coord::new(6, 1)]);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use maze_solving::graph::{CornerPolicy, GraphBuilder, Move, TurtleCommand, turtle_commands};
+use maze_solving::pathfinding::dijkstra;
+
+/// A corridor down from the top that turns right to the exit
+const L_CORRIDOR: &[&str] = &[
+    "#.###", //
+    "#.###", //
+    "#....", //
+    "#####", //
+];
+
+#[test]
+fn l_corridor_moves_go_down_then_right() {
+    let rows = common::ascii_rows(L_CORRIDOR);
+    for builder in [
+        GraphBuilder::new(),
+        GraphBuilder::new().skip_reduction(true),
+    ] {
+        let graph = common::graph_with(&rows, &builder);
+        let moves = graph.path_to_moves(&dijkstra(&graph).unwrap());
+        assert_eq!(
+            moves,
+            [
+                Move::Down,
+                Move::Down,
+                Move::Right,
+                Move::Right,
+                Move::Right
+            ]
+        );
+        let text: String = moves.iter().map(|step| step.to_string()).collect();
+        assert_eq!(text, "DDRRR");
+    }
+}
+
+#[test]
+fn diagonal_moves_cut_the_corner() {
+    let builder = GraphBuilder::new().diagonals(CornerPolicy::Always);
+    let graph = common::graph_with(&common::ascii_rows(L_CORRIDOR), &builder);
+    let moves = graph.path_to_moves(&dijkstra(&graph).unwrap());
+    assert_eq!(
+        moves,
+        [Move::Down, Move::DownRight, Move::Right, Move::Right]
+    );
+}
+
+#[test]
+fn l_corridor_turtle_goes_forward_turns_and_goes_forward() {
+    let graph = common::graph(&common::ascii_rows(L_CORRIDOR));
+    let path = dijkstra(&graph).unwrap();
+    // Heading down the image, the corner is a left turn
+    assert_eq!(
+        graph.path_to_turtle(&path, Move::Down),
+        [
+            TurtleCommand::Forward(2),
+            TurtleCommand::Left(90),
+            TurtleCommand::Forward(3)
+        ]
+    );
+    // Facing another way at first takes a turn before setting off
+    let commands = graph.path_to_turtle(&path, Move::Right);
+    let text: Vec<String> = commands.iter().map(|command| command.to_string()).collect();
+    assert_eq!(text, ["right 90", "forward 2", "left 90", "forward 3"]);
+}
+
+#[test]
+fn turning_back_is_a_half_turn() {
+    let moves = [Move::Up, Move::Up, Move::Down];
+    assert_eq!(
+        turtle_commands(&moves, Move::Up),
+        [
+            TurtleCommand::Forward(2),
+            TurtleCommand::Right(180),
+            TurtleCommand::Forward(1)
+        ]
+    );
+}
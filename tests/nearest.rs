@@ -0,0 +1,44 @@
+mod common;
+
+use maze_solving::graph::{Coord, Graph};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// The vertex with edges closest to `coord`, looking at every one of them.
+fn brute_force_nearest(graph: &Graph<Coord>, coord: &Coord) -> Option<usize> {
+    let squared = |pos: &Coord| {
+        let (dx, dy) = (pos.x().abs_diff(coord.x()), pos.y().abs_diff(coord.y()));
+        u64::from(dx) * u64::from(dx) + u64::from(dy) * u64::from(dy)
+    };
+    graph
+        .get_vertices()
+        .iter()
+        .enumerate()
+        .filter(|(_, vertex)| !vertex.get_neighbors().is_empty())
+        .min_by_key(|(idx, vertex)| (squared(vertex.get_pos()), *idx))
+        .map(|(idx, _)| idx)
+}
+
+#[test]
+fn nearest_matches_brute_force() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for seed in 0..4 {
+        for rows in [
+            common::maze_rows(20, seed, 40),
+            common::room_rows(50, seed, 0.4),
+        ] {
+            let graph = common::graph(&rows);
+            let side = rows.len() as u32;
+            // Queries inside the maze and well outside of it
+            for _ in 0..200 {
+                let coord =
+                    Coord::new(rng.random_range(0..side * 2), rng.random_range(0..side * 2));
+                assert_eq!(
+                    graph.nearest(&coord),
+                    brute_force_nearest(&graph, &coord),
+                    "seed {seed} {coord}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use maze_solving::graph::{Coord, Graph, GraphBuilder, GraphError, Grid, LoadOptions};
+use maze_solving::pathfinding::{PathfindingAlgorithm, solve_graph};
+
+/// A loop of corridors that never reaches the border
+const INTERIOR_ONLY: &[&str] = &[
+    "#######", //
+    "#.....#", //
+    "#.###.#", //
+    "#.#.#.#", //
+    "#.....#", //
+    "#######", //
+];
+
+fn from_rows(rows: &[Vec<bool>]) -> Result<Graph<Coord>, GraphError> {
+    Graph::from_grid(Grid::from_rows(rows).unwrap(), &LoadOptions::default())
+}
+
+#[test]
+fn all_walls_is_no_walkable_pixels() {
+    let rows = vec![vec![false; 8]; 8];
+    assert_eq!(from_rows(&rows).err(), Some(GraphError::NoWalkablePixels));
+}
+
+#[test]
+fn one_open_pixel_is_a_single_walkable_pixel() {
+    let mut rows = vec![vec![false; 8]; 8];
+    rows[3][4] = true;
+    assert_eq!(
+        from_rows(&rows).err(),
+        Some(GraphError::SingleWalkablePixel)
+    );
+}
+
+#[test]
+fn no_border_openings_fall_back_to_seeded_random_endpoints() {
+    let rows = common::ascii_rows(INTERIOR_ONLY);
+    let load = |seed| common::graph_with(&rows, &GraphBuilder::new().seed(seed));
+
+    let graph = load(7);
+    assert!(graph.boundary_openings().is_empty());
+    assert_ne!(graph.start, graph.end);
+    let vertices = graph.get_vertices();
+    assert!(!vertices[graph.start].get_neighbors().is_empty());
+    assert!(!vertices[graph.end].get_neighbors().is_empty());
+    assert!(
+        solve_graph(&graph, &PathfindingAlgorithm::Dijkstra)
+            .unwrap()
+            .is_some()
+    );
+
+    // The same seed picks the same endpoints
+    let again = load(7);
+    assert_eq!((again.start, again.end), (graph.start, graph.end));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn all_black_image_names_the_problem() {
+    let rows = vec![vec![false; 8]; 8];
+    let png = common::encode(&rows, image::ImageFormat::Png, 1);
+    let err = Graph::from_bytes(&png).err().unwrap();
+    assert!(err.to_string().contains("no walkable pixels"), "{err}");
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn seeded_png_picks_the_same_distinct_endpoints() {
+    let png = common::encode(
+        &common::ascii_rows(INTERIOR_ONLY),
+        image::ImageFormat::Png,
+        1,
+    );
+    let path = common::temp_file("interior_only.png", &png);
+    for seed in 0..10 {
+        let graph = Graph::from_png_seeded(&path, seed).unwrap();
+        assert_ne!(graph.start, graph.end, "seed {seed}");
+        let again = Graph::from_png_seeded(&path, seed).unwrap();
+        assert_eq!((again.start, again.end), (graph.start, graph.end));
+    }
+}
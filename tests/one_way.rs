@@ -0,0 +1,66 @@
+mod common;
+
+use maze_solving::graph::{Coord, Graph};
+use maze_solving::pathfinding::{ALGORITHM_NAMES, PathfindingAlgorithm, solve_verified};
+
+/// A short route along the top and a long one around the loop below it,
+/// joining at the junctions at (1, 1) and (5, 1)
+const TWO_ROUTES: &[&str] = &[
+    "#.#####", //
+    "#......", //
+    "#.###.#", //
+    "#.###.#", //
+    "#.....#", //
+    "#######", //
+];
+
+fn vertex_at(graph: &Graph<Coord>, x: u32, y: u32) -> usize {
+    graph
+        .get_vertices()
+        .iter()
+        .position(|vertex| *vertex.get_pos() == Coord::new(x, y))
+        .expect("every open pixel has a vertex")
+}
+
+#[test]
+fn only_route_runs_through_a_one_way_passage() {
+    let mut graph = common::graph(&common::ascii_rows(TWO_ROUTES));
+    let (left, right) = (vertex_at(&graph, 1, 1), vertex_at(&graph, 5, 1));
+    // Where the loop comes back up into the right junction, reduction
+    // leaves one vertex on it so it doesn't double the top route's edge
+    let loop_end = graph.get_vertices()[right]
+        .get_neighbors()
+        .iter()
+        .map(|(idx, _)| *idx)
+        .find(|&idx| idx != left && idx != graph.end)
+        .unwrap();
+    assert!(graph.has_edge(left, right));
+    assert!(graph.has_edge(loop_end, right));
+
+    // The top route only leads back to the start, the loop only forward
+    assert!(graph.set_one_way(right, left));
+    assert!(graph.set_one_way(loop_end, right));
+    assert!(!graph.has_edge(left, right) && !graph.has_edge(right, loop_end));
+    // Both junctions and the loop's last vertex are still there to hold
+    // the direction
+    for idx in [left, right, loop_end] {
+        assert!(!graph.get_vertices()[idx].get_neighbors().is_empty());
+    }
+
+    for &(name, algo) in ALGORITHM_NAMES {
+        if algo == PathfindingAlgorithm::Tremaux {
+            continue;
+        }
+        let result = solve_verified(&graph, &algo).unwrap_or_else(|err| panic!("{name}: {err}"));
+        assert!(result.path.windows(2).any(|pair| pair == [loop_end, right]));
+        if algo.finds_cheapest() {
+            assert_eq!(result.cost, 12.0, "{name}");
+        }
+    }
+
+    // The other way round the top route is the only way back
+    std::mem::swap(&mut graph.start, &mut graph.end);
+    let back = solve_verified(&graph, &PathfindingAlgorithm::Dijkstra).unwrap();
+    assert!(back.path.windows(2).any(|pair| pair == [right, left]));
+    assert_eq!(back.cost, 6.0);
+}
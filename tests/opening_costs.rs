@@ -0,0 +1,52 @@
+mod common;
+
+use maze_solving::graph::Coord;
+
+/// Three connected openings, (1, 0) close to the other two, and a fourth
+/// one at (5, 6) leading into a dead end of its own
+const FOUR_OPENINGS: &[&str] = &[
+    "#.#####", //
+    "#......", //
+    "#.#####", //
+    "#.###.#", //
+    "#.###.#", //
+    "#.###.#", //
+    "#.###.#", //
+];
+
+#[test]
+fn opening_costs_are_symmetric() {
+    let graph = common::graph(&common::ascii_rows(FOUR_OPENINGS));
+    let report = graph.opening_costs();
+    let positions: Vec<Coord> = report
+        .openings
+        .iter()
+        .map(|&idx| *graph.get_vertices()[idx].get_pos())
+        .collect();
+    assert_eq!(positions.len(), 4);
+    assert_eq!(report.costs.len(), 4);
+
+    let cost = |a: Coord, b: Coord| {
+        let i = positions.iter().position(|pos| *pos == a).unwrap();
+        let j = positions.iter().position(|pos| *pos == b).unwrap();
+        report.costs[i][j]
+    };
+    let (top, right, bottom, dead_end) = (
+        Coord::new(1, 0),
+        Coord::new(6, 1),
+        Coord::new(1, 6),
+        Coord::new(5, 6),
+    );
+    assert_eq!(cost(top, right), Some(6.0));
+    assert_eq!(cost(top, bottom), Some(6.0));
+    assert_eq!(cost(right, bottom), Some(10.0));
+    assert_eq!(cost(top, dead_end), None);
+
+    for (i, row) in report.costs.iter().enumerate() {
+        assert_eq!(row.len(), 4);
+        assert_eq!(row[i], Some(0.0));
+        for (j, cost) in row.iter().enumerate() {
+            assert_eq!(*cost, report.costs[j][i], "{i} {j}");
+        }
+    }
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use maze_solving::graph::{Coord, Graph, GraphBuilder, find_boundary_vertices};
+
+/// Openings in the top left and bottom right corner, each also spilling onto
+/// the border pixel next to it
+const CORNERS: &[&str] = &[
+    "..###", //
+    "#...#", //
+    "###..", //
+];
+
+fn positions(graph: &Graph<Coord>, indices: &[usize]) -> Vec<Coord> {
+    indices
+        .iter()
+        .map(|&idx| *graph.get_vertices()[idx].get_pos())
+        .collect()
+}
+
+#[test]
+fn corner_openings_are_found_once_each() {
+    let rows = common::ascii_rows(CORNERS);
+    let graph = common::graph_with(&rows, &GraphBuilder::new().skip_reduction(true));
+    let found = find_boundary_vertices(graph.get_vertices(), 5, 3);
+    assert_eq!(
+        positions(&graph, &found),
+        [Coord::new(0, 0), Coord::new(4, 2)]
+    );
+
+    // Loading picks the same two as the endpoints
+    let graph = common::graph(&rows);
+    let openings = graph.boundary_openings();
+    assert_eq!(
+        positions(&graph, &openings),
+        [Coord::new(0, 0), Coord::new(4, 2)]
+    );
+    let mut endpoints = [graph.start, graph.end];
+    endpoints.sort_unstable();
+    let mut expected = openings.clone();
+    expected.sort_unstable();
+    assert_eq!(endpoints.to_vec(), expected);
+}
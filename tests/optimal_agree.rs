@@ -0,0 +1,60 @@
+mod common;
+
+use maze_solving::graph::{CornerPolicy, Graph, GraphBuilder};
+
+#[test]
+fn optimal_solvers_agree_on_generated_mazes() {
+    for cells in [3, 10, 25] {
+        for loops in [0, cells as usize, 10 * cells as usize] {
+            for seed in 0..3 {
+                let rows = common::maze_rows(cells, seed, loops);
+                assert!(common::assert_optimal_agree(&common::graph(&rows)).is_some());
+            }
+        }
+    }
+}
+
+#[test]
+fn optimal_solvers_agree_on_unweighted_graphs() {
+    // Without reduction every edge costs 1, so BFS is checked too
+    let builder = GraphBuilder::new().skip_reduction(true);
+    for seed in 0..3 {
+        let maze = common::graph_with(&common::maze_rows(10, seed, 20), &builder);
+        assert!(common::assert_optimal_agree(&maze).is_some());
+        let room = common::graph_with(&common::room_rows(20, seed, 0.3), &builder);
+        common::assert_optimal_agree(&room);
+    }
+}
+
+#[test]
+fn optimal_solvers_agree_with_diagonals() {
+    for builder in [
+        GraphBuilder::new().diagonals(CornerPolicy::Always),
+        GraphBuilder::new().diagonals(CornerPolicy::NoSqueezing),
+        GraphBuilder::new()
+            .diagonals(CornerPolicy::NoCornerCutting)
+            .king_moves(true),
+    ] {
+        for seed in 0..3 {
+            let room = common::graph_with(&common::room_rows(30, seed, 0.3), &builder);
+            common::assert_optimal_agree(&room);
+        }
+    }
+}
+
+#[test]
+fn optimal_solvers_agree_in_3d() {
+    let layer = |seed| common::maze_rows(5, seed, 5);
+    let graph = Graph::from_layers(&[layer(1), layer(2), layer(3)]).unwrap();
+    assert!(common::assert_optimal_agree(&graph).is_some());
+}
+
+#[test]
+fn optimal_solvers_agree_there_is_no_path() {
+    let rows = common::ascii_rows(&[
+        "#.###", //
+        "#.#.#", //
+        "###.#", //
+    ]);
+    assert_eq!(common::assert_optimal_agree(&common::graph(&rows)), None);
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "parallel")]
+
+mod common;
+
+use maze_solving::pathfinding::{
+    PathfindingAlgorithm, calculate_cost, dijkstra, parallel_bfs, parallel_dijkstra, solve_graph,
+};
+
+#[test]
+fn parallel_dijkstra_costs_the_same_as_dijkstra() {
+    for seed in 0..3 {
+        let graph = common::graph(&common::maze_rows(120, seed, 2000));
+        let expected = calculate_cost(&graph, &dijkstra(&graph).unwrap());
+        let result = parallel_dijkstra(&graph).unwrap();
+        assert_eq!(result.cost, expected);
+        assert_eq!(calculate_cost(&graph, &result.path), expected);
+        assert_eq!(result.path.first(), Some(&graph.start));
+        assert_eq!(result.path.last(), Some(&graph.end));
+    }
+}
+
+#[test]
+fn parallel_bfs_takes_as_few_hops_as_bfs() {
+    let graph = common::graph(&common::maze_rows(120, 1, 2000));
+    let bfs = solve_graph(&graph, &PathfindingAlgorithm::BreadthFirst)
+        .unwrap()
+        .unwrap();
+    assert_eq!(parallel_bfs(&graph).unwrap().len(), bfs.len());
+}
@@ -0,0 +1,26 @@
+mod common;
+
+use maze_solving::pathfinding::dijkstra;
+
+#[test]
+fn path_coords_run_from_start_to_end_pixel_by_pixel() {
+    for seed in 0..4 {
+        let graph = common::graph(&common::maze_rows(8, seed, 6));
+        let path = dijkstra(&graph).unwrap();
+        let coords = graph.path_to_coords(&path);
+        let start = graph.get_vertices()[graph.start].get_pos();
+        let end = graph.get_vertices()[graph.end].get_pos();
+        assert_eq!(coords.first(), Some(&(start.x(), start.y())));
+        assert_eq!(coords.last(), Some(&(end.x(), end.y())));
+
+        // Every pixel of the corridors in between, not just the junctions
+        assert!(coords.len() > path.len());
+        for pair in coords.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            assert_eq!(x0.abs_diff(x1) + y0.abs_diff(y1), 1, "{pair:?}");
+        }
+
+        let json: Vec<(u32, u32)> = serde_json::from_str(&graph.path_to_json(&path)).unwrap();
+        assert_eq!(json, coords);
+    }
+}
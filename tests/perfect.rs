@@ -0,0 +1,41 @@
+mod common;
+
+use maze_solving::pathfinding::PathfindingAlgorithm;
+
+/// A tree of corridors with a side branch and no loops
+const TREE: &[&str] = &[
+    "#.#####", //
+    "#.....#", //
+    "#.#.#.#", //
+    "#.#.#.#", //
+    "#.#####", //
+];
+
+/// The same corridors with the two branches joined at the bottom
+const LOOP: &[&str] = &[
+    "#.#####", //
+    "#.....#", //
+    "#.#.#.#", //
+    "#.#...#", //
+    "#.#####", //
+];
+
+#[test]
+fn trees_are_perfect_and_loops_are_not() {
+    let tree = common::graph(&common::ascii_rows(TREE));
+    assert!(tree.is_perfect());
+    let paths = tree
+        .k_shortest_paths(3, &PathfindingAlgorithm::Dijkstra)
+        .unwrap();
+    assert_eq!(paths.len(), 1);
+
+    assert!(!common::graph(&common::ascii_rows(LOOP)).is_perfect());
+}
+
+#[test]
+fn carved_mazes_are_perfect_until_a_wall_comes_down() {
+    for seed in 0..4 {
+        assert!(common::graph(&common::maze_rows(8, seed, 0)).is_perfect());
+        assert!(!common::graph(&common::maze_rows(8, seed, 1)).is_perfect());
+    }
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use maze_solving::graph::{Adjacent, Coord, Graph, GraphBuilder};
+use maze_solving::pathfinding::{ALGORITHM_NAMES, solve_verified};
+
+/// A corridor widening into a 2x2 open block on its way to the exit
+const OPEN_BLOCK: &[&str] = &[
+    "#.#####", //
+    "#.#####", //
+    "#..####", //
+    "##..###", //
+    "###..##", //
+    "####.##", //
+];
+
+/// Just the block, entered and left at opposite corners
+const BARE_BLOCK: &[&str] = &[
+    "#.###", //
+    "#..##", //
+    "#..##", //
+    "##.##", //
+];
+
+fn assert_valid(graph: &Graph<Coord>) {
+    for (idx, vertex) in graph.get_vertices().iter().enumerate() {
+        let mut neighbors: Vec<usize> = vertex.get_neighbors().iter().map(|(n, _)| *n).collect();
+        neighbors.sort_unstable();
+        let count = neighbors.len();
+        neighbors.dedup();
+        assert_eq!(neighbors.len(), count, "{idx} has parallel edges");
+    }
+    for (from, to, weight) in graph.edges() {
+        assert_ne!(from, to, "self-loop");
+        assert!(weight > 0.0);
+        assert!(graph.has_edge(to, from), "{from} -> {to} is one-way");
+    }
+}
+
+fn assert_reduces_cleanly(rows: &[Vec<bool>]) {
+    let reduced = common::graph(rows);
+    let full = common::graph_with(rows, &GraphBuilder::new().skip_reduction(true));
+    assert_valid(&reduced);
+    for &(name, algo) in ALGORITHM_NAMES {
+        let Ok(result) = solve_verified(&reduced, &algo) else {
+            panic!("{name} failed on the reduced graph");
+        };
+        let unreduced = solve_verified(&full, &algo).unwrap();
+        if algo.finds_cheapest() {
+            assert_eq!(result.cost, unreduced.cost, "{name}");
+        }
+    }
+}
+
+#[test]
+fn open_blocks_reduce_to_a_solvable_graph() {
+    assert_reduces_cleanly(&common::ascii_rows(OPEN_BLOCK));
+    assert_reduces_cleanly(&common::ascii_rows(BARE_BLOCK));
+}
+
+#[test]
+fn open_rooms_reduce_to_a_solvable_graph() {
+    for seed in 0..4 {
+        let rows = common::room_rows(9, seed, 0.2);
+        let reduced = common::graph(&rows);
+        assert_valid(&reduced);
+        common::assert_optimal_agree(&reduced);
+    }
+}
+
+#[test]
+fn skipping_reduction_keeps_every_open_pixel_live() {
+    let rows = common::maze_rows(8, 3, 10);
+    let full = common::graph_with(&rows, &GraphBuilder::new().skip_reduction(true));
+    let open: Vec<Coord> = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, open)| **open)
+                .map(move |(x, _)| Coord::new(x as u32, y as u32))
+        })
+        .collect();
+    let vertices = full.get_vertices();
+    assert_eq!(vertices.len(), open.len());
+
+    for (vertex, pos) in vertices.iter().zip(&open) {
+        assert_eq!(vertex.get_pos(), pos);
+        // One edge to every open pixel next to it
+        let expected = pos
+            .potential_neighbors()
+            .filter(|n| open.contains(n))
+            .count();
+        assert_eq!(vertex.get_neighbors().len(), expected, "{pos}");
+        for (neighbor, weight) in vertex.get_neighbors() {
+            let at = vertices[*neighbor].get_pos();
+            assert!(pos.potential_neighbors().any(|n| n == *at), "{pos} -> {at}");
+            assert_eq!(*weight, 1.0);
+        }
+    }
+    // While the default load empties out the corridors between junctions
+    assert!(
+        common::graph(&rows)
+            .get_vertices()
+            .iter()
+            .any(|v| v.get_neighbors().is_empty())
+    );
+}
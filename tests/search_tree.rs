@@ -0,0 +1,42 @@
+mod common;
+
+use maze_solving::pathfinding::{ALGORITHM_NAMES, solve_graph_tree};
+
+#[test]
+fn path_vertices_trace_back_to_start_in_the_tree() {
+    let graph = common::graph(&common::maze_rows(8, 5, 10));
+    for &(name, algo) in ALGORITHM_NAMES {
+        let (path, tree) = solve_graph_tree(&graph, &algo).unwrap();
+        let path = path.unwrap();
+        assert_eq!(tree.len(), graph.get_vertices().len(), "{name}");
+        assert_eq!(tree[graph.start], None, "{name}");
+
+        for &vertex in &path {
+            // Each parent is a neighbor, and the chain ends at start
+            let mut current = vertex;
+            let mut steps = 0;
+            while let Some(parent) = tree[current] {
+                assert!(graph.has_edge(parent, current), "{name}");
+                current = parent;
+                steps += 1;
+                assert!(steps <= tree.len(), "{name} has a cycle");
+            }
+            assert_eq!(current, graph.start, "{name} from {vertex}");
+        }
+
+        // The path is the chain from `end`, except for walks like Trémaux's
+        // that pass some vertices more than once
+        let mut unique = path.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        if unique.len() < path.len() {
+            continue;
+        }
+        let mut back = vec![graph.end];
+        while let Some(parent) = tree[*back.last().unwrap()] {
+            back.push(parent);
+        }
+        back.reverse();
+        assert_eq!(back, path, "{name}");
+    }
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use maze_solving::graph::Coord;
+use maze_solving::pathfinding::{
+    PathResult, PathfindingAlgorithm, SearchEvent, calculate_cost, solve_graph_traced,
+};
+
+#[test]
+fn paths_and_traces_round_trip_through_json() {
+    let graph = common::graph(&common::maze_rows(5, 2, 3));
+    let (path, events) = solve_graph_traced(&graph, &PathfindingAlgorithm::Dijkstra).unwrap();
+    let path = path.unwrap();
+    let result = PathResult {
+        cost: calculate_cost(&graph, &path),
+        path,
+        expansions: 0,
+    };
+
+    let json = serde_json::to_string(&result).unwrap();
+    assert_eq!(serde_json::from_str::<PathResult>(&json).unwrap(), result);
+    let json = serde_json::to_string(&events).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Vec<SearchEvent>>(&json).unwrap(),
+        events
+    );
+
+    let coord = Coord::new(3, 7);
+    let json = serde_json::to_string(&coord).unwrap();
+    assert_eq!(json, r#"{"x":3,"y":7}"#);
+    assert_eq!(serde_json::from_str::<Coord>(&json).unwrap(), coord);
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use maze_solving::pathfinding::{
+    ALL_PAIRS_VERTEX_LIMIT, SolveError, calculate_cost, dijkstra, shortest_paths_from,
+};
+
+#[test]
+fn distances_match_dijkstra_to_each_target() {
+    let mut graph = common::graph(&common::maze_rows(6, 4, 8));
+    let source = graph.start;
+    let (dists, parents) = shortest_paths_from(&graph, source);
+    assert_eq!(dists[source], 0.0);
+    assert_eq!(parents[source], None);
+
+    for target in 0..graph.get_vertices().len() {
+        if graph.get_vertices()[target].get_neighbors().is_empty() {
+            // Emptied out by reduction, nothing reaches it
+            assert_eq!(dists[target], f32::INFINITY);
+            assert_eq!(parents[target], None);
+            continue;
+        }
+        graph.end = target;
+        let path = dijkstra(&graph).unwrap();
+        assert_eq!(dists[target], calculate_cost(&graph, &path), "{target}");
+
+        // Following the parents gives a path just as cheap
+        let mut back = vec![target];
+        while let Some(parent) = parents[*back.last().unwrap()] {
+            back.push(parent);
+        }
+        back.reverse();
+        assert_eq!(back[0], source);
+        assert_eq!(calculate_cost(&graph, &back), dists[target]);
+    }
+}
+
+#[test]
+fn all_pairs_match_dijkstra_from_each_source() {
+    let graph = common::graph(&common::maze_rows(5, 2, 6));
+    let all_pairs = graph.all_pairs_shortest_paths().unwrap();
+    assert_eq!(all_pairs.len(), graph.get_vertices().len());
+    for (source, row) in all_pairs.iter().enumerate() {
+        assert_eq!(*row, shortest_paths_from(&graph, source).0, "{source}");
+    }
+}
+
+#[test]
+fn all_pairs_refuse_large_graphs() {
+    let graph = common::graph(&common::maze_rows(30, 0, 0));
+    let vertex_count = graph.get_vertices().len();
+    assert!(vertex_count > ALL_PAIRS_VERTEX_LIMIT);
+    assert_eq!(
+        graph.all_pairs_shortest_paths().err(),
+        Some(SolveError::TooManyVertices {
+            vertex_count,
+            limit: ALL_PAIRS_VERTEX_LIMIT,
+        })
+    );
+}
+
+#[test]
+fn reachable_within_stops_at_the_budget() {
+    let graph = common::graph(&common::maze_rows(8, 1, 12));
+    let source = graph.start;
+    let (dists, _) = shortest_paths_from(&graph, source);
+    let within = |budget: f32| -> Vec<usize> {
+        (0..dists.len())
+            .filter(|&idx| dists[idx] <= budget)
+            .collect()
+    };
+
+    for &dist in dists.iter().filter(|dist| dist.is_finite()) {
+        // A vertex exactly at the budget is in, one just beyond it is out
+        assert_eq!(graph.reachable_within(source, dist), within(dist), "{dist}");
+        let below = dist - 0.5;
+        assert_eq!(
+            graph.reachable_within(source, below),
+            within(below),
+            "{below}"
+        );
+    }
+    assert_eq!(graph.reachable_within(source, 0.0), [source]);
+}
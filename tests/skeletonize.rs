@@ -0,0 +1,54 @@
+mod common;
+
+use maze_solving::graph::GraphBuilder;
+use maze_solving::pathfinding::{PathfindingAlgorithm, calculate_cost, solve_graph};
+
+/// Passages and walls `SCALE` pixels wide
+const SCALE: usize = 5;
+
+fn thick(rows: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    (0..rows.len() * SCALE)
+        .map(|y| {
+            (0..rows[0].len() * SCALE)
+                .map(|x| rows[y / SCALE][x / SCALE])
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn thick_passages_solve_along_their_centerline() {
+    for seed in 0..3 {
+        let rows = common::maze_rows(5, seed, 3);
+        let thin = common::graph(&rows);
+        let thin_path = solve_graph(&thin, &PathfindingAlgorithm::Dijkstra)
+            .unwrap()
+            .unwrap();
+        let thin_cost = calculate_cost(&thin, &thin_path);
+
+        let graph = common::graph_with(&thick(&rows), &GraphBuilder::new().skeletonize(true));
+        let path = solve_graph(&graph, &PathfindingAlgorithm::Dijkstra)
+            .unwrap()
+            .unwrap();
+        let cost = calculate_cost(&graph, &path);
+        // Every cell the thin path crosses is SCALE pixels long
+        assert!(
+            (cost - thin_cost * SCALE as f32).abs() <= SCALE as f32,
+            "seed {seed}"
+        );
+
+        let grid = graph.get_grid().unwrap();
+        let center = (SCALE / 2) as u32;
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                // On a centerline, or rounding off a corner within a pixel
+                // of the cell's center
+                if grid.is_open(x, y) {
+                    let off = |v: u32| (v % SCALE as u32).abs_diff(center);
+                    let (near, far) = (off(x).min(off(y)), off(x).max(off(y)));
+                    assert!(near == 0 || far <= 1, "({x}, {y}) seed {seed}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use maze_solving::graph::GraphBuilder;
+use maze_solving::pathfinding::{dijkstra, smooth_path};
+
+/// A corridor three pixels wide running diagonally down to the right, which
+/// a four-connected path can only take as a staircase
+const DIAGONAL_BAND: &[&str] = &[
+    "#.#########", //
+    "#...#######", //
+    "##...######", //
+    "###...#####", //
+    "####...####", //
+    "#####...###", //
+    "######...##", //
+    "#######...#", //
+    "########.##", //
+];
+
+#[test]
+fn zig_zag_smooths_to_fewer_waypoints_without_crossing_walls() {
+    let graph = common::graph_with(
+        &common::ascii_rows(DIAGONAL_BAND),
+        &GraphBuilder::new().skip_reduction(true),
+    );
+    let grid = graph.get_grid().unwrap();
+    let pos = |idx: usize| graph.get_vertices()[idx].get_pos();
+
+    let path = dijkstra(&graph).unwrap();
+    let smoothed = smooth_path(&graph, &path);
+    assert!(smoothed.len() < path.len() / 2, "{smoothed:?}");
+    assert_eq!(smoothed.first(), path.first());
+    assert_eq!(smoothed.last(), path.last());
+    // Every waypoint comes from the path, in order
+    let mut rest = path.iter();
+    assert!(smoothed.iter().all(|idx| rest.any(|p| p == idx)));
+    for pair in smoothed.windows(2) {
+        assert!(grid.line_of_sight(pos(pair[0]), pos(pair[1])));
+    }
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use maze_solving::graph::{Coord, Graph};
+
+/// A straight corridor with a sealed off junction right next to its middle
+const WALLED_OFF_JUNCTION: &[&str] = &[
+    "#.#####", //
+    "#.#####", //
+    "#.#####", //
+    "#.#####", //
+    "#.#.###", //
+    "#.#..##", //
+    "#.#.###", //
+    "#.#####", //
+    "#.#####", //
+    "#.#####", //
+    "#.#####", //
+];
+
+fn pos(graph: &Graph<Coord>, idx: usize) -> Coord {
+    *graph.get_vertices()[idx].get_pos()
+}
+
+#[test]
+fn snapping_does_not_cross_walls() {
+    let graph = common::graph(&common::ascii_rows(WALLED_OFF_JUNCTION));
+    let click = Coord::new(1, 5);
+
+    // In a straight line the junction is closest
+    let nearest = graph.nearest(&click).unwrap();
+    assert_eq!(pos(&graph, nearest), Coord::new(3, 5));
+
+    // Walking the corridor both openings are 5 steps away
+    let snapped = graph.snap_to_vertex(&click, 10).unwrap();
+    assert_eq!(snapped, graph.start.min(graph.end));
+    assert_eq!(pos(&graph, snapped).x(), 1);
+    assert_eq!(graph.snap_to_vertex(&click, 4), None);
+}
+
+#[test]
+fn snapping_from_a_wall_or_a_vertex() {
+    let graph = common::graph(&common::ascii_rows(WALLED_OFF_JUNCTION));
+
+    let junction = graph.snap_to_vertex(&Coord::new(3, 5), 0).unwrap();
+    assert_eq!(pos(&graph, junction), Coord::new(3, 5));
+
+    // The wall pixel between both is one step from the junction
+    assert_eq!(graph.snap_to_vertex(&Coord::new(2, 5), 1), Some(junction));
+    assert_eq!(graph.snap_to_vertex(&Coord::new(7, 5), 10), None);
+}
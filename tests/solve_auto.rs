@@ -0,0 +1,42 @@
+mod common;
+
+use maze_solving::pathfinding::{AutoAlgorithm, Preset, SolveError, solve_auto};
+
+#[test]
+fn every_preset_finds_a_path() {
+    let graph = common::graph(&common::maze_rows(10, 5, 15));
+    for preset in [Preset::Fastest, Preset::Balanced, Preset::Optimal] {
+        let (algorithm, result) = solve_auto(&graph, preset).unwrap().unwrap();
+        assert_eq!(result.path.first(), Some(&graph.start));
+        assert_eq!(result.path.last(), Some(&graph.end));
+        if preset == Preset::Optimal {
+            assert_eq!(algorithm, AutoAlgorithm::AStar);
+        }
+    }
+}
+
+#[test]
+fn out_of_range_endpoints_are_errors() {
+    let graph = common::graph(&common::maze_rows(10, 5, 15));
+    let vertex_count = graph.get_vertices().len();
+
+    let mut bad_start = graph.clone();
+    bad_start.start = vertex_count;
+    assert!(matches!(
+        solve_auto(&bad_start, Preset::Optimal),
+        Err(SolveError::EndpointOutOfRange {
+            endpoint: "start",
+            ..
+        })
+    ));
+
+    let mut bad_end = graph;
+    bad_end.end = vertex_count + 5;
+    assert!(matches!(
+        solve_auto(&bad_end, Preset::Fastest),
+        Err(SolveError::EndpointOutOfRange {
+            endpoint: "end",
+            ..
+        })
+    ));
+}
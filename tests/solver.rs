@@ -0,0 +1,22 @@
+mod common;
+
+use maze_solving::pathfinding::{ALGORITHM_NAMES, Solver, solve_graph};
+
+#[test]
+fn reused_solver_gives_identical_results() {
+    let small = common::graph(&common::maze_rows(5, 1, 4));
+    let large = common::graph(&common::maze_rows(12, 2, 20));
+    for &(name, algo) in ALGORITHM_NAMES {
+        let mut solver = Solver::new(algo);
+        let first = solver.solve(&large).unwrap();
+        let visited = solver.visited().to_vec();
+        assert_eq!(solver.solve(&large).unwrap(), first, "{name}");
+        assert_eq!(solver.visited(), visited, "{name}");
+        assert_eq!(first, solve_graph(&large, &algo).unwrap(), "{name}");
+
+        // Buffers sized for the larger graph don't leak into a smaller one
+        let fresh = solve_graph(&small, &algo).unwrap();
+        assert_eq!(solver.solve(&small).unwrap(), fresh, "{name}");
+        assert_eq!(solver.solve(&large).unwrap(), first, "{name}");
+    }
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use maze_solving::pathfinding::{PathfindingAlgorithm, SearchState, dijkstra, solve_graph};
+
+#[test]
+fn stepping_dijkstra_to_the_end_matches_dijkstra() {
+    for seed in 0..4 {
+        let graph = common::graph(&common::maze_rows(8, seed, 12));
+        let mut state = SearchState::dijkstra(&graph).unwrap();
+        assert_eq!(state.frontier(), [graph.start]);
+        assert_eq!(state.path(), None);
+
+        let mut steps = 0;
+        let mut last_cost = 0.0;
+        while !state.step() {
+            steps += 1;
+            // Vertices come off the frontier in order of cost
+            let current = state.current().unwrap();
+            let cost = state.distance(current).unwrap();
+            assert!(cost >= last_cost, "seed {seed}");
+            last_cost = cost;
+        }
+        assert!(state.is_done());
+        assert_eq!(state.expansions(), steps);
+        assert_eq!(state.current(), Some(graph.end));
+
+        let expected = dijkstra(&graph).unwrap();
+        assert_eq!(state.path(), Some(expected.clone()), "seed {seed}");
+        // Further steps change nothing
+        assert!(state.step());
+        assert_eq!(state.path(), Some(expected));
+    }
+}
+
+#[test]
+fn stepping_bfs_matches_bfs() {
+    let graph = common::graph(&common::maze_rows(8, 1, 12));
+    let mut state = SearchState::bfs(&graph).unwrap();
+    assert_eq!(
+        state.run(),
+        solve_graph(&graph, &PathfindingAlgorithm::BreadthFirst).unwrap()
+    );
+}
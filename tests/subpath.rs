@@ -0,0 +1,34 @@
+mod common;
+
+use maze_solving::pathfinding::{calculate_cost, dijkstra, subpath};
+
+#[test]
+fn subpath_between_waypoints_in_order() {
+    let path = [4, 8, 15, 16, 23, 42];
+    assert_eq!(subpath(&path, 8, 23), Some(vec![8, 15, 16, 23]));
+    assert_eq!(subpath(&path, 4, 42), Some(path.to_vec()));
+    assert_eq!(subpath(&path, 15, 15), Some(vec![15]));
+
+    // Part of a solved path is a path of its own
+    let graph = common::graph(&common::maze_rows(8, 1, 6));
+    let solved = dijkstra(&graph).unwrap();
+    let (from, to) = (solved[1], solved[solved.len() - 2]);
+    let middle = subpath(&solved, from, to).unwrap();
+    assert_eq!(middle, solved[1..solved.len() - 1]);
+    assert!(calculate_cost(&graph, &middle) < calculate_cost(&graph, &solved));
+}
+
+#[test]
+fn subpath_in_reverse_order_is_none() {
+    let path = [4, 8, 15, 16, 23, 42];
+    assert_eq!(subpath(&path, 23, 8), None);
+    assert_eq!(subpath(&path, 42, 4), None);
+}
+
+#[test]
+fn subpath_with_a_waypoint_off_the_path_is_none() {
+    let path = [4, 8, 15, 16, 23, 42];
+    assert_eq!(subpath(&path, 7, 23), None);
+    assert_eq!(subpath(&path, 8, 7), None);
+    assert_eq!(subpath(&[], 4, 8), None);
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use std::collections::HashSet;
+
+use maze_solving::graph::{Coord, Graph};
+use maze_solving::pathfinding::{PathResult, suurballe, suurballe_sharing_entrances};
+
+/// A loop around a pillar, entered at the top and left at the bottom
+const RING: &[&str] = &[
+    "#.###", //
+    "#...#", //
+    "#.#.#", //
+    "#...#", //
+    "###.#", //
+];
+
+/// The same loop, but left through a corridor every route has to take
+const RING_WITH_BRIDGE: &[&str] = &[
+    "#.#####", //
+    "#...###", //
+    "#.#.###", //
+    "#......", //
+    "#######", //
+];
+
+fn vertex_at(graph: &Graph<Coord>, x: u32, y: u32) -> usize {
+    graph
+        .get_vertices()
+        .iter()
+        .position(|vertex| *vertex.get_pos() == Coord::new(x, y))
+        .expect("every open pixel has a vertex")
+}
+
+fn undirected_edges(path: &[usize]) -> HashSet<(usize, usize)> {
+    path.windows(2)
+        .map(|step| (step[0].min(step[1]), step[0].max(step[1])))
+        .collect()
+}
+
+fn assert_connects(graph: &Graph<Coord>, pair: &[PathResult; 2]) {
+    for result in pair {
+        assert_eq!(result.path.first(), Some(&graph.start));
+        assert_eq!(result.path.last(), Some(&graph.end));
+    }
+    assert!(pair[0].cost <= pair[1].cost);
+}
+
+#[test]
+fn finds_a_disjoint_pair_around_a_loop() {
+    let mut graph = common::graph(&common::ascii_rows(RING));
+    graph.start = vertex_at(&graph, 1, 1);
+    graph.end = vertex_at(&graph, 3, 3);
+
+    let pair = suurballe(&graph).unwrap();
+    assert_connects(&graph, &pair);
+    assert_eq!([pair[0].cost, pair[1].cost], [4.0, 4.0]);
+    assert!(undirected_edges(&pair[0].path).is_disjoint(&undirected_edges(&pair[1].path)));
+}
+
+#[test]
+fn entrance_corridors_rule_out_a_disjoint_pair() {
+    let graph = common::graph(&common::ascii_rows(RING));
+    assert!(suurballe(&graph).is_none());
+
+    let pair = suurballe_sharing_entrances(&graph).unwrap();
+    assert_connects(&graph, &pair);
+    assert_eq!([pair[0].cost, pair[1].cost], [6.0, 6.0]);
+}
+
+#[test]
+fn a_bridge_rules_out_a_disjoint_pair() {
+    let mut graph = common::graph(&common::ascii_rows(RING_WITH_BRIDGE));
+    graph.start = vertex_at(&graph, 1, 1);
+    assert!(suurballe(&graph).is_none());
+
+    // The bridge ends in the exit, so it counts as the exit's corridor
+    let pair = suurballe_sharing_entrances(&graph).unwrap();
+    assert_connects(&graph, &pair);
+}
+
+#[test]
+fn perfect_mazes_have_no_disjoint_pair() {
+    let graph = common::graph(&common::maze_rows(8, 2, 0));
+    assert!(suurballe(&graph).is_none());
+    assert!(suurballe_sharing_entrances(&graph).is_none());
+}
+
+#[test]
+fn looped_mazes_have_a_disjoint_pair_between_junctions() {
+    for seed in 0..4 {
+        let mut graph = common::graph(&common::maze_rows(10, seed, 80));
+        let shared = suurballe_sharing_entrances(&graph).unwrap();
+        assert_connects(&graph, &shared);
+
+        // The junctions where the two paths split up and meet again
+        let [a, b] = [&shared[0].path, &shared[1].path];
+        let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+        let suffix = a
+            .iter()
+            .rev()
+            .zip(b.iter().rev())
+            .take_while(|(x, y)| x == y)
+            .count();
+        graph.start = a[prefix - 1];
+        graph.end = a[a.len() - suffix];
+        for junction in [graph.start, graph.end] {
+            assert!(graph.get_vertices()[junction].get_neighbors().len() >= 3);
+        }
+
+        let pair = suurballe(&graph).unwrap();
+        assert_connects(&graph, &pair);
+        assert!(undirected_edges(&pair[0].path).is_disjoint(&undirected_edges(&pair[1].path)));
+    }
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use maze_solving::graph::{Coord, Graph};
+use maze_solving::pathfinding::{PathfindingAlgorithm, calculate_cost, solve_graph, theta_star};
+
+fn astar_cost(graph: &Graph<Coord>) -> f32 {
+    let path = solve_graph(graph, &PathfindingAlgorithm::AStar)
+        .unwrap()
+        .unwrap();
+    calculate_cost(graph, &path)
+}
+
+/// Asserts every step of the Theta* path is either a straight line through
+/// open pixels or an edge of the graph, and returns its cost.
+fn theta_star_cost(graph: &Graph<Coord>) -> f32 {
+    let result = theta_star(graph).unwrap();
+    let grid = graph.get_grid().unwrap();
+    let pos = |idx: usize| graph.get_vertices()[idx].get_pos();
+    assert_eq!(result.path.first(), Some(&graph.start));
+    assert_eq!(result.path.last(), Some(&graph.end));
+    for pair in result.path.windows(2) {
+        assert!(grid.line_of_sight(pos(pair[0]), pos(pair[1])) || graph.has_edge(pair[0], pair[1]));
+    }
+    result.cost
+}
+
+#[test]
+fn never_longer_than_astar_in_rooms() {
+    for seed in 0..4 {
+        let graph = common::graph(&common::room_rows(15, seed, 0.2));
+        assert!(theta_star_cost(&graph) <= astar_cost(&graph) + 1e-3);
+    }
+}
+
+#[test]
+fn cuts_across_an_open_room() {
+    let graph = common::graph(&common::room_rows(15, 0, 0.0));
+    assert!(theta_star_cost(&graph) < astar_cost(&graph) - 1.0);
+}
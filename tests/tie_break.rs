@@ -0,0 +1,36 @@
+mod common;
+
+use maze_solving::graph::GraphBuilder;
+use maze_solving::pathfinding::{PathfindingAlgorithm, calculate_cost, dijkstra, solve_graph};
+
+/// A loop around a pillar, both ways around it as long
+const RING: &[&str] = &[
+    "#.###", //
+    "#...#", //
+    "#.#.#", //
+    "#...#", //
+    "###.#", //
+];
+
+#[test]
+fn equal_cost_routes_give_the_same_path_every_run() {
+    let rows = common::ascii_rows(RING);
+    for builder in [
+        GraphBuilder::new(),
+        GraphBuilder::new().skip_reduction(true),
+    ] {
+        let first = common::graph_with(&rows, &builder);
+        let expected = dijkstra(&first).unwrap();
+        assert_eq!(calculate_cost(&first, &expected), 6.0);
+        // Ties go to the lower vertex index, which is the pixel further up
+        // and so the route to the right of the pillar
+        assert!(first.path_to_coords(&expected).contains(&(3, 1)));
+
+        for _ in 0..20 {
+            let graph = common::graph_with(&rows, &builder);
+            assert_eq!(dijkstra(&graph).unwrap(), expected);
+            let solved = solve_graph(&graph, &PathfindingAlgorithm::Dijkstra).unwrap();
+            assert_eq!(solved, Some(expected.clone()));
+        }
+    }
+}
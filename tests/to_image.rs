@@ -0,0 +1,69 @@
+#![cfg(feature = "image")]
+
+mod common;
+
+use image::ImageFormat;
+use maze_solving::graph::{Coord, Graph};
+use maze_solving::pathfinding::dijkstra;
+
+fn png(img: &image::RgbImage) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)
+        .unwrap();
+    bytes
+}
+
+fn assert_same_graph(a: &Graph<Coord>, b: &Graph<Coord>) {
+    let positions = |graph: &Graph<Coord>| -> Vec<Coord> {
+        graph.get_vertices().iter().map(|v| *v.get_pos()).collect()
+    };
+    assert_eq!(positions(a), positions(b));
+    assert_eq!(a.edges().collect::<Vec<_>>(), b.edges().collect::<Vec<_>>());
+    assert_eq!((a.start, a.end), (b.start, b.end));
+}
+
+#[test]
+fn to_image_round_trips_through_png() {
+    for seed in 0..4 {
+        let rows = common::maze_rows(8, seed, 6);
+        let graph = Graph::from_bytes(&common::encode(&rows, ImageFormat::Png, 1)).unwrap();
+        let img = graph.to_image();
+        assert_eq!(img.dimensions(), (rows[0].len() as u32, rows.len() as u32));
+        for (x, y, pixel) in img.enumerate_pixels() {
+            let open = rows[y as usize][x as usize];
+            assert_eq!(pixel.0, if open { [255; 3] } else { [0; 3] }, "({x}, {y})");
+        }
+
+        let reloaded = Graph::from_bytes(&png(&img)).unwrap();
+        assert_same_graph(&graph, &reloaded);
+    }
+}
+
+#[test]
+fn exported_solution_reloads_as_a_single_corridor() {
+    for seed in 0..4 {
+        let rows = common::maze_rows(8, seed, 10);
+        let graph = Graph::from_bytes(&common::encode(&rows, ImageFormat::Png, 1)).unwrap();
+        let path = dijkstra(&graph).unwrap();
+        let output = common::temp_file(&format!("solution_{seed}.png"), &[]);
+        graph.export_solution_only(&path, &output).unwrap();
+
+        let corridor = Graph::from_png(&output).unwrap();
+        // Reduced to the two openings joined by one edge
+        let live: Vec<usize> = (0..corridor.get_vertices().len())
+            .filter(|&idx| !corridor.get_vertices()[idx].get_neighbors().is_empty())
+            .collect();
+        assert_eq!(live.len(), 2, "seed {seed}");
+        assert!(corridor.is_perfect());
+
+        let pos = |graph: &Graph<Coord>, idx: usize| *graph.get_vertices()[idx].get_pos();
+        assert_eq!(pos(&corridor, corridor.start), pos(&graph, graph.start));
+        assert_eq!(pos(&corridor, corridor.end), pos(&graph, graph.end));
+        let solution = dijkstra(&corridor).unwrap();
+        assert_eq!(solution.len(), 2);
+        assert_eq!(
+            corridor.path_to_coords(&solution),
+            graph.path_to_coords(&path)
+        );
+    }
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use maze_solving::pathfinding::{
+    PathfindingAlgorithm, SearchEvent, dijkstra, solve_graph_traced, trace_to_json,
+};
+use std::collections::HashSet;
+
+#[test]
+fn dijkstra_trace_ends_with_the_path_found() {
+    for seed in 0..4 {
+        let graph = common::graph(&common::maze_rows(8, seed, 8));
+        let (path, events) = solve_graph_traced(&graph, &PathfindingAlgorithm::Dijkstra).unwrap();
+        let expected = dijkstra(&graph).unwrap();
+        assert_eq!(path.as_ref(), Some(&expected));
+        assert_eq!(
+            events.last(),
+            Some(&SearchEvent::Found {
+                path: expected.clone()
+            })
+        );
+
+        // Replaying the events, only vertices on the frontier are visited
+        let mut frontier = HashSet::from([graph.start]);
+        for event in &events {
+            match event {
+                SearchEvent::Visit { vertex } => {
+                    assert!(frontier.remove(vertex), "seed {seed} vertex {vertex}")
+                }
+                SearchEvent::Relax { vertex, .. } => {
+                    frontier.insert(*vertex);
+                }
+                _ => {}
+            }
+        }
+
+        let json: serde_json::Value = serde_json::from_str(&trace_to_json(&events)).unwrap();
+        let json = json.as_array().unwrap();
+        assert_eq!(json.len(), events.len());
+        assert_eq!(json.last().unwrap()["found"], serde_json::json!(expected));
+    }
+}
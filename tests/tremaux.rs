@@ -0,0 +1,27 @@
+mod common;
+
+use std::collections::HashMap;
+
+use maze_solving::pathfinding::{PathfindingAlgorithm, solve_graph};
+
+#[test]
+fn walk_reaches_the_end_of_looped_mazes() {
+    for seed in 0..6 {
+        let graph = common::graph(&common::maze_rows(10, seed, 30));
+        let walk = solve_graph(&graph, &PathfindingAlgorithm::Tremaux)
+            .unwrap()
+            .unwrap();
+        assert_eq!(walk.first(), Some(&graph.start));
+        assert_eq!(walk.last(), Some(&graph.end));
+
+        // Every step follows an edge and no passage is walked a third time
+        let mut walked: HashMap<(usize, usize), usize> = HashMap::new();
+        for step in walk.windows(2) {
+            assert!(graph.has_edge(step[0], step[1]), "seed {seed}");
+            *walked
+                .entry((step[0].min(step[1]), step[0].max(step[1])))
+                .or_default() += 1;
+        }
+        assert!(walked.values().all(|&count| count <= 2), "seed {seed}");
+    }
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use maze_solving::graph::GraphBuilder;
+use maze_solving::pathfinding::{dijkstra, dijkstra_with_turn_penalty};
+
+/// Two routes of 12 steps from (1, 1) to (7, 7): right then down around
+/// the corner at (7, 1), or a staircase through the middle
+const STRAIGHT_OR_STAIRS: &[&str] = &[
+    "#.#######", //
+    "#.......#", //
+    "#.#####.#", //
+    "#..####.#", //
+    "##..###.#", //
+    "###..##.#", //
+    "####..#.#", //
+    "#####...#", //
+    "#######.#", //
+];
+
+#[test]
+fn turn_penalty_picks_the_straight_route() {
+    let rows = common::ascii_rows(STRAIGHT_OR_STAIRS);
+    for builder in [
+        GraphBuilder::new(),
+        GraphBuilder::new().skip_reduction(true),
+    ] {
+        let graph = common::graph_with(&rows, &builder);
+        let shortest = dijkstra(&graph).unwrap();
+        let result = dijkstra_with_turn_penalty(&graph, 0.5).unwrap();
+        // The penalties are left out of the cost, both routes are as long
+        assert_eq!(result.cost, 14.0);
+        assert_eq!(graph.path_to_coords(&shortest).len(), 15);
+
+        let coords = graph.path_to_coords(&result.path);
+        assert!(coords.contains(&(7, 1)), "{coords:?}");
+        assert!(!coords.contains(&(4, 5)), "{coords:?}");
+    }
+}
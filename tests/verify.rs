@@ -0,0 +1,59 @@
+mod common;
+
+use maze_solving::pathfinding::{ALGORITHM_NAMES, VerifyError, solve_verified, validate_path};
+
+#[test]
+fn every_solver_passes_verification() {
+    for seed in 0..3 {
+        for rows in [
+            common::maze_rows(6, seed, 0),
+            common::maze_rows(6, seed, 12),
+        ] {
+            let graph = common::graph(&rows);
+            for &(name, algo) in ALGORITHM_NAMES {
+                if let Err(err) = solve_verified(&graph, &algo) {
+                    panic!("{name} on seed {seed}: {err}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn unsolvable_mazes_report_no_path() {
+    let graph = common::graph(&common::ascii_rows(&[
+        "#.###", //
+        "#.#.#", //
+        "###.#", //
+    ]));
+    for &(name, algo) in ALGORITHM_NAMES {
+        assert_eq!(
+            solve_verified(&graph, &algo),
+            Err(VerifyError::NoPath),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn validate_path_rejects_broken_paths() {
+    let graph = common::graph(&common::maze_rows(6, 0, 0));
+    let path = solve_verified(&graph, &ALGORITHM_NAMES[0].1).unwrap().path;
+    assert!(validate_path(&graph, &path).is_ok());
+
+    let reversed: Vec<usize> = path.iter().rev().copied().collect();
+    assert!(matches!(
+        validate_path(&graph, &reversed),
+        Err(VerifyError::WrongEndpoints { .. })
+    ));
+
+    let skipping = [graph.start, graph.end];
+    assert_eq!(
+        validate_path(&graph, &skipping),
+        Err(VerifyError::MissingEdge {
+            from: graph.start,
+            to: graph.end
+        })
+    );
+    assert!(validate_path(&graph, &[]).is_err());
+}
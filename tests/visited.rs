@@ -0,0 +1,29 @@
+mod common;
+
+use maze_solving::pathfinding::{PathfindingAlgorithm, solve_graph, solve_graph_with_visited};
+
+#[test]
+fn bfs_visits_every_vertex_on_its_path() {
+    for seed in 0..4 {
+        let graph = common::graph(&common::maze_rows(10, seed, 10));
+        let (path, visited) =
+            solve_graph_with_visited(&graph, &PathfindingAlgorithm::BreadthFirst).unwrap();
+        let path = path.unwrap();
+        assert_eq!(
+            Some(&path),
+            solve_graph(&graph, &PathfindingAlgorithm::BreadthFirst)
+                .unwrap()
+                .as_ref()
+        );
+        assert_eq!(visited.len(), graph.get_vertices().len());
+        for idx in &path {
+            assert!(visited[*idx], "seed {seed} vertex {idx}");
+        }
+        // Vertices emptied out by reduction are never reached
+        for (idx, vertex) in graph.get_vertices().iter().enumerate() {
+            if vertex.get_neighbors().is_empty() {
+                assert!(!visited[idx], "seed {seed} vertex {idx}");
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use maze_solving::graph::{Coord, GraphBuilder, WeightFn};
+use maze_solving::pathfinding::{
+    ALGORITHM_NAMES, Preset, SolveError, calculate_cost, dijkstra, solve_auto, solve_graph,
+};
+
+/// A short route along the top and a long one around the loop below it
+const TWO_ROUTES: &[&str] = &[
+    "#.#####", //
+    "#......", //
+    "#.###.#", //
+    "#.###.#", //
+    "#.....#", //
+    "#######", //
+];
+
+#[test]
+fn dijkstra_follows_a_custom_weight() {
+    let rows = common::ascii_rows(TWO_ROUTES);
+    let default = common::graph(&rows);
+    let path = dijkstra(&default).unwrap();
+    assert_eq!(calculate_cost(&default, &path), 6.0);
+
+    // Stepping onto the middle of the top route costs 10, so going around is
+    // cheaper
+    let toll = WeightFn::new(|_: &Coord, to: &Coord| {
+        if to.y() == 1 && (2..=4).contains(&to.x()) {
+            10.0
+        } else {
+            1.0
+        }
+    });
+    let graph = common::graph_with(&rows, &GraphBuilder::new().weight(toll));
+    let path = dijkstra(&graph).unwrap();
+    assert_eq!(calculate_cost(&graph, &path), 12.0);
+    let pixels = graph.path_to_coords(&path);
+    assert!(pixels.contains(&(3, 4)) && !pixels.contains(&(3, 1)));
+
+    // A weight of 1 everywhere is the default
+    let flat = WeightFn::new(|_: &Coord, _: &Coord| 1.0);
+    let graph = common::graph_with(&rows, &GraphBuilder::new().weight(flat));
+    assert_eq!(
+        graph.edges().collect::<Vec<_>>(),
+        default.edges().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn non_finite_step_weights_are_rejected() {
+    let rows = common::maze_rows(6, 2, 0);
+    for bad in [f32::NAN, f32::INFINITY] {
+        // Checked up front, whether or not the search would get to the bad edges
+        let weight =
+            WeightFn::new(move |from: &Coord, _: &Coord| if from.x() == 5 { bad } else { 1.0 });
+        let graph = common::graph_with(&rows, &GraphBuilder::new().weight(weight));
+        for &(name, algo) in ALGORITHM_NAMES {
+            assert!(
+                matches!(
+                    solve_graph(&graph, &algo),
+                    Err(SolveError::InvalidWeight { .. })
+                ),
+                "{name} with {bad}"
+            );
+        }
+        assert!(matches!(
+            solve_auto(&graph, Preset::Optimal),
+            Err(SolveError::InvalidWeight { .. })
+        ));
+        assert!(matches!(
+            graph.all_pairs_shortest_paths(),
+            Err(SolveError::InvalidWeight { .. })
+        ));
+    }
+}
+
+#[test]
+fn non_finite_edge_edits_are_rejected() {
+    let mut graph = common::graph(&common::maze_rows(6, 2, 0));
+    let (a, b, weight) = graph.edges().next().unwrap();
+    let far = graph.end;
+
+    for bad in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+        assert!(matches!(
+            graph.set_edge_weight(a, b, bad),
+            Err(SolveError::InvalidWeight { .. })
+        ));
+        assert!(matches!(
+            graph.add_edge(a, far, bad),
+            Err(SolveError::InvalidWeight { .. })
+        ));
+    }
+    assert!(graph.has_edge(a, b));
+    assert!(!graph.has_edge(a, far));
+    assert_eq!(graph.edges().next(), Some((a, b, weight)));
+}